@@ -106,7 +106,7 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
 
         let p1 = &path[0..app.frame_count as usize / 2].to_vec();
         Shape::new()
-            .points(&p1)
+            .points(p1)
             .cubic()
             .no_fill()
             .stroke_color(*WHITE)
@@ -115,7 +115,7 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
 
         let p2 = &path[app.frame_count as usize / 2..app.frame_count as usize].to_vec();
         Shape::new()
-            .points(&p2)
+            .points(p2)
             .no_fill()
             .stroke_color(*WHITE)
             .stroke_weight(2.5)
@@ -147,7 +147,7 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
 
         let p3 = &path[0..(app.frame_count - n2 / 2) as usize].to_vec();
         Shape::new()
-            .points(&p3)
+            .points(p3)
             .cubic()
             .no_fill()
             .stroke_color(*WHITE)
@@ -156,7 +156,7 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
 
         let p4 = &path[(app.frame_count - n2 / 2) as usize..n2 as usize].to_vec();
         Shape::new()
-            .points(&p4)
+            .points(p4)
             .no_fill()
             .stroke_color(*WHITE)
             .stroke_weight(2.5)
@@ -6,11 +6,11 @@
 //!
 //! ## Features Demonstrated
 //! - Seamless looping animation using 3D noise
-//! - Frame saving for GIF creation (saves first 50 frames)
+//! - Writing one full loop as an animated GIF with a capture
 //! - 3D noise sampling with circular time parameter
 //! - Grayscale image generation from noise values
 //! - Integration with `tiny-skia` for rendering
-//! - Mathematical mapping functions for value conversion
+//! - Mapping a grid into the margin-aware content area
 //!
 //! ## Technical Details
 //! The seamless loop is achieved by:
@@ -23,21 +23,22 @@
 //! - Smooth, organic noise patterns that flow continuously
 //! - Each pixel's brightness determined by 3D noise value
 //! - Perfect loop that can be played repeatedly
-//! - 50 frames saved automatically as PNG files
+//! - One full loop of 100 frames saved automatically as a GIF
 //!
 //! ## File Output
-//! The example saves the first 50 frames as PNG files in Downloads/frames/
-//! These can be combined into a GIF using external tools.
+//! The example writes `noise_loop.gif` to the current directory once the
+//! first loop has been drawn. Swap `GifAnimation` for `ApngAnimation` and the
+//! file name for `noise_loop.png` to get a lossless animated PNG instead.
 //!
 //! ## Usage
 //! ```bash
-//! cargo run --example noise_loop
+//! cargo run --example noise_loop --features tiny-skia
 //! ```
 //!
-//! The application will automatically save frames and can be used to create
-//! seamless looping animations.
+//! The application will automatically save the GIF and keep animating.
 
 use artimate::app::{App, AppMode, Config, Error};
+use artimate::capture::{Capture, GifAnimation};
 use noise::{NoiseFn, Value};
 use tiny_skia::*;
 
@@ -45,24 +46,21 @@ const TAU: f32 = std::f32::consts::PI * 2.0;
 
 fn main() -> Result<(), Error> {
     let model = Model::default();
-    let config = Config::with_dims(700, 700);
-    let mut app = App::app(model, config, |_, model| model, draw)
-        .set_title("Noise Loop")
-        .set_frames_to_save(50);
+    let config = Config::with_dims(700, 700).margin(70.0);
+    // Frames 1 to 100 are one loop of the animation
+    let gif = GifAnimation::new("noise_loop.gif", 30.0);
+    let capture = Capture::new(gif).frames(1..model.num_frames + 1);
+    let mut app = App::app(model, config, |_, model| model, draw).set_title("Noise Loop");
+    app.add_capture(capture);
     app.run()
 }
 
-fn map_range(x: f32, in_min: f32, in_max: f32, out_min: f32, out_max: f32) -> f32 {
-    (x - in_min) / (in_max - in_min) * (out_max - out_min) + out_min
-}
-
 #[derive(Clone)]
 struct Model {
     scale: f32,
     factor: f32,
     m: u32,
     num_frames: u32,
-    margin: f32,
     noise: Value,
 }
 
@@ -73,7 +71,6 @@ impl Default for Model {
             factor: 0.01,
             m: 500,
             num_frames: 100,
-            margin: 70.0,
             noise: Value::default(),
         }
     }
@@ -93,8 +90,8 @@ fn periodic_noise(model: &Model, p: f32, seed: f32, x: f32, y: f32) -> f32 {
 
 // Offset for the the first 2 parameters of the 4d noise function.
 fn offset(app: &App<AppMode, Model>, model: &Model, x: f32, y: f32) -> f32 {
-    let (w, h) = app.wh_f32();
-    let dist2 = (x - w / 2.0) * (x - w / 2.0) + (y - h / 2.0) * (y - h / 2.0);
+    let (cx, cy) = app.center();
+    let dist2 = (x - cx) * (x - cx) + (y - cy) * (y - cy);
     model.factor * dist2.sqrt()
 }
 
@@ -108,33 +105,23 @@ pub fn point(pixmap: &mut Pixmap, x: f32, y: f32, color: Color) {
 
 // Draw a single frame.
 fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
-    let mut pixmap = Pixmap::new(app.config.width, app.config.height).unwrap();
-    let t = (app.frame_count - 1) as f32 / model.num_frames as f32;
-    for i in 0..model.m {
-        for j in 0..model.m {
-            let x = map_range(
-                i as f32,
-                0.0,
-                model.m as f32 - 1.0,
-                model.margin,
-                app.config.width as f32 - model.margin,
-            );
-            let y = map_range(
-                j as f32,
-                0.0,
-                model.m as f32 - 1.0,
-                model.margin,
-                app.config.height as f32 - model.margin,
-            );
-            let dx = 40.0 * periodic_noise(model, t - offset(app, model, x, y), 0.0, x, y);
-            let dy = 40.0 * periodic_noise(model, t - offset(app, model, x, y), 123.0, x, y);
-            point(
-                &mut pixmap,
-                x + dx,
-                y + dy,
-                Color::from_rgba8(255, 255, 255, 153),
-            );
+    app.draw_pixmap(|pixmap| {
+        let t = (app.frame_count - 1) as f32 / model.num_frames as f32;
+        for i in 0..model.m {
+            for j in 0..model.m {
+                let (x, y) = app.content_point(
+                    i as f32 / (model.m as f32 - 1.0),
+                    j as f32 / (model.m as f32 - 1.0),
+                );
+                let dx = 40.0 * periodic_noise(model, t - offset(app, model, x, y), 0.0, x, y);
+                let dy = 40.0 * periodic_noise(model, t - offset(app, model, x, y), 123.0, x, y);
+                point(
+                    pixmap,
+                    x + dx,
+                    y + dy,
+                    Color::from_rgba8(255, 255, 255, 153),
+                );
+            }
         }
-    }
-    pixmap.take()
+    })
 }
@@ -19,96 +19,96 @@
 //! - Maurer roses add an additional rotation parameter for more complexity
 //!
 //! ## Controls
-//! - **n/d**: Adjust n parameter and degrees
-//! - **c/f**: Change color and fourier seeds
-//! - **a/m/r/w**: Adjust scale, density, rotation, stroke weight
+//! - **n/d**: Control n parameter and degrees
+//! - **c/f**: Control color and fourier seeds
+//! - **a/m/r/w**: Control scale, density, rotation, stroke weight
+//! - **right/left**: Increase or decrease the controlled parameter
+//!   (hold Shift for coarse steps, Alt for fine steps)
 //! - **i/h**: Toggle irrational and maurer modes
-//! - **s**: Cycle through drawing styles
-//! - **space**: Switch control modes
-//! - **enter**: Regenerate with new random parameters
-//! - **p**: Print current parameters
+//! - **1/2/3**: Switch drawing styles
+//! - **?**: Show all hotkeys
 //!
 //! ## Usage
 //! ```bash
 //! cargo run --example rose
+//! cargo run --example rose -- --keys   # print the controls as a Markdown table
 //! ```
 
 use artimate::app::{App, AppMode, Config, Error};
+use artimate::input::Step;
 use num_complex::Complex;
 use std::ops::{Add, Mul};
 use wassily::prelude::*;
-use winit::keyboard::Key;
+use winit::keyboard::{Key, NamedKey};
 
 const LINES: u32 = 3600;
 const COLOR_SEED: u64 = 95;
 const FOURIER_SEED: u64 = 0;
 
-fn message(model: &Model) {
-    println!();
-    println!(" Control Mode  :  {:?}", model.control);
-    println!(" Style         :  {:?}", model.style);
-    println!("┌──────────────┬───┬────────────┐");
-    println!("│ n            │ n │ {:<10} │", format!("{:.2}", model.n));
+/// Prints the current parameter values on one line
+fn status(model: &Model) {
     println!(
-        "│ degrees      │ d │ {:<10} │",
-        format!("{:.1}", model.degrees)
+        "{:?} | n {:.2} | degrees {:.1} | scale {:.2} | rotate {:.2} | density {} | stroke {:.2} \
+         | color seed {} | fourier seed {} | irrational {} | maurer {} | {:?}",
+        model.control,
+        model.n,
+        model.degrees,
+        model.scale,
+        model.rotate,
+        model.density,
+        model.stroke_weight,
+        model.color_seed,
+        model.series_seed,
+        model.irrational,
+        model.maurer,
+        model.style
     );
-    println!("├──────────────┼───┼────────────┤");
-    println!("│ color seed   │ c │ {:<10} │", model.color_seed);
-    println!("│ fourier seed │ f │ {:<10} │", model.series_seed);
-    println!("├──────────────┼───┼────────────┤");
-    println!(
-        "│ scale        │ a │ {:<10} │",
-        format!("{:.2}", model.scale)
-    );
-    println!("│ density      │ m │ {:<10} │", model.density);
-    println!(
-        "│ rotate       │ r │ {:<10} │",
-        format!("{:.2}", model.rotate)
-    );
-    println!(
-        "│ stroke weight│ w │ {:<10} │",
-        format!("{:.2}", model.stroke_weight)
-    );
-    println!("├──────────────┼───┼────────────┤");
-    println!("│ irrational   │ i │ {:<10} │", model.irrational);
-    println!("│ maurer       │ h │ {:<10} │", model.maurer);
-    println!("└──────────────┴───┴────────────┘");
 }
 
-fn control<Mode>(
-    app: &mut App<Mode, Model>,
-    control: Control,
-    n: f32,
-    d: f32,
-    s: f32,
-    r: f32,
-    m: i32,
-    c: i64,
-    f: i64,
-    w: f32,
-) {
-    match control {
-        Control::N => app.model.n += n,
-        Control::Degrees => app.model.degrees += d,
-        Control::Scale => app.model.scale += s,
-        Control::Rotate => app.model.rotate += r,
+/// Binds `key` to selecting `control` as the parameter adjusted with the arrow keys
+fn control_key<Mode>(app: &mut App<Mode, Model>, key: &str, control: Control, description: &str) {
+    app.on_key_press(Key::Character(key.into()), move |app| {
+        app.model.control = control;
+        status(&app.model);
+    });
+    app.describe_key(Key::Character(key.into()), description);
+}
+
+/// Binds `key` to switching the drawing style
+fn style_key<Mode>(app: &mut App<Mode, Model>, key: &str, style: Style, description: &str) {
+    app.on_key_press(Key::Character(key.into()), move |app| {
+        app.model.style = style;
+        status(&app.model);
+    });
+    app.describe_key(Key::Character(key.into()), description);
+}
+
+/// Adjusts the parameter selected by the control mode, `direction` is 1 or -1
+fn adjust<Mode>(app: &mut App<Mode, Model>, direction: i32, step: Step) {
+    let sign = direction as f32;
+    match app.model.control {
+        Control::N => app.model.n += sign * step.pick(0.25, 0.5, 1.0),
+        Control::Degrees => app.model.degrees += sign * step.pick(0.1, 1.0, 10.0),
+        Control::Scale => app.model.scale += sign * step.pick(0.05, 0.1, 0.5),
+        Control::Rotate => app.model.rotate -= sign * step.pick(0.5, 1.0, 5.0),
         Control::Density => {
-            if app.model.density as i32 + m > 0 {
-                app.model.density = (app.model.density as i32 + m) as u32;
+            if app.model.density as i32 + direction > 0 {
+                app.model.density = (app.model.density as i32 + direction) as u32;
             }
         }
-        Control::StrokeWeight => app.model.stroke_weight += w,
+        Control::StrokeWeight => app.model.stroke_weight += sign * step.pick(0.05, 0.1, 0.25),
         Control::Color => {
-            app.model.color_seed = (app.model.color_seed as i64 + c) as u64;
+            let delta = direction as i64 * step.pick(1, 1, 10);
+            app.model.color_seed = (app.model.color_seed as i64 + delta) as u64;
             app.model.update_grad(app.model.color_seed);
         }
         Control::Fourier => {
-            app.model.series_seed = (app.model.series_seed as i64 + f) as u64;
+            let delta = direction as i64 * step.pick(1, 1, 10);
+            app.model.series_seed = (app.model.series_seed as i64 + delta) as u64;
             app.model.random_series();
         }
     };
-    message(&app.model);
+    status(&app.model);
 }
 
 fn main() -> Result<(), Error> {
@@ -119,148 +119,43 @@ fn main() -> Result<(), Error> {
         .set_title("Maurer Rose")
         .no_loop();
 
-    app.on_key_press(Key::Character("n".into()), |app| {
-        app.model.control = Control::N;
-        println!("Control Mode: n");
-    });
-    app.on_key_press(Key::Character("d".into()), |app| {
-        app.model.control = Control::Degrees;
-        println!("Control Mode: Degrees");
-    });
-    app.on_key_press(Key::Character("r".into()), |app| {
-        app.model.control = Control::Rotate;
-        println!("Control Mode: Rotate");
-    });
-    app.on_key_press(Key::Character("a".into()), |app| {
-        app.model.control = Control::Scale;
-        println!("Control Mode: Scale");
-    });
-    app.on_key_press(Key::Character("w".into()), |app| {
-        app.model.control = Control::StrokeWeight;
-        println!("Control Mode: Stroke Weight");
-    });
-    app.on_key_press(Key::Character("c".into()), |app| {
-        app.model.control = Control::Color;
-        println!("Control Mode: Color");
-    });
-    app.on_key_press(Key::Character("f".into()), |app| {
-        app.model.control = Control::Fourier;
-        println!("Control Mode: Fourier");
-    });
-    app.on_key_press(Key::Character("m".into()), |app| {
-        app.model.control = Control::Density;
-        println!("Control Mode: Density");
-    });
-    app.on_key_press(Key::Character("1".into()), |app| {
-        app.model.style = Style::Line;
-    });
-    app.on_key_press(Key::Character("2".into()), |app| {
-        app.model.style = Style::Bezier2;
-    });
-    app.on_key_press(Key::Character("3".into()), |app| {
-        app.model.style = Style::Bezier3;
-    });
-    app.on_key_press(Key::Named(winit::keyboard::NamedKey::ArrowRight), |app| {
-        control(
-            app,
-            app.model.control,
-            0.5,  // n
-            1.0,  // degrees
-            0.1,  // scale
-            -1.0, // rotate
-            1,    // density
-            1,    // color
-            1,    // fourier
-            0.1,  // stroke weight
-        );
-        message(&app.model);
-    });
-    app.on_key_press(Key::Named(winit::keyboard::NamedKey::ArrowLeft), |app| {
-        control(
-            app,
-            app.model.control,
-            -0.5, // n
-            -1.0, // degrees
-            -0.1, // scale
-            1.0,  // rotate
-            -1,   // density
-            -1,   // color
-            -1,   // fourier
-            -0.1, // stroke weight
-        );
-        message(&app.model);
-    });
-
-    app.on_key_press(Key::Character("=".into()), |app| {
-        control(
-            app,
-            app.model.control,
-            0.25, // n
-            0.1,  // degrees
-            0.05, // scale
-            -0.5, // rotate
-            1,    // density
-            1,    // color
-            1,    // fourier
-            0.05, // stroke weight
-        );
-        message(&app.model);
-    });
-    app.on_key_press(Key::Character("-".into()), |app| {
-        control(
-            app,
-            app.model.control,
-            -0.25, // n
-            -0.1,  // degrees
-            0.05,  // scale
-            0.5,   // rotate
-            -1,    // density
-            -1,    // color
-            -1,    // fourier
-            -0.05, // stroke weight
-        );
-        message(&app.model);
-    });
-
-    app.on_key_press(Key::Named(winit::keyboard::NamedKey::ArrowUp), |app| {
-        control(
-            app,
-            app.model.control,
-            1.0,  // n
-            10.0, // degrees
-            0.5,  // scale
-            -5.0, // rotate
-            1,    // density
-            10,   // color
-            10,   // fourier
-            0.25, // stroke weight
-        );
-    });
-    app.on_key_press(Key::Named(winit::keyboard::NamedKey::ArrowDown), |app| {
-        control(
-            app,
-            app.model.control,
-            -1.0,  // n
-            -10.0, // degrees
-            -0.5,  // scale
-            5.0,   // rotate
-            -1,    // density
-            -10,   // color
-            -10,   // fourier
-            -0.25, // stroke weight
-        );
-        message(&app.model);
-    });
+    control_key(&mut app, "n", Control::N, "Adjust n");
+    control_key(&mut app, "d", Control::Degrees, "Adjust degrees");
+    control_key(&mut app, "r", Control::Rotate, "Adjust rotation");
+    control_key(&mut app, "a", Control::Scale, "Adjust scale");
+    control_key(&mut app, "w", Control::StrokeWeight, "Adjust stroke weight");
+    control_key(&mut app, "c", Control::Color, "Adjust color seed");
+    control_key(&mut app, "f", Control::Fourier, "Adjust fourier seed");
+    control_key(&mut app, "m", Control::Density, "Adjust density");
+    style_key(&mut app, "1", Style::Line, "Draw lines");
+    style_key(&mut app, "2", Style::Bezier2, "Draw quadratic curves");
+    style_key(&mut app, "3", Style::Bezier3, "Draw cubic curves");
+    app.register_action("increase", Key::Named(NamedKey::ArrowRight));
+    app.register_action("decrease", Key::Named(NamedKey::ArrowLeft));
+    app.on_action_step("increase", |app, step| adjust(app, 1, step));
+    app.on_action_step("decrease", |app, step| adjust(app, -1, step));
+    let fine_coarse = "(Shift coarse, Alt fine)";
+    app.describe_key(
+        Key::Named(NamedKey::ArrowRight),
+        &format!("Increase the selected parameter {}", fine_coarse),
+    );
+    app.describe_key(
+        Key::Named(NamedKey::ArrowLeft),
+        &format!("Decrease the selected parameter {}", fine_coarse),
+    );
 
     app.on_key_press(Key::Character("h".into()), |app| {
         app.model.maurer = !app.model.maurer;
-        message(&app.model);
+        status(&app.model);
     });
+    app.describe_key(Key::Character("h".into()), "Toggle maurer rose");
     app.on_key_press(Key::Character("i".into()), |app| {
         app.model.irrational = !app.model.irrational;
-        message(&app.model);
+        status(&app.model);
     });
-    message(&app.model);
+    app.describe_key(Key::Character("i".into()), "Toggle irrational n");
+    app.print_help();
+    status(&app.model);
     app.run()
 }
 
@@ -422,8 +317,8 @@ impl FourierSeries {
     }
 
     fn random(rng: &mut SmallRng, n: usize) -> Self {
-        let an: Vec<f32> = (0..n).map(|_| rng.gen_range(-1.0..=1.0)).collect();
-        let bn: Vec<f32> = (0..n - 1).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+        let an: Vec<f32> = (0..n).map(|_| rng.random_range(-1.0..=1.0)).collect();
+        let bn: Vec<f32> = (0..n - 1).map(|_| rng.random_range(-1.0..=1.0)).collect();
         Self::new(&an, &bn)
     }
 
@@ -506,21 +401,22 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
     let mut canvas = Canvas::new(app.config.width, app.config.height);
     canvas.fill(*BLACK);
 
-    let mut vertices = vec![];
+    // Temporary vertices come from the frame arena, so redrawing does not allocate
+    let vertices = app.alloc_slice((LINES * model.density) as usize);
     let size = app.w_f32() / 2.2;
     let degrees = if model.maurer { model.degrees } else { 1.0 };
 
-    for theta in 0..LINES * model.density {
+    for (theta, vertex) in vertices.iter_mut().enumerate() {
         let k = theta as f32
             * std::f32::consts::PI
             * (degrees + if model.irrational { 0.01 } else { 0.0 })
             / 180.0;
         let r = size * model.series.eval(model.scale, model.n * k);
-        vertices.push(pt(r * k.cos(), r * k.sin()));
+        *vertex = pt(r * k.cos(), r * k.sin());
     }
 
     let trans = Transform::from_rotate_at(model.rotate, 0.0, 0.0);
-    trans.map_points(&mut vertices);
+    trans.map_points(vertices);
     match model.style {
         Style::Line => {
             for v in vertices.windows(2) {
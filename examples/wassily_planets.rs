@@ -186,10 +186,10 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
     let mut rng = SmallRng::seed_from_u64(0);
     let mut star_color = *WHITE;
     for _ in 0..model.num_stars {
-        let x = rng.gen_range(0.0..w_f32);
-        let y = rng.gen_range(0.0..h_f32);
-        let r = rng.gen_range(0.5..2.0);
-        star_color.set_alpha(0.4 + (0.5 + half_time).sin() * rng.gen_range(0.0..0.6));
+        let x = rng.random_range(0.0..w_f32);
+        let y = rng.random_range(0.0..h_f32);
+        let r = rng.random_range(0.5..2.0);
+        star_color.set_alpha(0.4 + (0.5 + half_time).sin() * rng.random_range(0.0..0.6));
         Shape::new()
             .star(pt(x, y), r, 3.0 * r, 5)
             .fill_color(star_color)
@@ -202,11 +202,11 @@ fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
         center.x + model.offset * w_f32 * half_time.cos(),
         center.y + model.offset * h_f32 * half_time.sin(),
     );
-    draw_planet(app, &model, pos_1, model.stops_1.clone(), &mut canvas);
+    draw_planet(app, model, pos_1, model.stops_1.clone(), &mut canvas);
 
     // Position of the second planet, opposite position of the first planet.
     let pos_2 = pt(w_f32 - pos_1.x, h_f32 - pos_1.y);
-    draw_planet(app, &model, pos_2, model.stops_2.clone(), &mut canvas);
+    draw_planet(app, model, pos_2, model.stops_2.clone(), &mut canvas);
 
     // return the canvas data as a Vec<u8>
     canvas.take()
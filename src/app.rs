@@ -1,26 +1,60 @@
+use crate::arena::Arena;
+use crate::buffer::{
+    blend_rect, check_frame, clear, copy_rect, unpremultiply, AlphaMode, FrameIssue, Image,
+};
+#[cfg(feature = "video")]
+use crate::capture::FfmpegVideo;
+use crate::capture::{Capture, CaptureWorker, CapturedFrame, GifAnimation};
+use crate::color::{srgb_to_linear, ColorProfile};
+use crate::geom::Rect;
+use crate::guides::{draw_guides, Guide};
+use crate::input::{load_bindings, Input, Step};
+use crate::overlay::{
+    bindings_markdown, cheat_sheet, chord_name, draw_help, draw_insets, draw_recording,
+    draw_scrub_bar, key_name, mouse_button_name, Binding, OverlayTheme, Trigger, SCRUB_BAR_HEIGHT,
+};
+use crate::pacing::{PacingReport, PacingStats};
+use crate::package::{write_archive, Format};
+use crate::power::PowerMode;
+use crate::preset::Preset;
+use crate::random::Rng;
+use crate::scaling::{surface_to_buffer, Filter, SurfaceRenderer};
+use crate::session::{Event as SessionEvent, Session, Settings as SessionSettings, Tick};
+#[cfg(feature = "watch")]
+use crate::watch::Watcher;
 use delegate::delegate;
 use dirs;
 pub use pixels::Error;
-use pixels::{Pixels, SurfaceTexture};
-use png::Encoder;
-use std::collections::{HashMap, HashSet};
+use pixels::{wgpu, Pixels, PixelsBuilder, SurfaceTexture};
+use png::{Encoder, Info};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
+use std::mem::Discriminant;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::sync::Arc;
-use std::sync::mpsc;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use winit::{
     application::ApplicationHandler,
-    dpi::LogicalSize,
-    event::{Modifiers, MouseButton, WindowEvent},
+    dpi::{LogicalSize, PhysicalSize},
+    event::{Ime, Modifiers, MouseButton, StartCause, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
-    keyboard::{Key, ModifiersKeyState},
-    window::{CursorIcon, Window, WindowId},
+    keyboard::{Key, ModifiersKeyState, ModifiersState, NamedKey},
+    window::{CursorIcon, Window, WindowId, WindowLevel},
 };
 
 const DEFAULT_WIDTH: u32 = 1080;
 const DEFAULT_HEIGHT: u32 = 700;
 const DEFAULT_TITLE: &str = "Artimate";
+const DEFAULT_FRAME_PATTERN: &str = "frame_{}";
+
+/// Normalized coordinates of the window center, for use with `App::denorm`
+pub const CENTER: (f32, f32) = (0.5, 0.5);
+/// Normalized coordinates of the top-left corner of the window
+pub const TOP_LEFT: (f32, f32) = (0.0, 0.0);
+/// Normalized coordinates of the bottom-right corner of the window
+pub const BOTTOM_RIGHT: (f32, f32) = (1.0, 1.0);
 
 /// Configuration for the application window and rendering behavior
 #[derive(Debug)]
@@ -37,8 +71,101 @@ pub struct Config {
     pub cursor_visible: bool,
     /// Number of frames to save as PNG files
     pub frames_to_save: u32,
+    /// Number of frames queued for saving at most, and what happens to frames beyond it
+    pub save_queue: (usize, SavePolicy),
+    /// Directory saved frames and takes are written to, if None, `Downloads/frames`
+    pub frame_dir: Option<PathBuf>,
+    /// File name of saved frames without extension, `{}` is replaced by the frame number
+    pub frame_pattern: String,
+    /// Number of the first frame saved to a session or take directory
+    pub frame_start: u32,
+    /// Minimum number of digits in saved frame numbers, padded with zeros
+    pub frame_padding: usize,
     /// Title of the application window
     pub window_title: String,
+    /// If true, the window can be resized freely and the pixel buffer follows its size
+    pub resizable: bool,
+    /// If true, the window is see-through wherever the drawn frame is transparent
+    pub transparent: bool,
+    /// If true, the window floats above other windows
+    pub always_on_top: bool,
+    /// Smallest size the window can be resized to, defaults to the configured size unless resizable
+    pub min_size: Option<(u32, u32)>,
+    /// Largest size the window can be resized to, if any
+    pub max_size: Option<(u32, u32)>,
+    /// Size of each buffer pixel in window pixels, for chunky pixel-art scaling
+    pub pixel_size: u32,
+    /// How the buffer is sampled when it is scaled to the window
+    pub scaling_filter: Filter,
+    /// RGBA color of the window around the scaled buffer, black or transparent if None
+    pub surface_clear_color: Option<[u8; 4]>,
+    /// Optional WGSL fragment shader applied to the frame on screen
+    pub post_shader: Option<String>,
+    /// Optional video file and frame rate every drawn frame is encoded to with ffmpeg
+    #[cfg(feature = "video")]
+    pub record_video: Option<(PathBuf, f32)>,
+    /// Optional dimensions of a separate export buffer, if None, frames are exported at window size
+    pub export_size: Option<(u32, u32)>,
+    /// Factor saved frames are rendered larger by and scaled down from, for antialiasing
+    pub supersample: u32,
+    /// Optional target frames per second redraws are paced to, also the frame budget for `frame_skip`
+    pub fps: Option<f32>,
+    /// Policy applied when a frame takes longer than the frame budget
+    pub frame_skip: FrameSkip,
+    /// If set, `app.time` advances exactly `1 / fps` seconds per frame instead of following the wall clock
+    pub deterministic: Option<f32>,
+    /// Number of frames to run before starting time, statistics and frame capture
+    pub warmup_frames: u32,
+    /// RGBA color used to clear frames returned by `App::new_frame`
+    pub clear_color: [u8; 4],
+    /// If true, `App::new_frame` returns a cleared buffer, otherwise a copy of the previous frame
+    pub auto_clear: bool,
+    /// Margin in pixels between the window edges and the content area
+    pub margin: f32,
+    /// Color profile embedded into saved PNG files
+    pub color_profile: ColorProfile,
+    /// Image format saved frames are written in
+    pub export_format: ExportFormat,
+    /// If true, saved frames keep their alpha channel and the window shows a checkerboard behind them
+    pub transparent_export: bool,
+    /// If true, saved frames are made fully opaque to match what the window shows
+    pub opaque_export: bool,
+    /// Whether draw functions return straight or premultiplied alpha
+    pub alpha_mode: AlphaMode,
+    /// Which stage of the frame is written by screenshots and frame saving
+    pub capture_stage: CaptureStage,
+    /// Composition guides drawn over the preview, toggled with Cmd+G
+    pub guides: Vec<Guide>,
+    /// If true, luminance and coverage statistics are computed for every drawn frame
+    pub track_frame_stats: bool,
+    /// If true, debug builds check drawn frames for common mistakes such as invisible output
+    pub check_frames: bool,
+    /// If true, common failures are followed by an explanation and a suggested fix
+    pub verbose_diagnostics: bool,
+    /// If set, every saved frame also gets a thumbnail with this maximum edge length in pixels
+    pub thumbnail_size: Option<u32>,
+    /// If set, saved frames, metadata and a source snapshot are archived in this format at exit
+    pub package_format: Option<Format>,
+    /// How the frame rate responds to running on battery
+    pub power_mode: PowerMode,
+    /// Colors and text size of guides and the hotkey help overlay
+    pub overlay_theme: OverlayTheme,
+    /// Key that starts and stops recording numbered takes together with Cmd, if any
+    pub record_key: Option<char>,
+    /// Key and exact modifiers that save a screenshot, if any
+    pub screenshot_key: Option<(Key, ModifiersState)>,
+    /// Directory screenshots and preset exports are saved to, if None, `Downloads/artmate`
+    pub screenshot_dir: Option<PathBuf>,
+    /// Rectangle `(x0, y0, x1, y1)` of the saved frame that screenshots are cropped to, if any
+    pub screenshot_region: Option<(u32, u32, u32, u32)>,
+    /// File format takes are recorded in
+    pub take_format: TakeFormat,
+    /// Longest time between two clicks of a mouse button that counts as a double click
+    pub double_click_interval: Duration,
+    /// Number of recent frames kept for scrubbing while paused and their maximum edge length
+    pub frame_history: Option<(usize, u32)>,
+    /// Root seed of the random streams returned by `App::fork_rng`
+    pub seed: u64,
 }
 
 impl Config {
@@ -65,7 +192,54 @@ impl Config {
             frames: None,
             cursor_visible,
             frames_to_save,
+            save_queue: (DEFAULT_SAVE_QUEUE, SavePolicy::Block),
+            frame_dir: None,
+            frame_pattern: DEFAULT_FRAME_PATTERN.to_string(),
+            frame_start: 1,
+            frame_padding: 6,
             window_title: DEFAULT_TITLE.to_string(),
+            resizable: false,
+            transparent: false,
+            always_on_top: false,
+            min_size: None,
+            max_size: None,
+            pixel_size: 1,
+            scaling_filter: Filter::Nearest,
+            surface_clear_color: None,
+            post_shader: None,
+            #[cfg(feature = "video")]
+            record_video: None,
+            export_size: None,
+            supersample: 1,
+            fps: None,
+            frame_skip: FrameSkip::None,
+            deterministic: None,
+            warmup_frames: 0,
+            clear_color: [0, 0, 0, 255],
+            auto_clear: true,
+            margin: 0.0,
+            color_profile: ColorProfile::Srgb,
+            export_format: ExportFormat::Png,
+            transparent_export: false,
+            opaque_export: false,
+            alpha_mode: AlphaMode::Straight,
+            capture_stage: CaptureStage::Post,
+            guides: Vec::new(),
+            track_frame_stats: false,
+            check_frames: true,
+            verbose_diagnostics: false,
+            thumbnail_size: None,
+            package_format: None,
+            power_mode: PowerMode::Normal,
+            overlay_theme: OverlayTheme::Default,
+            record_key: Some('r'),
+            screenshot_key: Some((Key::Character("s".into()), ModifiersState::SUPER)),
+            screenshot_dir: None,
+            screenshot_region: None,
+            take_format: TakeFormat::Png,
+            double_click_interval: Duration::from_millis(400),
+            frame_history: None,
+            seed: 0,
         }
     }
 
@@ -95,6 +269,26 @@ impl Config {
         self.height as f32
     }
 
+    /// Returns the aspect ratio, width divided by height
+    pub fn aspect(&self) -> f32 {
+        self.w_f32() / self.h_f32()
+    }
+
+    /// Returns the center of the window in pixels
+    pub fn center(&self) -> (f32, f32) {
+        (self.w_f32() / 2.0, self.h_f32() / 2.0)
+    }
+
+    /// Converts a pixel position to normalized coordinates in [0, 1]
+    pub fn norm(&self, x: f32, y: f32) -> (f32, f32) {
+        (x / self.w_f32(), y / self.h_f32())
+    }
+
+    /// Converts normalized coordinates in [0, 1] to a pixel position
+    pub fn denorm(&self, u: f32, v: f32) -> (f32, f32) {
+        (u * self.w_f32(), v * self.h_f32())
+    }
+
     /// Sets the number of frames to save and returns updated config
     pub fn set_frames_to_save(self, frames_to_save: u32) -> Self {
         Self {
@@ -103,6 +297,104 @@ impl Config {
         }
     }
 
+    /// Sets how many frames may wait for the frame saving thread and returns updated config
+    ///
+    /// Encoding can be slower than drawing, especially at large sizes, so frames
+    /// queue up while they are written. Once `capacity` frames are waiting,
+    /// `policy` decides whether the app waits for the saving thread or drops a
+    /// frame with a warning. Defaults to 8 frames with `SavePolicy::Block`, which
+    /// keeps every frame and bounds the memory used to 8 frames. Frames are never
+    /// dropped while `App::checkpoint` is enabled, since a checkpoint is only
+    /// written once every earlier frame is saved.
+    pub fn save_queue(self, capacity: usize, policy: SavePolicy) -> Self {
+        Self {
+            save_queue: (capacity.max(1), policy),
+            ..self
+        }
+    }
+
+    /// Sets the directory saved frames are written to and returns updated config
+    ///
+    /// Frames are written directly into the directory instead of a new
+    /// `Downloads/frames/session_{timestamp}_{title}` directory per run, so frames
+    /// of a later run replace those with the same number; takes are recorded into
+    /// numbered directories inside it. The directory is created when the app
+    /// starts, and `App::run` returns an error if that fails.
+    pub fn frame_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            frame_dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    /// Sets the file name pattern of saved frames and returns updated config
+    ///
+    /// `{}` is replaced by the zero padded frame number and the extension of
+    /// `Config::export_format` is appended, `.png` by default, so
+    /// the default `frame_{}` gives `frame_000001.png`, `frame_000002.png` and so on.
+    /// `{:04}` pads the number to 4 digits instead of `Config::frame_padding`. The
+    /// number is appended if the pattern has no placeholder.
+    pub fn frame_pattern(self, pattern: &str) -> Self {
+        Self {
+            frame_pattern: pattern.to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the number of the first saved frame and the digits it is padded to and returns updated config
+    ///
+    /// Defaults to starting at 1 with 6 digits. Numbers with more digits than
+    /// `padding` are written in full rather than wrapping.
+    pub fn frame_numbering(self, start: u32, padding: usize) -> Self {
+        Self {
+            frame_start: start,
+            frame_padding: padding,
+            ..self
+        }
+    }
+
+    /// Returns the file name of the saved frame with the given number
+    ///
+    /// # Examples
+    /// ```
+    /// use artimate::app::Config;
+    ///
+    /// let config = Config::with_dims(100, 100);
+    /// assert_eq!(config.frame_filename(1), "frame_000001.png");
+    /// let config = config.frame_pattern("shot_{}_final").frame_numbering(0, 4);
+    /// assert_eq!(config.frame_filename(12), "shot_0012_final.png");
+    /// let config = config.frame_pattern("loop-{:03}");
+    /// assert_eq!(config.frame_filename(7), "loop-007.png");
+    /// ```
+    pub fn frame_filename(&self, number: u32) -> String {
+        let pattern = &self.frame_pattern;
+        // A `{}` or `{:0N}` placeholder, and the padding it asks for
+        let placeholder = pattern.find('{').and_then(|start| {
+            let end = start + pattern[start..].find('}')?;
+            let padding = match &pattern[start + 1..end] {
+                "" => self.frame_padding,
+                spec => spec.strip_prefix(':')?.parse().ok()?,
+            };
+            Some((start, end, padding))
+        });
+        match placeholder {
+            Some((start, end, padding)) => format!(
+                "{}{:0padding$}{}.{}",
+                &pattern[..start],
+                number,
+                &pattern[end + 1..],
+                self.export_format.extension()
+            ),
+            None => format!(
+                "{}{:0padding$}.{}",
+                pattern,
+                number,
+                self.export_format.extension(),
+                padding = self.frame_padding
+            ),
+        }
+    }
+
     /// Sets cursor visibility and returns updated config
     pub fn set_cursor_visibility(self, cursor_visible: bool) -> Self {
         Self {
@@ -134,454 +426,4861 @@ impl Config {
             ..self
         }
     }
-}
 
-impl Default for Config {
-    fn default() -> Self {
-        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT, false, true, 0)
+    /// Sets whether the pixel buffer follows the window size and returns updated config
+    ///
+    /// By default the window cannot be made smaller than the configured size, see
+    /// `set_min_size`, and the buffer is scaled to fill it. When resizable, the window can take any
+    /// size and `width` and `height` are updated to match, so the draw function
+    /// renders at the new size on the next frame.
+    pub fn resizable(self, resizable: bool) -> Self {
+        Self { resizable, ..self }
     }
-}
-
-/// Marker type for simple sketches that only need drawing functionality
-/// 
-/// Used with `App::sketch()` to create applications that don't need persistent state.
-/// Perfect for static graphics, simple animations, or interactive graphics that only
-/// depend on time and mouse position.
-pub struct SketchMode;
-
-/// Marker type for stateful sketches that need both model state and update functionality
-/// 
-/// Used with `App::app()` to create applications that maintain state between frames.
-/// The model is updated each frame via an update function, allowing for complex
-/// animations and interactive applications.
-pub struct AppMode;
-
-/// Main application struct that handles window management and rendering
-///
-/// Artimate provides a simple framework for creating pixel-based graphics applications.
-/// The `App` struct manages the window lifecycle, input handling, and rendering pipeline.
-///
-/// # Type Parameters
-/// * `Mode` - The application mode, either `SketchMode` for simple sketches or `AppMode` for stateful applications
-/// * `M` - The type of the model/state used in the application
-/// 
-/// # Examples
-/// 
-/// ## Simple Sketch
-/// ```rust,no_run
-/// use artimate::app::{App, Config, Error};
-/// 
-/// fn main() -> Result<(), Error> {
-///     let config = Config::with_dims(800, 600);
-///     let mut app = App::sketch(config, draw);
-///     app.run()
-/// }
-/// 
-/// fn draw(app: &App, _model: &()) -> Vec<u8> {
-///     // Return RGBA pixel data
-///     vec![255; (app.config.width * app.config.height * 4) as usize]
-/// }
-/// ```
-/// 
-/// ## Stateful Application
-/// ```rust,no_run
-/// use artimate::app::{App, AppMode, Config, Error};
-/// 
-/// #[derive(Default, Clone)]
-/// struct Model {
-///     counter: i32,
-/// }
-/// 
-/// fn main() -> Result<(), Error> {
-///     let config = Config::with_dims(800, 600);
-///     let model = Model::default();
-///     let mut app = App::app(model, config, update, draw);
-///     app.run()
-/// }
-/// 
-/// fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
-///     model.counter += 1;
-///     model
-/// }
-/// 
-/// fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
-///     // Return RGBA pixel data based on model state
-///     vec![255; (app.config.width * app.config.height * 4) as usize]
-/// }
-/// ```
-pub struct App<Mode = SketchMode, M = ()> {
-    /// The application's model/state
-    pub model: M,
-    /// Configuration settings for the application
-    pub config: Config,
-    /// Function called each frame to update the model
-    pub update: Option<fn(&App<Mode, M>, M) -> M>,
-    /// Function called each frame to generate pixel data
-    pub draw: fn(&App<Mode, M>, &M) -> Vec<u8>,
-    /// Time elapsed since application start in seconds
-    pub time: f32,
-    /// Instant when the application started
-    pub start_time: Instant,
-    /// Number of frames rendered
-    pub frame_count: u32,
-    /// Window handle
-    window: Option<Arc<Window>>,
-    /// Pixels handle
-    pixels: Option<Pixels<'static>>,
-    /// Current mouse position as (x, y) coordinates
-    pub mouse_position: (f32, f32),
-    /// Channel for sending frame data to be saved
-    frame_sender: Option<mpsc::Sender<(Vec<u8>, String, u32, u32)>>,
-    /// Map of key handlers for custom key events
-    key_handlers: HashMap<Key, Rc<dyn Fn(&mut App<Mode, M>)>>,
-    /// Map of mouse button handlers for custom mouse events
-    mouse_handlers: HashMap<MouseButton, Rc<dyn Fn(&mut App<Mode, M>)>>,
-    /// Map of key press handlers for custom key events
-    key_press_handlers: HashMap<Key, Rc<dyn Fn(&mut App<Mode, M>)>>,
-    /// Map of key release handlers for custom key events
-    key_release_handlers: HashMap<Key, Rc<dyn Fn(&mut App<Mode, M>)>>,
-    /// Set of keys currently held down
-    keys_down: HashSet<Key>,
-    /// Modifiers state
-    modifiers: Modifiers,
-    /// Phantom data for mode type
-    _mode: PhantomData<Mode>,
-}
-
-// Helper function for frame saving setup
-fn setup_frame_sender() -> Option<mpsc::Sender<(Vec<u8>, String, u32, u32)>> {
-    let (tx, rx) = mpsc::channel();
 
-    std::thread::spawn(move || {
-        while let Ok((frame_data, filename, width, height)) = rx.recv() {
-            if let Err(err) = save_frame(frame_data, filename, width, height) {
-                eprintln!("Failed to save frame: {}", err);
-            }
+    /// Sets whether the window surface is transparent and returns updated config
+    ///
+    /// The alpha of the drawn frame is passed to the compositor, so sketches can be
+    /// see-through desktop overlays; draw with a transparent clear color such as
+    /// `[0, 0, 0, 0]`. The checkerboard of `transparent_export` is not drawn in a
+    /// transparent window.
+    ///
+    /// The window is created transparent, but `pixels` configures its surface with
+    /// the first alpha mode the graphics backend reports, and cannot be asked for
+    /// another. That is opaque on Metal (macOS), DX12 and OpenGL, and on Vulkan
+    /// drivers that offer opaque composition, so on those the desktop does not show
+    /// through. It only does on Vulkan drivers that list premultiplied or
+    /// postmultiplied alpha first.
+    pub fn transparent(self, transparent: bool) -> Self {
+        Self {
+            transparent,
+            ..self
         }
-    });
+    }
 
-    Some(tx)
-}
+    /// Sets whether the window floats above other windows and returns updated config
+    ///
+    /// Can be changed while running with `App::set_always_on_top`.
+    pub fn always_on_top(self, always_on_top: bool) -> Self {
+        Self {
+            always_on_top,
+            ..self
+        }
+    }
 
-fn save_frame(
-    frame_data: Vec<u8>,
-    filename: String,
-    width: u32,
-    height: u32,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let file = std::fs::File::create(&filename)?;
-    let mut encoder = Encoder::new(file, width, height);
-    encoder.set_color(png::ColorType::Rgba);
-    encoder.set_depth(png::BitDepth::Eight);
+    /// Sets the smallest size the window can be resized to and returns updated config
+    ///
+    /// Without it the window cannot be made smaller than the configured size unless
+    /// it is `resizable`. A minimum below the configured size lets the window shrink;
+    /// the pixel buffer is then cropped to the window with the default
+    /// `Filter::Nearest`, or scaled down to fit with `Filter::Linear`.
+    pub fn set_min_size(self, width: u32, height: u32) -> Self {
+        Self {
+            min_size: Some((width, height)),
+            ..self
+        }
+    }
 
-    let mut writer = encoder.write_header()?;
-    writer.write_image_data(&frame_data)?;
-    Ok(())
-}
+    /// Sets the largest size the window can be resized to and returns updated config
+    pub fn set_max_size(self, width: u32, height: u32) -> Self {
+        Self {
+            max_size: Some((width, height)),
+            ..self
+        }
+    }
 
-/// Simple sketches that only need drawing functionality
-impl App<SketchMode> {
-    /// Creates a simple sketch application with just a draw function and configuration
-    /// 
-    /// This is the simplest way to create an Artimate application. It's perfect for
-    /// static graphics, animations that don't need persistent state, or simple
-    /// interactive graphics that only depend on time and mouse position.
+    /// Sets the size of each buffer pixel in window pixels and returns updated config
     ///
-    /// # Arguments
-    /// * `config` - Configuration settings for the window and rendering
-    /// * `draw` - Function called each frame to generate RGBA pixel data
+    /// The configured width and height are divided by `pixel_size` while the window
+    /// keeps its size, so the draw function fills a small buffer that is shown
+    /// scaled up with crisp square pixels, for retro and pixel-art sketches. Set it
+    /// after the dimensions. `config.width`, `config.height` and the mouse position
+    /// are all in buffer pixels.
     ///
-    /// # Examples
-    /// ```rust,no_run
-    /// use artimate::app::{App, Config, Error};
-    /// 
-    /// fn main() -> Result<(), Error> {
-    ///     let config = Config::with_dims(400, 400);
-    ///     let mut app = App::sketch(config, draw);
-    ///     app.run()
-    /// }
-    /// 
-    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
-    ///     // Create a simple animated circle
-    ///     let mut pixels = vec![0u8; (app.config.width * app.config.height * 4) as usize];
-    ///     // Fill with pixel data...
-    ///     pixels
-    /// }
     /// ```
-    pub fn sketch(config: Config, draw: fn(&App<SketchMode, ()>, &()) -> Vec<u8>) -> Self {
-        let maybe_tx = if config.frames_to_save > 0 {
-            setup_frame_sender()
-        } else {
-            None
-        };
+    /// use artimate::app::Config;
+    ///
+    /// let config = Config::with_dims(800, 600).pixel_size(4);
+    /// assert_eq!(config.wh(), (200, 150));
+    /// ```
+    pub fn pixel_size(self, pixel_size: u32) -> Self {
+        let pixel_size = pixel_size.max(1);
+        let (width, height) = self.window_wh();
+        Self {
+            width: (width / pixel_size).max(1),
+            height: (height / pixel_size).max(1),
+            pixel_size,
+            ..self
+        }
+    }
 
+    /// Sets how the buffer is scaled to the window and returns updated config
+    ///
+    /// `Filter::Nearest`, the default, keeps pixels crisp by scaling in whole
+    /// multiples. `Filter::Linear` smooths between pixels and fills the window at
+    /// any scale, keeping the buffer's aspect ratio.
+    pub fn scaling_filter(self, scaling_filter: Filter) -> Self {
         Self {
-            model: (),
-            config,
-            update: None,
-            draw,
-            time: 0.0,
-            frame_count: 0,
-            window: None,
-            pixels: None,
-            start_time: Instant::now(),
-            mouse_position: (0.0, 0.0),
-            frame_sender: maybe_tx,
-            key_handlers: HashMap::new(),
-            mouse_handlers: HashMap::new(),
-            key_press_handlers: HashMap::new(),
-            key_release_handlers: HashMap::new(),
-            keys_down: HashSet::new(),
-            modifiers: Modifiers::default(),
-            _mode: PhantomData,
+            scaling_filter,
+            ..self
         }
     }
-}
 
-/// Stateful sketches that need both model state and update functionality
-impl<M> App<AppMode, M>
-where
-    M: Clone,
-{
-    /// Creates a stateful application with model, update, and draw functions
+    /// Sets the color of the window around the pixel buffer and returns updated config
     ///
-    /// This method creates a full-featured application that can maintain state
-    /// between frames. The model is updated each frame via the update function,
-    /// and the draw function generates pixel data based on the current model state.
+    /// The buffer keeps its aspect ratio when scaled, so a window of another shape
+    /// shows borders around it, black by default. Match them to the sketch's
+    /// background to hide them. Unlike `clear_color`, this never appears in frames.
     ///
-    /// # Arguments
-    /// * `model` - Initial state of the application
-    /// * `config` - Configuration settings for the window and rendering
-    /// * `update` - Function called each frame to update the model based on app state
-    /// * `draw` - Function called each frame to generate RGBA pixel data from the model
+    /// ```
+    /// use artimate::app::Config;
     ///
-    /// # Examples
-    /// ```rust,no_run
-    /// use artimate::app::{App, AppMode, Config, Error};
-    /// 
-    /// #[derive(Clone)]
-    /// struct Model {
-    ///     position: f32,
-    ///     direction: f32,
+    /// let config = Config::with_dims(400, 300)
+    ///     .clear_color([240, 235, 220, 255])
+    ///     .set_clear_color(240, 235, 220, 255);
+    /// assert_eq!(config.surface_clear_color, Some([240, 235, 220, 255]));
+    /// ```
+    pub fn set_clear_color(self, r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            surface_clear_color: Some([r, g, b, a]),
+            ..self
+        }
+    }
+
+    /// Sets a WGSL fragment shader that post-processes the frame on screen and returns
+    /// updated config
+    ///
+    /// The shader runs on the GPU between the pixel buffer and the window, for
+    /// effects such as bloom, color grading or warping that would be slow on the
+    /// CPU. Saved frames are not affected. See the `scaling` module for the
+    /// bindings the shader can use. If it fails to compile, the error is printed
+    /// and frames are shown without it.
+    pub fn post_shader(self, wgsl: impl Into<String>) -> Self {
+        Self {
+            post_shader: Some(wgsl.into()),
+            ..self
+        }
+    }
+
+    /// Returns the minimum window size to apply, if any
+    fn min_window_size(&self) -> Option<LogicalSize<f64>> {
+        match self.min_size {
+            Some((width, height)) => Some(LogicalSize::new(width as f64, height as f64)),
+            None if !self.resizable => Some(self.window_size()),
+            None => None,
+        }
+    }
+
+    /// Returns the window size in window pixels, the buffer size times `pixel_size`
+    fn window_wh(&self) -> (u32, u32) {
+        let pixel_size = self.pixel_size.max(1);
+        (self.width * pixel_size, self.height * pixel_size)
+    }
+
+    /// Returns the logical window size for the buffer size
+    fn window_size(&self) -> LogicalSize<f64> {
+        let (width, height) = self.window_wh();
+        LogicalSize::new(width as f64, height as f64)
+    }
+
+    /// Returns the maximum window size to apply, if any
+    fn max_window_size(&self) -> Option<LogicalSize<f64>> {
+        self.max_size
+            .map(|(width, height)| LogicalSize::new(width as f64, height as f64))
+    }
+
+    /// Sets the dimensions of the export buffer and returns updated config
+    ///
+    /// When set, the draw function is called a second time at this size whenever a
+    /// frame is captured, so the window can show a low-res preview while saved
+    /// frames are rendered at full resolution.
+    pub fn set_export_size(self, width: u32, height: u32) -> Self {
+        Self {
+            export_size: Some((width, height)),
+            ..self
+        }
+    }
+
+    /// Sets the supersampling factor of saved frames and returns updated config
+    ///
+    /// Each saved frame is drawn at `factor` times the export size, or the window
+    /// size if no export size is set, and scaled back down by averaging blocks of
+    /// `factor` x `factor` pixels. Pixel-level sketches get smooth, antialiased
+    /// stills without changing the preview. The draw function sees the enlarged
+    /// size in `app.config`, so it must scale its drawing to the canvas size.
+    /// Drawing takes roughly `factor` squared times as long. Defaults to 1, no
+    /// supersampling. If the enlarged frame would not fit in memory addressable by
+    /// a `u32` byte count, frames are saved without supersampling and a warning is
+    /// printed.
+    pub fn supersample(self, factor: u32) -> Self {
+        Self {
+            supersample: factor.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the target frames per second and returns updated config
+    ///
+    /// Redraws are limited to this rate: the event loop waits between frames
+    /// instead of spinning, so animations run at a consistent speed without keeping
+    /// a CPU core busy. Each frame is scheduled one frame period after the previous
+    /// one was due rather than after it finished, so waking up late does not lower
+    /// the average rate. Without a target rate frames are drawn as fast as possible.
+    pub fn set_fps(self, fps: f32) -> Self {
+        Self {
+            fps: Some(fps),
+            ..self
+        }
+    }
+
+    /// Sets the policy for frames that exceed the frame budget and returns updated config
+    ///
+    /// Has no effect unless a target frame rate is set with `set_fps`.
+    pub fn set_frame_skip(self, frame_skip: FrameSkip) -> Self {
+        Self { frame_skip, ..self }
+    }
+
+    /// Uses a fixed virtual clock of `fps` frames per second and returns updated config
+    ///
+    /// `app.time` becomes `frame_count / fps` regardless of how long frames take to
+    /// render, so exported animations are identical from run to run.
+    pub fn deterministic(self, fps: f32) -> Self {
+        Self {
+            deterministic: Some(fps),
+            ..self
+        }
+    }
+
+    /// Sets the root seed of the sketch's random streams and returns updated config
+    ///
+    /// Every stream returned by `App::fork_rng` and every seed from `App::seed_for`
+    /// is derived from this seed and its label.
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Encodes every drawn frame to a video file and returns updated config
+    ///
+    /// Needs the `video` feature. Frames are piped as raw RGBA to an `ffmpeg`
+    /// process, which must be on the `PATH`. The codec follows the file
+    /// extension: VP9 for `.webm` and H.264 otherwise. The video holds the
+    /// frames of `Config::capture_stage` and is finished when the app exits; for
+    /// a frame range, stride or other codec settings add a `capture::FfmpegVideo`
+    /// capture instead. Frames are only also saved as image files if
+    /// `set_frames_to_save` is set, so leave it at 0 to write just the video.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # #[cfg(feature = "video")] {
+    /// use artimate::app::{App, Config};
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let config = Config::with_dims(800, 800).deterministic(60.0).set_frames(600);
+    /// let mut app = App::sketch(config.record_video("out.mp4", 60.0), draw);
+    /// # }
+    /// ```
+    #[cfg(feature = "video")]
+    pub fn record_video(self, path: impl Into<PathBuf>, fps: f32) -> Self {
+        Self {
+            record_video: Some((path.into(), fps)),
+            ..self
+        }
+    }
+
+    /// Sets the number of warmup frames and returns updated config
+    ///
+    /// Warmup frames are drawn and update the model as usual, but `time` stays at zero
+    /// and they are not counted in `frame_count`, the exit statistics or frame capture.
+    pub fn warmup_frames(self, warmup_frames: u32) -> Self {
+        Self {
+            warmup_frames,
+            ..self
+        }
+    }
+
+    /// Sets the color used to clear new frames and returns updated config
+    pub fn clear_color(self, clear_color: [u8; 4]) -> Self {
+        Self {
+            clear_color,
+            ..self
+        }
+    }
+
+    /// Sets whether new frames start cleared and returns updated config
+    ///
+    /// When disabled, `App::new_frame` starts from the previous frame instead, so
+    /// anything drawn leaves a trail.
+    pub fn auto_clear(self, auto_clear: bool) -> Self {
+        Self { auto_clear, ..self }
+    }
+
+    /// Sets the width and height from a size preset and returns updated config
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::Config;
+    /// use artimate::preset::{Paper, Preset};
+    ///
+    /// let config = Config::default().preset(Preset::Print300dpi(Paper::A4));
+    /// assert_eq!(config.wh(), (2480, 3508));
+    /// ```
+    pub fn preset(self, preset: Preset) -> Self {
+        let (width, height) = preset.dims();
+        Self {
+            width,
+            height,
+            ..self
+        }
+    }
+
+    /// Sets the margin around the content area and returns updated config
+    pub fn margin(self, margin: f32) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// Returns the content area: the window inset by the margin
+    pub fn content_rect(&self) -> Rect {
+        Rect::from_size(self.w_f32(), self.h_f32()).inset(self.margin)
+    }
+
+    /// Sets the color profile embedded into saved frames and returns updated config
+    ///
+    /// With `ColorProfile::DisplayP3` saved frames are converted from sRGB to Display P3.
+    /// The window preview is not affected.
+    pub fn color_profile(self, color_profile: ColorProfile) -> Self {
+        Self {
+            color_profile,
+            ..self
+        }
+    }
+
+    /// Sets the image format saved frames are written in and returns updated config
+    ///
+    /// Defaults to PNG. JPEG and WebP, available with the `image` feature, take a
+    /// fraction of the disk space of PNG on long captures. The extension of saved
+    /// frames follows the format; screenshots and thumbnails stay PNG.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[cfg(feature = "image")] {
+    /// use artimate::app::{Config, ExportFormat};
+    ///
+    /// let config = Config::with_dims(1920, 1080).export_format(ExportFormat::Jpeg(90));
+    /// assert_eq!(config.frame_filename(1), "frame_000001.jpg");
+    /// # }
+    /// ```
+    pub fn export_format(self, export_format: ExportFormat) -> Self {
+        Self {
+            export_format,
+            ..self
+        }
+    }
+
+    /// Sets which stage of the frame is saved and returns updated config
+    ///
+    /// See `CaptureStage`. The default, `CaptureStage::Post`, saves the frame after
+    /// the draw passes but without overlays.
+    pub fn capture_stage(self, capture_stage: CaptureStage) -> Self {
+        Self {
+            capture_stage,
+            ..self
+        }
+    }
+
+    /// Sets whether saved frames keep transparency and returns updated config
+    ///
+    /// With `transparent_export(true)` the alpha returned by the draw function is
+    /// kept in saved PNGs, for overlays and stickers, even if `opaque_export` is
+    /// set, and the window composites each frame over a checkerboard so transparent
+    /// areas are visible.
+    pub fn transparent_export(self, transparent_export: bool) -> Self {
+        Self {
+            transparent_export,
+            ..self
+        }
+    }
+
+    /// Sets whether saved frames are made fully opaque and returns updated config
+    ///
+    /// By default saved frames keep the alpha returned by the draw function, while
+    /// the window shows them without transparency. With `opaque_export(true)` the
+    /// alpha of saved frames is set to 255 so they match the window.
+    pub fn opaque_export(self, opaque_export: bool) -> Self {
+        Self {
+            opaque_export,
+            ..self
+        }
+    }
+
+    /// Declares the alpha mode of frames returned by draw functions and returns updated config
+    ///
+    /// Premultiplied frames, such as the data of a tiny-skia pixmap returned with
+    /// `pixmap.take()`, are converted to straight alpha before the passes run, so
+    /// the window, saved PNGs and captures all receive straight alpha sRGB.
+    /// `App::present_pixmap` and `App::draw_pixmap` already convert, so frames
+    /// from them are straight.
+    pub fn alpha_mode(self, alpha_mode: AlphaMode) -> Self {
+        Self { alpha_mode, ..self }
+    }
+
+    /// Sets the composition guides shown over the preview and returns updated config
+    ///
+    /// Guides start visible and are toggled with Cmd+G. They are never saved or exported.
+    pub fn guides(self, guides: &[Guide]) -> Self {
+        Self {
+            guides: guides.to_vec(),
+            ..self
+        }
+    }
+
+    /// Sets the theme of guides and the hotkey help overlay and returns updated config
+    ///
+    /// `OverlayTheme::HighContrast` uses opaque panels, saturated colors and larger text.
+    pub fn overlay_theme(self, overlay_theme: OverlayTheme) -> Self {
+        Self {
+            overlay_theme,
+            ..self
+        }
+    }
+
+    /// Sets whether statistics are computed for every drawn frame and returns updated config
+    ///
+    /// The statistics of the latest frame are available from `App::frame_stats`.
+    pub fn track_frame_stats(self, track_frame_stats: bool) -> Self {
+        Self {
+            track_frame_stats,
+            ..self
+        }
+    }
+
+    /// Sets whether debug builds check drawn frames for common mistakes and returns updated config
+    ///
+    /// When enabled, each kind of problem found by `buffer::check_frame` (a fully
+    /// transparent frame, blank rows at the bottom, premultiplied alpha) is reported
+    /// once on stderr. Transparent frames are not reported when `transparent` or
+    /// `transparent_export` is set, since they are then intended.
+    /// Release builds never check frames.
+    pub fn check_frames(self, check_frames: bool) -> Self {
+        Self {
+            check_frames,
+            ..self
+        }
+    }
+
+    /// Sets whether common failures are explained and returns updated config
+    ///
+    /// Intended for workshops and first sketches: wrongly sized frames, zero
+    /// dimensions, GPU surface errors and sudden frame time spikes are followed by
+    /// a short explanation and a suggested fix, each printed once per run.
+    pub fn verbose_diagnostics(self, verbose_diagnostics: bool) -> Self {
+        Self {
+            verbose_diagnostics,
+            ..self
+        }
+    }
+
+    /// Sets the key combination that saves a screenshot and returns updated config
+    ///
+    /// Defaults to Cmd+S. The modifiers must match exactly; Shift changes the
+    /// logical key of characters, so pair `"S"` rather than `"s"` with
+    /// `ModifiersState::SHIFT`. Pass None to disable the shortcut.
+    ///
+    /// # Examples
+    /// ```
+    /// use artimate::app::Config;
+    /// use winit::keyboard::{Key, ModifiersState, NamedKey};
+    ///
+    /// let config = Config::with_dims(400, 400)
+    ///     .screenshot_key(Some((Key::Named(NamedKey::F12), ModifiersState::empty())));
+    /// ```
+    pub fn screenshot_key(self, screenshot_key: Option<(Key, ModifiersState)>) -> Self {
+        Self {
+            screenshot_key,
+            ..self
+        }
+    }
+
+    /// Sets the directory screenshots are saved to and returns updated config
+    ///
+    /// Preset exports are saved there too. The directory is created with the
+    /// first screenshot. Defaults to `Downloads/artmate`.
+    pub fn screenshot_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            screenshot_dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    /// Crops screenshots to a rectangle of the saved frame and returns updated config
+    ///
+    /// The rectangle is `(x0, y0, x1, y1)` in pixels of the saved frame, which is
+    /// the export size if one is set, and is clipped to the frame.
+    pub fn screenshot_region(self, x0: u32, y0: u32, x1: u32, y1: u32) -> Self {
+        Self {
+            screenshot_region: Some((x0, y0, x1, y1)),
+            ..self
+        }
+    }
+
+    /// Sets the key that toggles recording takes with Cmd and returns updated config
+    ///
+    /// Defaults to Cmd+R. Pass None to disable the hotkey; `App::toggle_recording`
+    /// still works from handlers.
+    pub fn record_key(self, record_key: Option<char>) -> Self {
+        Self { record_key, ..self }
+    }
+
+    /// Sets the file format takes are recorded in and returns updated config
+    ///
+    /// With `TakeFormat::Gif`, each take is written to `Downloads/frames/take_001.gif`
+    /// and so on when it stops, at the target frame rate or 50 frames per second.
+    pub fn take_format(self, take_format: TakeFormat) -> Self {
+        Self {
+            take_format,
+            ..self
+        }
+    }
+
+    /// Sets the longest time between the clicks of a double click and returns updated config
+    ///
+    /// Defaults to 400 milliseconds. The second click must also land within a few
+    /// pixels of the first to reach `App::on_mouse_double_click` handlers.
+    pub fn double_click_interval(self, double_click_interval: Duration) -> Self {
+        Self {
+            double_click_interval,
+            ..self
+        }
+    }
+
+    /// Sets the maximum edge length of thumbnails written with saved frames and returns updated config
+    ///
+    /// Every saved frame is accompanied by a downscaled copy with the same file name
+    /// in a `thumbnails` directory next to it, which makes large sweeps fast to browse.
+    pub fn thumbnail_size(self, max_edge: u32) -> Self {
+        Self {
+            thumbnail_size: Some(max_edge),
+            ..self
+        }
+    }
+
+    /// Keeps a downscaled history of recent frames for review and returns updated config
+    ///
+    /// While the sketch is paused with Cmd+P, the retained frames can be scrubbed with
+    /// the left and right arrow keys or by dragging along the scrub bar at the bottom
+    /// of the window, without re-running the simulation.
+    ///
+    /// # Arguments
+    /// * `frames` - Number of most recent frames to keep
+    /// * `max_edge` - Maximum edge length in pixels of the retained copies
+    pub fn frame_history(self, frames: usize, max_edge: u32) -> Self {
+        Self {
+            frame_history: Some((frames, max_edge)),
+            ..self
+        }
+    }
+
+    /// Sets the archive format used to package the session at exit and returns updated config
+    ///
+    /// When the application exits, the saved frames and their thumbnails, the timing
+    /// manifest, a `session.txt` metadata file and a snapshot of the sketch's
+    /// `Cargo.toml` and `src` directory are written to a single `session_{timestamp}`
+    /// archive next to the frames. Nothing is written if no frames were saved.
+    pub fn package_session(self, format: Format) -> Self {
+        Self {
+            package_format: Some(format),
+            ..self
+        }
+    }
+
+    /// Sets the power mode and returns updated config
+    ///
+    /// In low power mode the target frame rate is halved (from 60 fps if none is set),
+    /// and frames are paced to it as with `set_fps`. `PowerMode::Auto`
+    /// switches to low power when the machine is on battery at startup.
+    pub fn power_mode(self, power_mode: PowerMode) -> Self {
+        Self { power_mode, ..self }
+    }
+
+    /// Returns the settings applied when writing a frame to disk
+    pub(crate) fn save_options(&self) -> SaveOptions {
+        SaveOptions {
+            color_profile: self.color_profile.clone(),
+            format: self.export_format,
+            thumbnail_size: self.thumbnail_size,
+            #[cfg(feature = "image")]
+            background: self.clear_color,
+        }
+    }
+
+    /// Returns the settings screenshots and preset exports are written with, always as PNG
+    pub(crate) fn screenshot_options(&self) -> SaveOptions {
+        SaveOptions {
+            format: ExportFormat::Png,
+            ..self.save_options()
+        }
+    }
+}
+
+/// What to do when drawing a frame takes longer than the frame budget
+///
+/// The frame budget is `1 / fps` seconds, set with `Config::set_fps`. A tick is one
+/// frame budget of wall-clock time; ticks that elapse while a slow frame is being
+/// drawn are "missed".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameSkip {
+    /// Run one update and one draw per frame no matter how long it takes
+    #[default]
+    None,
+    /// Skip the frame numbers of missed ticks: run one update and one draw, then
+    /// advance `frame_count` past the missed ticks so frame-based animation stays
+    /// in sync with the wall clock
+    ///
+    /// The model is not updated for the skipped frames, and saved frame numbers
+    /// and capture ranges have gaps where frames were skipped.
+    SkipFrames,
+    /// Keep updating once per frame but skip drawing and presenting frames while
+    /// behind, so the simulation catches up without paying for draw
+    SkipPresent,
+    /// Run one extra update for every missed tick (up to `MAX_CATCH_UP_STEPS`)
+    /// before drawing, accumulating simulation steps
+    CatchUp,
+}
+
+/// The stage of a frame captured by screenshots and frame saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureStage {
+    /// The buffer returned by the draw function, before any draw passes
+    Raw,
+    /// The buffer after the enabled draw passes, as exported at `Config::export_size`
+    #[default]
+    Post,
+    /// The composited window contents, including guides, debug views and other
+    /// overlays, always at window size
+    ///
+    /// Without a window, such as in headless renders and preset exports, this
+    /// captures the same frame as `Post`.
+    Final,
+}
+
+/// The image format saved frames are written in
+///
+/// Formats other than PNG need the `image` feature. Only PNG and WebP carry the
+/// `Config::color_profile`; the other formats are always written in sRGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ExportFormat {
+    /// Lossless PNG with an embedded color profile
+    #[default]
+    Png,
+    /// Lossy JPEG at a quality from 1 to 100, flattened over `Config::clear_color`
+    #[cfg(feature = "image")]
+    Jpeg(u8),
+    /// Lossless WebP, usually smaller than PNG
+    #[cfg(feature = "image")]
+    WebP,
+    /// Uncompressed BMP
+    #[cfg(feature = "image")]
+    Bmp,
+    /// Uncompressed TIFF
+    #[cfg(feature = "image")]
+    Tiff,
+}
+
+impl ExportFormat {
+    /// Returns the file extension of the format, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            #[cfg(feature = "image")]
+            ExportFormat::Jpeg(_) => "jpg",
+            #[cfg(feature = "image")]
+            ExportFormat::WebP => "webp",
+            #[cfg(feature = "image")]
+            ExportFormat::Bmp => "bmp",
+            #[cfg(feature = "image")]
+            ExportFormat::Tiff => "tiff",
+        }
+    }
+}
+
+/// The file format takes are recorded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TakeFormat {
+    /// A directory of numbered PNG files, one per frame
+    #[default]
+    Png,
+    /// A single looping GIF, written when the take stops
+    Gif,
+}
+
+/// What happens to a frame to save when the frame saving queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SavePolicy {
+    /// Wait for the saving thread, slowing the app down to the speed of encoding
+    #[default]
+    Block,
+    /// Drop the oldest queued frame to make room, keeping the app responsive
+    DropOldest,
+    /// Drop the new frame, keeping the frames already queued
+    DropNewest,
+}
+
+/// Default number of frames queued for the frame saving thread
+pub const DEFAULT_SAVE_QUEUE: usize = 8;
+
+/// Upper bound on the number of updates run in a single frame by `FrameSkip::CatchUp`
+pub const MAX_CATCH_UP_STEPS: u32 = 8;
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT, false, true, 0)
+    }
+}
+
+/// Function called each frame to update the model
+pub type UpdateFn<Mode, M> = fn(&App<Mode, M>, M) -> M;
+
+/// Function called each frame to generate RGBA pixel data
+pub type DrawFn<Mode, M> = fn(&App<Mode, M>, &M) -> Vec<u8>;
+
+/// Function called each frame to draw into a reused RGBA buffer of the current size
+///
+/// The buffer is cleared to `Config::clear_color` first, or holds the previous
+/// frame when `auto_clear` is disabled.
+pub type DrawInPlaceFn<Mode, M> = fn(&App<Mode, M>, &M, &mut [u8]);
+
+/// Function that applies a seed to a model
+pub type SeedFn<M> = fn(&mut M, u64);
+
+/// Function that returns a value which changes whenever the model needs redrawing
+pub type VersionFn<M> = fn(&M) -> u64;
+
+/// Function that draws one named pass into the frame buffer in place
+pub type PassFn<Mode, M> = fn(&App<Mode, M>, &M, &mut [u8]);
+
+/// Function that renders an auxiliary debug buffer shown as an inset over the preview
+pub type DebugViewFn<Mode, M> = fn(&App<Mode, M>, &M) -> Image;
+
+/// Handler invoked in response to an input event
+type Handler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>)>;
+
+/// Handler invoked with the cursor position when the cursor moves
+type MoveHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, f32, f32)>;
+
+/// Handler invoked with text typed or composed by an input method
+type TextHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, &str)>;
+
+/// Handler invoked with the path of a file dropped onto the window
+type DropHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, &Path)>;
+
+/// Handler invoked while a mouse button is dragged, with the press and current cursor positions
+type DragHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, (f32, f32), (f32, f32))>;
+
+/// Handler invoked when an action is triggered, with the step selected by the held modifiers
+type StepHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, Step)>;
+
+/// Handler invoked with the path of a watched file that changed
+#[cfg(feature = "watch")]
+type FileHandler<Mode, M> = Rc<dyn Fn(&mut App<Mode, M>, &Path)>;
+
+/// Hook invoked with the paths of the files written by a finished export
+type ExportHook = Rc<dyn Fn(&[PathBuf])>;
+
+/// Function that serializes the model for a checkpoint
+pub type SaveStateFn<M> = fn(&M) -> Vec<u8>;
+
+/// Function that restores a model from checkpoint bytes, None if they are invalid
+pub type LoadStateFn<M> = fn(&[u8]) -> Option<M>;
+
+/// Work sent to the frame saving thread
+enum FrameMessage {
+    /// Frame data, filename, width, height and save settings
+    Frame(Vec<u8>, String, u32, u32, SaveOptions),
+    /// Checkpoint contents and path, written once every earlier frame is saved
+    Checkpoint(Vec<u8>, PathBuf),
+}
+
+/// Messages waiting for the frame saving thread and whether the queue is closed
+#[derive(Default)]
+struct SaveState {
+    messages: VecDeque<FrameMessage>,
+    closed: bool,
+}
+
+/// Queue of work for the frame saving thread, holding at most `Config::save_queue` frames
+struct SaveQueue {
+    state: Mutex<SaveState>,
+    /// Signalled when a message is queued or the queue is closed
+    queued: Condvar,
+    /// Signalled when the saving thread takes a message or the queue is closed
+    taken: Condvar,
+    capacity: usize,
+}
+
+impl SaveQueue {
+    /// Queues a message, returning the frame left out to respect the capacity, if any
+    ///
+    /// Checkpoints are always queued, so only frames are ever dropped. Nothing is
+    /// queued once the queue is closed.
+    fn push(&self, message: FrameMessage, policy: SavePolicy) -> Option<FrameMessage> {
+        let mut state = self.state.lock().unwrap();
+        let mut dropped = None;
+        if let FrameMessage::Frame(..) = message {
+            while !state.closed && state.messages.len() >= self.capacity {
+                match policy {
+                    SavePolicy::Block => state = self.taken.wait(state).unwrap(),
+                    SavePolicy::DropNewest => return Some(message),
+                    SavePolicy::DropOldest => {
+                        let oldest = state
+                            .messages
+                            .iter()
+                            .position(|queued| matches!(queued, FrameMessage::Frame(..)));
+                        dropped = oldest.and_then(|i| state.messages.remove(i));
+                        break;
+                    }
+                }
+            }
+        }
+        if state.closed {
+            return Some(message);
+        }
+        state.messages.push_back(message);
+        self.queued.notify_one();
+        dropped
+    }
+
+    /// Takes the next message, waiting for one, or returns None once closed and empty
+    fn pop(&self) -> Option<FrameMessage> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.messages.pop_front() {
+                self.taken.notify_one();
+                return Some(message);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.queued.wait(state).unwrap();
+        }
+    }
+}
+
+/// Handle to the frame saving queue that closes it when dropped
+///
+/// The saving thread holds one too, so a panic while saving never leaves the
+/// app waiting on a full queue.
+struct FrameSender(Arc<SaveQueue>);
+
+impl Drop for FrameSender {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().closed = true;
+        self.0.queued.notify_all();
+        self.0.taken.notify_all();
+    }
+}
+
+/// First line of a checkpoint file
+const CHECKPOINT_HEADER: &str = "artimate checkpoint";
+
+/// Settings applied when writing a frame to disk, copied from the config
+#[derive(Debug, Clone)]
+pub(crate) struct SaveOptions {
+    color_profile: ColorProfile,
+    format: ExportFormat,
+    thumbnail_size: Option<u32>,
+    /// Color transparent pixels are flattened over for formats without alpha
+    #[cfg(feature = "image")]
+    background: [u8; 4],
+}
+
+/// Wall-clock timing of a single exported frame
+///
+/// Timestamps are integer microseconds since the application started, so a
+/// variable frame rate capture can be re-timed exactly when encoding to video.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameTiming {
+    /// Index of the frame
+    pub frame: u32,
+    /// Path of the saved image file
+    pub path: String,
+    /// Time at which the frame started, in microseconds since application start
+    pub timestamp_us: u64,
+    /// Time until the next frame started in microseconds, None until it is known
+    pub duration_us: Option<u64>,
+}
+
+/// Summary statistics of the pixels of a drawn frame
+///
+/// Luminance is the Rec. 709 weighted sum of the color channels, scaled to [0, 1].
+/// Useful for automatically discarding blank or washed out outputs in batch runs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameStats {
+    /// Darkest pixel luminance
+    pub min_luminance: f32,
+    /// Brightest pixel luminance
+    pub max_luminance: f32,
+    /// Average pixel luminance
+    pub mean_luminance: f32,
+    /// Fraction of pixels that differ from the background color
+    pub coverage: f32,
+}
+
+impl FrameStats {
+    /// Computes the statistics of an RGBA frame drawn over `background`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::FrameStats;
+    ///
+    /// let frame = [0, 0, 0, 255, 255, 255, 255, 255];
+    /// let stats = FrameStats::from_frame(&frame, [0, 0, 0, 255]);
+    /// assert_eq!(stats.max_luminance, 1.0);
+    /// assert_eq!(stats.coverage, 0.5);
+    /// ```
+    pub fn from_frame(frame: &[u8], background: [u8; 4]) -> Self {
+        let mut stats = FrameStats {
+            min_luminance: f32::MAX,
+            max_luminance: f32::MIN,
+            ..FrameStats::default()
+        };
+        let mut total = 0.0;
+        let mut covered = 0;
+        let mut count = 0;
+        for px in frame.chunks_exact(4) {
+            let luminance =
+                (0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32) / 255.0;
+            stats.min_luminance = stats.min_luminance.min(luminance);
+            stats.max_luminance = stats.max_luminance.max(luminance);
+            total += luminance as f64;
+            if px != background {
+                covered += 1;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            return FrameStats::default();
+        }
+        stats.mean_luminance = (total / count as f64) as f32;
+        stats.coverage = covered as f32 / count as f32;
+        stats
+    }
+}
+
+/// Timing statistics of a named draw pass
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PassTiming {
+    /// Name the pass was registered with
+    pub name: String,
+    /// Whether the pass currently runs
+    pub enabled: bool,
+    /// Number of times the pass has run
+    pub calls: u32,
+    /// Total time spent in the pass
+    pub total: Duration,
+    /// Time spent in the most recent run of the pass
+    pub last: Duration,
+}
+
+impl PassTiming {
+    /// Returns the average time per run, zero if the pass has not run
+    pub fn mean(&self) -> Duration {
+        self.total.checked_div(self.calls).unwrap_or_default()
+    }
+}
+
+/// A named draw pass and its timing
+struct Pass<Mode, M> {
+    draw: PassFn<Mode, M>,
+    timing: PassTiming,
+}
+
+/// A named debug buffer drawn as a picture-in-picture inset
+struct DebugView<Mode, M> {
+    name: String,
+    draw: DebugViewFn<Mode, M>,
+    visible: bool,
+}
+
+/// Marker type for simple sketches that only need drawing functionality
+/// 
+/// Used with `App::sketch()` to create applications that don't need persistent state.
+/// Perfect for static graphics, simple animations, or interactive graphics that only
+/// depend on time and mouse position.
+pub struct SketchMode;
+
+/// Marker type for stateful sketches that need both model state and update functionality
+/// 
+/// Used with `App::app()` to create applications that maintain state between frames.
+/// The model is updated each frame via an update function, allowing for complex
+/// animations and interactive applications.
+pub struct AppMode;
+
+/// Main application struct that handles window management and rendering
+///
+/// Artimate provides a simple framework for creating pixel-based graphics applications.
+/// The `App` struct manages the window lifecycle, input handling, and rendering pipeline.
+///
+/// # Type Parameters
+/// * `Mode` - The application mode, either `SketchMode` for simple sketches or `AppMode` for stateful applications
+/// * `M` - The type of the model/state used in the application
+/// 
+/// # Examples
+/// 
+/// ## Simple Sketch
+/// ```rust,no_run
+/// use artimate::app::{App, Config, Error};
+/// 
+/// fn main() -> Result<(), Error> {
+///     let config = Config::with_dims(800, 600);
+///     let mut app = App::sketch(config, draw);
+///     app.run()
+/// }
+/// 
+/// fn draw(app: &App, _model: &()) -> Vec<u8> {
+///     // Return RGBA pixel data
+///     vec![255; (app.config.width * app.config.height * 4) as usize]
+/// }
+/// ```
+/// 
+/// ## Stateful Application
+/// ```rust,no_run
+/// use artimate::app::{App, AppMode, Config, Error};
+/// 
+/// #[derive(Default, Clone)]
+/// struct Model {
+///     counter: i32,
+/// }
+/// 
+/// fn main() -> Result<(), Error> {
+///     let config = Config::with_dims(800, 600);
+///     let model = Model::default();
+///     let mut app = App::app(model, config, update, draw);
+///     app.run()
+/// }
+/// 
+/// fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
+///     model.counter += 1;
+///     model
+/// }
+/// 
+/// fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+///     // Return RGBA pixel data based on model state
+///     vec![255; (app.config.width * app.config.height * 4) as usize]
+/// }
+/// ```
+pub struct App<Mode = SketchMode, M = ()> {
+    /// The application's model/state
+    pub model: M,
+    /// Configuration settings for the application
+    pub config: Config,
+    /// Function called each frame to update the model
+    pub update: Option<UpdateFn<Mode, M>>,
+    /// Function called each frame to generate pixel data
+    pub draw: DrawFn<Mode, M>,
+    /// Optional function used instead of `draw` to render the export buffer
+    pub export_draw: Option<DrawFn<Mode, M>>,
+    /// Function drawing into `frame_buffer`, used instead of `draw` if set
+    draw_in_place: Option<DrawInPlaceFn<Mode, M>>,
+    /// Buffer reused by `draw_in_place`, holding the previously drawn frame
+    frame_buffer: Vec<u8>,
+    /// Copy of the last drawn frame, kept when `auto_clear` is disabled
+    previous_frame: Vec<u8>,
+    /// Regions marked with `mark_dirty` during the current draw, empty for the whole frame
+    dirty_rects: std::cell::RefCell<Vec<(u32, u32, u32, u32)>>,
+    /// Whether the window's pixel buffer holds exactly the last presented frame
+    pixels_hold_frame: bool,
+    /// Seeds rendered side by side in seed comparison mode, empty when disabled
+    compare_seeds: Vec<u64>,
+    /// Applies a seed to a copy of the model in seed comparison mode
+    seed_fn: Option<SeedFn<M>>,
+    /// Time elapsed since application start in seconds
+    pub time: f32,
+    /// Instant when the application started
+    pub start_time: Instant,
+    /// Number of frames rendered
+    pub frame_count: u32,
+    /// Instant at which the previous frame started, used to measure the frame budget
+    last_frame: Option<Instant>,
+    /// Instant the next frame is due at when pacing to a target frame rate
+    frame_deadline: Option<Instant>,
+    /// Time since the previous frame of the frames drawn after warmup
+    frame_intervals: PacingStats,
+    /// Wall-clock timing of every frame sent to the frame saving thread
+    frame_timings: Vec<FrameTiming>,
+    /// Number of warmup frames still to run
+    warmup_remaining: u32,
+    /// Session being recorded and the file it is written to at exit
+    session_recording: Option<(Session, PathBuf)>,
+    /// Session being replayed and the index of its next event
+    session_replay: Option<(Session, usize)>,
+    /// Instant from which the exit statistics are measured
+    stats_start: Instant,
+    /// Window handle
+    window: Option<Arc<Window>>,
+    /// Pixels handle
+    pixels: Option<Pixels<'static>>,
+    /// Renderer replacing the one built into `pixels` for linear scaling and post shaders
+    surface_renderer: Option<SurfaceRenderer>,
+    /// Current mouse position as (x, y) coordinates
+    pub mouse_position: (f32, f32),
+    /// Channel for sending frame data to be saved
+    frame_sender: Option<FrameSender>,
+    /// Thread saving the frames received on `frame_sender`
+    frame_saver: Option<JoinHandle<()>>,
+    /// Frame from which `config.frames_to_save` frames are saved
+    save_start: u32,
+    /// Number of frames dropped because the frame saving queue was full
+    dropped_frames: u32,
+    /// Output of the draw function before the passes, kept for `CaptureStage::Raw`
+    raw_frame: Vec<u8>,
+    /// Capture sinks registered with `add_capture`, each on its own thread
+    captures: Vec<CaptureWorker>,
+    /// Number and directory, or GIF path, of the take being recorded
+    take: Option<(u32, PathBuf)>,
+    /// Index in `captures` and first frame of the GIF take being recorded
+    take_capture: Option<(usize, u32)>,
+    /// Directory of the frames saved outside takes, created with the first saved frame
+    session_dir: Option<PathBuf>,
+    /// Hook called with the files written by each finished export
+    export_hook: Option<ExportHook>,
+    /// Whether frames are paced at half the target frame rate to save power
+    low_power: bool,
+    /// Checkpoint interval in frames and the model (de)serialization functions
+    checkpoint: Option<(u32, SaveStateFn<M>, LoadStateFn<M>)>,
+    /// Map of key handlers for custom key events
+    key_handlers: HashMap<Key, Handler<Mode, M>>,
+    /// Map of mouse button handlers for custom mouse events
+    mouse_handlers: HashMap<MouseButton, Handler<Mode, M>>,
+    /// Map of mouse button release handlers
+    mouse_release_handlers: HashMap<MouseButton, Handler<Mode, M>>,
+    /// Map of mouse buttons to their double click handler functions
+    mouse_double_click_handlers: HashMap<MouseButton, Handler<Mode, M>>,
+    /// Button, input clock time and position of the last click that may start a double click
+    last_click: Option<(MouseButton, f32, (f32, f32))>,
+    /// Map of handlers called while a mouse button is dragged
+    mouse_drag_handlers: HashMap<MouseButton, DragHandler<Mode, M>>,
+    /// Handler called whenever the cursor moves
+    mouse_move_handler: Option<MoveHandler<Mode, M>>,
+    /// Handler called with typed text, which also enables input methods
+    text_handler: Option<TextHandler<Mode, M>>,
+    /// Handler called with files dropped onto the window
+    file_drop_handler: Option<DropHandler<Mode, M>>,
+    /// Mouse buttons currently held down and the cursor position each was pressed at
+    buttons_down: HashMap<MouseButton, (f32, f32)>,
+    /// Map of key press handlers for custom key events
+    key_press_handlers: HashMap<Key, Handler<Mode, M>>,
+    /// Map of key press handlers for keys pressed with exactly the given modifiers
+    key_chord_handlers: HashMap<(Key, ModifiersState), Handler<Mode, M>>,
+    /// Map of key release handlers for custom key events
+    key_release_handlers: HashMap<Key, Handler<Mode, M>>,
+    /// Set of keys currently held down
+    keys_down: HashSet<Key>,
+    /// Modifiers state
+    modifiers: Modifiers,
+    /// Whether the configured composition guides are drawn over the preview
+    guides_visible: bool,
+    /// Whether the hotkey help overlay is drawn over the preview
+    help_visible: bool,
+    /// Descriptions of key bindings shown in the help overlay
+    key_descriptions: HashMap<Key, String>,
+    /// Downscaled recent frames with their frame numbers, oldest first
+    history: VecDeque<(u32, Image)>,
+    /// Index into `history` of the frame shown while paused
+    history_cursor: usize,
+    /// When the sketch was paused, None while running
+    paused: Option<Instant>,
+    /// Whether the left mouse button is dragging along the scrub bar
+    scrubbing: bool,
+    /// Inputs assigned to action names by a loaded bindings file
+    remaps: HashMap<String, Input>,
+    /// Registered actions and the inputs they are currently bound to, in registration order
+    actions: Vec<(String, Input)>,
+    /// Map of action handlers, called when the input bound to the action is pressed
+    action_handlers: HashMap<String, StepHandler<Mode, M>>,
+    /// Named draw passes run in order over the output of the draw function
+    passes: Vec<Pass<Mode, M>>,
+    /// Debug buffers shown as insets over the preview, in registration order
+    debug_views: Vec<DebugView<Mode, M>>,
+    /// Memory for temporary per-frame data, reset before each draw
+    arena: Arena,
+    /// Pixmap reused by `draw_pixmap`, allocated on first use
+    #[cfg(feature = "tiny-skia")]
+    pixmap: std::cell::RefCell<Option<tiny_skia::Pixmap>>,
+    /// Watched files and the handlers called when they change
+    #[cfg(feature = "watch")]
+    file_watches: Vec<(Watcher, FileHandler<Mode, M>)>,
+    /// Optional function used to skip drawing while the model is unchanged
+    model_version: Option<VersionFn<M>>,
+    /// Model version of the retained frame in `previous_frame`
+    drawn_version: Option<u64>,
+    /// Whether a draw function has already been reported for returning a wrongly sized frame
+    size_mismatch_reported: bool,
+    /// Whether a supersampling factor too large for the export size has already been reported
+    supersample_reported: bool,
+    /// Size of the last frame the draw function returned at the right size
+    drawn_size: (u32, u32),
+    /// Kinds of frame problems already reported by the debug frame checks
+    reported_frame_issues: Vec<Discriminant<FrameIssue>>,
+    /// Diagnostics already explained when `Config::verbose_diagnostics` is enabled
+    reported_diagnostics: HashSet<Diagnostic>,
+    /// Running mean of the draw time in seconds, used to spot frame time spikes
+    mean_draw_time: f32,
+    /// Statistics of the latest drawn frame, if tracking is enabled
+    frame_stats: Option<FrameStats>,
+    /// Phantom data for mode type
+    _mode: PhantomData<Mode>,
+}
+
+// Helper function for frame saving setup
+fn setup_frame_sender(config: &Config) -> (FrameSender, JoinHandle<()>) {
+    let queue = Arc::new(SaveQueue {
+        state: Mutex::new(SaveState::default()),
+        queued: Condvar::new(),
+        taken: Condvar::new(),
+        capacity: config.save_queue.0.max(1),
+    });
+    let tx = FrameSender(Arc::clone(&queue));
+
+    let handle = std::thread::spawn(move || {
+        let rx = FrameSender(queue);
+        while let Some(message) = rx.0.pop() {
+            match message {
+                FrameMessage::Frame(frame_data, filename, width, height, options) => {
+                    if let Err(err) = save_frame(frame_data, filename, width, height, &options) {
+                        eprintln!("Failed to save frame: {}", err);
+                    }
+                }
+                FrameMessage::Checkpoint(contents, path) => {
+                    // Write then rename, so an interruption never leaves a partial checkpoint
+                    let partial = path.with_extension("partial");
+                    let result = std::fs::write(&partial, contents)
+                        .and_then(|_| std::fs::rename(&partial, &path));
+                    if let Err(err) = result {
+                        eprintln!("Failed to write checkpoint: {}", err);
+                    }
+                }
+            }
+        }
+    });
+
+    (tx, handle)
+}
+
+/// Draw time multiple of the running mean above which a frame counts as a spike
+const SPIKE_FACTOR: f32 = 4.0;
+
+/// Minimum draw time in seconds for a frame to count as a spike
+const SPIKE_MIN_SECS: f32 = 0.008;
+
+/// Largest distance in pixels the cursor may move between the clicks of a double click
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+
+/// A common failure explained when `Config::verbose_diagnostics` is enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Diagnostic {
+    /// The draw function returned a buffer of the wrong length
+    FrameSize,
+    /// The window or buffer was given a zero width or height
+    ZeroDimensions,
+    /// The GPU surface could not be created, resized or presented
+    Surface,
+    /// A frame took much longer to draw than the ones before it
+    FrameSpike,
+    /// The shader set with `Config::post_shader` failed to compile
+    PostShader,
+}
+
+impl Diagnostic {
+    /// Returns the explanation and suggested fix printed for this failure
+    fn hint(self) -> &'static str {
+        match self {
+            Diagnostic::FrameSize => {
+                "A draw function must return exactly width * height * 4 bytes (RGBA). \
+                 Start from `app.new_frame()`, or compute the length from `app.config.width` \
+                 and `app.config.height` every frame instead of storing it, since `set_dims` \
+                 and exports at `Config::export_size` change the size."
+            }
+            Diagnostic::ZeroDimensions => {
+                "Windows and frames must be at least 1x1 pixels. Check the values passed to \
+                 `Config::with_dims`, `Config::new` or `App::set_dims`."
+            }
+            Diagnostic::Surface => {
+                "The GPU surface used to show frames failed. This usually means outdated \
+                 graphics drivers, a remote desktop session or a virtual machine without GPU \
+                 support. Updating drivers often helps; frames can still be rendered without \
+                 a window using `headless::render_single`."
+            }
+            Diagnostic::FrameSpike => {
+                "A frame took much longer to draw than the ones before it. Spikes like this \
+                 are often caused by allocating inside the draw function, such as a new `Vec` \
+                 per shape or per row. Allocate the frame once with `app.new_frame()` and keep \
+                 other buffers in the model so they can be reused."
+            }
+            Diagnostic::PostShader => {
+                "A post shader must define `@fragment fn fs_main(@location(0) tex_coord: \
+                 vec2<f32>) -> @location(0) vec4<f32>` and can only use the bindings declared \
+                 for it: `frame_texture`, `frame_sampler` and `locals`. See the `scaling` module."
+            }
+        }
+    }
+}
+
+/// Size in pixels of the squares of the transparency checkerboard
+const CHECKER_SIZE: u32 = 8;
+
+/// Composites an RGBA frame over a light gray checkerboard into `target`
+fn composite_checkerboard(target: &mut [u8], frame: &[u8], width: u32) {
+    // The two alternating rows of squares, copied into place row by row
+    let rows: [Vec<u8>; 2] = std::array::from_fn(|phase| {
+        (0..width)
+            .flat_map(|x| {
+                let v = if (x / CHECKER_SIZE + phase as u32).is_multiple_of(2) {
+                    204
+                } else {
+                    255
+                };
+                [v, v, v, 255]
+            })
+            .collect()
+    });
+    let row_bytes = (width * 4) as usize;
+    for (y, row) in target.chunks_exact_mut(row_bytes).enumerate() {
+        row.copy_from_slice(&rows[(y as u32 / CHECKER_SIZE % 2) as usize]);
+    }
+    let height = (frame.len() / row_bytes.max(1)) as u32;
+    blend_rect(target, width, frame, width, (0, 0, width, height), (0, 0));
+}
+
+/// Crops or pads a wrongly sized frame to `width` x `height`, filling gaps with `fill`
+///
+/// `source` is the size the frame was drawn at, if known, as when a frame of the
+/// previous window size is returned after a resize; the frame is then cropped or
+/// padded row by row. Without it the result is just `fill`, since reinterpreting
+/// the bytes at the wrong row length would shear the image.
+fn fit_frame(
+    frame: Vec<u8>,
+    source: Option<(u32, u32)>,
+    width: u32,
+    height: u32,
+    fill: [u8; 4],
+) -> Vec<u8> {
+    let mut fitted = vec![0; (width * height * 4) as usize];
+    clear(&mut fitted, fill);
+    if let Some((source_width, source_height)) = source {
+        let rect = (0, 0, source_width, source_height);
+        copy_rect(&mut fitted, width, &frame, source_width, rect, (0, 0));
+    }
+    fitted
+}
+
+/// Returns the size of a frame supersampled by `factor`, or None if its byte count overflows a u32
+fn supersampled_size(width: u32, height: u32, factor: u32) -> Option<(u32, u32)> {
+    let (width, height) = (width.checked_mul(factor)?, height.checked_mul(factor)?);
+    width.checked_mul(height)?.checked_mul(4)?;
+    Some((width, height))
+}
+
+/// Draws `image` scaled to fill an RGBA frame of the given size, using nearest neighbor sampling
+fn blit_scaled(target: &mut [u8], width: u32, height: u32, image: &Image) {
+    for y in 0..height {
+        let sy = (y as u64 * image.height as u64 / height.max(1) as u64) as u32;
+        for x in 0..width {
+            let sx = (x as u64 * image.width as u64 / width.max(1) as u64) as u32;
+            let i = ((y * width + x) * 4) as usize;
+            target[i..i + 4].copy_from_slice(&image.pixel(sx, sy));
+        }
+    }
+}
+
+/// Stands in for the draw function of sketches that draw in place, which never call it
+fn no_draw<Mode, M: Clone>(app: &App<Mode, M>, _model: &M) -> Vec<u8> {
+    app.new_frame()
+}
+
+/// Returns the window level for floating above other windows or not
+fn window_level(always_on_top: bool) -> WindowLevel {
+    if always_on_top {
+        WindowLevel::AlwaysOnTop
+    } else {
+        WindowLevel::Normal
+    }
+}
+
+/// Presents the pixel buffer, through the surface renderer if there is one
+fn render_pixels(
+    pixels: &Pixels,
+    renderer: Option<&SurfaceRenderer>,
+    time: f32,
+) -> Result<(), Error> {
+    match renderer {
+        Some(renderer) => renderer.render(pixels, time),
+        None => pixels.render(),
+    }
+}
+
+/// Returns the value following `flag` on the command line, e.g. the path in `--replay run.artimate`
+fn arg_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next()
+}
+
+/// Returns the directory frames are saved to, `Config::frame_dir` or `Downloads/frames`
+fn frames_dir(config: &Config) -> std::io::Result<PathBuf> {
+    if let Some(dir) = &config.frame_dir {
+        return Ok(dir.clone());
+    }
+    dirs::download_dir()
+        .map(|dir| dir.join("frames"))
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no downloads directory"))
+}
+
+/// Returns `title` in lowercase with everything but ASCII letters and digits replaced by `_`
+fn title_slug(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Creates a new `session_{millis}_{slug}` directory in `dir` and returns its path
+///
+/// The timestamp is bumped past any existing directory, so sessions started in
+/// the same millisecond never share one.
+fn new_session_dir(dir: &Path, slug: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let mut millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    loop {
+        let path = dir.join(format!("session_{}_{}", millis, slug));
+        match std::fs::create_dir(&path) {
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => millis += 1,
+            result => return result.map(|_| path),
+        }
+    }
+}
+
+/// Returns the most recent `session_{millis}_{slug}` directory in `dir`, if any
+fn latest_session_dir(dir: &Path, slug: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?.strip_prefix("session_")?;
+            let (timestamp, title) = name.split_once('_')?;
+            let timestamp = timestamp.parse::<u128>().ok().filter(|_| title == slug)?;
+            path.is_dir().then_some((timestamp, path))
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, path)| path)
+}
+
+/// Returns the number following the highest `take_NNN` directory in `dir`, starting at 1
+fn next_take_number(dir: &Path) -> u32 {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let name = entry.ok()?.file_name();
+                    name.to_str()?.strip_prefix("take_")?.parse::<u32>().ok()
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0)
+        + 1
+}
+
+/// Returns the directory for screenshots and exports, creating it if needed
+fn screenshot_dir(config: &Config) -> std::io::Result<PathBuf> {
+    let output_dir = match &config.screenshot_dir {
+        Some(dir) => dir.clone(),
+        None => dirs::download_dir()
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "no downloads directory")
+            })?
+            .join("artmate"),
+    };
+    std::fs::create_dir_all(&output_dir)?;
+    Ok(output_dir)
+}
+
+pub(crate) fn save_frame(
+    mut frame_data: Vec<u8>,
+    filename: String,
+    width: u32,
+    height: u32,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    if let Some(max_edge) = options.thumbnail_size {
+        save_thumbnail(&frame_data, Path::new(&filename), width, height, max_edge)?;
+    }
+    match options.format {
+        ExportFormat::Png => {}
+        #[cfg(feature = "image")]
+        format => return encode_frame(frame_data, &filename, width, height, format, options),
+    }
+    let profile = &options.color_profile;
+    profile.convert(&mut frame_data);
+    let file = std::fs::File::create(&filename)?;
+    let mut info = Info::with_size(width, height);
+    info.color_type = png::ColorType::Rgba;
+    info.bit_depth = png::BitDepth::Eight;
+    info.icc_profile = Some(profile.icc().into());
+    let encoder = Encoder::with_info(file, info).map_err(std::io::Error::other)?;
+
+    let mut writer = encoder.write_header().map_err(std::io::Error::other)?;
+    writer
+        .write_image_data(&frame_data)
+        .map_err(std::io::Error::other)
+}
+
+/// Writes a frame in a format other than PNG with the `image` crate
+#[cfg(feature = "image")]
+fn encode_frame(
+    mut frame_data: Vec<u8>,
+    filename: &str,
+    width: u32,
+    height: u32,
+    format: ExportFormat,
+    options: &SaveOptions,
+) -> std::io::Result<()> {
+    use image::codecs::{bmp::BmpEncoder, jpeg::JpegEncoder, tiff::TiffEncoder, webp::WebPEncoder};
+    use image::{ExtendedColorType, ImageEncoder};
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    let rgba = ExtendedColorType::Rgba8;
+    let result = match format {
+        ExportFormat::Png => unreachable!("PNG frames are written by save_frame"),
+        ExportFormat::Jpeg(quality) => {
+            let bg = options.background;
+            let rgb: Vec<u8> = frame_data
+                .chunks_exact(4)
+                .flat_map(|px| {
+                    let a = px[3] as u32;
+                    let over = |c: usize| {
+                        ((px[c] as u32 * a + bg[c] as u32 * (255 - a) + 127) / 255) as u8
+                    };
+                    [over(0), over(1), over(2)]
+                })
+                .collect();
+            JpegEncoder::new_with_quality(&mut file, quality.clamp(1, 100)).write_image(
+                &rgb,
+                width,
+                height,
+                ExtendedColorType::Rgb8,
+            )
+        }
+        ExportFormat::WebP => {
+            let profile = &options.color_profile;
+            profile.convert(&mut frame_data);
+            let mut encoder = WebPEncoder::new_lossless(&mut file);
+            // The encoder always accepts a profile
+            let _ = encoder.set_icc_profile(profile.icc());
+            encoder.write_image(&frame_data, width, height, rgba)
+        }
+        ExportFormat::Bmp => {
+            BmpEncoder::new(&mut file).write_image(&frame_data, width, height, rgba)
+        }
+        ExportFormat::Tiff => {
+            TiffEncoder::new(&mut file).write_image(&frame_data, width, height, rgba)
+        }
+    };
+    result.map_err(std::io::Error::other)?;
+    std::io::Write::flush(&mut file)
+}
+
+/// Appends every file below `dir` to `entries`, named under `prefix`
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    entries: &mut Vec<(String, PathBuf)>,
+) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut children: Vec<_> = std::fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+    for child in children {
+        let name = format!("{}/{}", prefix, child.file_name().to_string_lossy());
+        let path = child.path();
+        if path.is_dir() {
+            collect_files(&path, &name, entries)?;
+        } else {
+            entries.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Writes a downscaled copy of a frame to a `thumbnails` directory next to `path`
+fn save_thumbnail(
+    frame_data: &[u8],
+    path: &Path,
+    width: u32,
+    height: u32,
+    max_edge: u32,
+) -> std::io::Result<()> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    let Some(image) = Image::from_raw(width, height, frame_data.to_vec()) else {
+        return Ok(());
+    };
+    let dir = dir.join("thumbnails");
+    std::fs::create_dir_all(&dir)?;
+    image
+        .thumbnail(max_edge)
+        .save_png(dir.join(name).with_extension("png"))
+}
+
+/// Simple sketches that only need drawing functionality
+impl App<SketchMode> {
+    /// Creates a simple sketch application with just a draw function and configuration
+    /// 
+    /// This is the simplest way to create an Artimate application. It's perfect for
+    /// static graphics, animations that don't need persistent state, or simple
+    /// interactive graphics that only depend on time and mouse position.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration settings for the window and rendering
+    /// * `draw` - Function called each frame to generate RGBA pixel data
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config, Error};
+    /// 
+    /// fn main() -> Result<(), Error> {
+    ///     let config = Config::with_dims(400, 400);
+    ///     let mut app = App::sketch(config, draw);
+    ///     app.run()
+    /// }
+    /// 
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     // Create a simple animated circle
+    ///     let mut pixels = vec![0u8; (app.config.width * app.config.height * 4) as usize];
+    ///     // Fill with pixel data...
+    ///     pixels
+    /// }
+    /// ```
+    pub fn sketch(config: Config, draw: DrawFn<SketchMode, ()>) -> Self {
+        let (frame_sender, frame_saver) = if config.frames_to_save > 0 {
+            let (tx, handle) = setup_frame_sender(&config);
+            (Some(tx), Some(handle))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            model: (),
+            config,
+            update: None,
+            draw,
+            export_draw: None,
+            draw_in_place: None,
+            frame_buffer: Vec::new(),
+            previous_frame: Vec::new(),
+            dirty_rects: std::cell::RefCell::new(Vec::new()),
+            pixels_hold_frame: false,
+            compare_seeds: Vec::new(),
+            seed_fn: None,
+            time: 0.0,
+            frame_count: 0,
+            last_frame: None,
+            frame_deadline: None,
+            frame_intervals: PacingStats::new(),
+            frame_timings: Vec::new(),
+            warmup_remaining: 0,
+            session_recording: None,
+            session_replay: None,
+            stats_start: Instant::now(),
+            window: None,
+            pixels: None,
+            surface_renderer: None,
+            start_time: Instant::now(),
+            mouse_position: (0.0, 0.0),
+            frame_sender,
+            frame_saver,
+            save_start: 0,
+            dropped_frames: 0,
+            raw_frame: Vec::new(),
+            captures: Vec::new(),
+            take: None,
+            take_capture: None,
+            session_dir: None,
+            export_hook: None,
+            low_power: false,
+            checkpoint: None,
+            key_handlers: HashMap::new(),
+            mouse_handlers: HashMap::new(),
+            mouse_release_handlers: HashMap::new(),
+            mouse_double_click_handlers: HashMap::new(),
+            last_click: None,
+            mouse_drag_handlers: HashMap::new(),
+            mouse_move_handler: None,
+            text_handler: None,
+            file_drop_handler: None,
+            buttons_down: HashMap::new(),
+            key_press_handlers: HashMap::new(),
+            key_chord_handlers: HashMap::new(),
+            key_release_handlers: HashMap::new(),
+            keys_down: HashSet::new(),
+            modifiers: Modifiers::default(),
+            guides_visible: true,
+            help_visible: false,
+            key_descriptions: HashMap::new(),
+            history: VecDeque::new(),
+            history_cursor: 0,
+            paused: None,
+            scrubbing: false,
+            remaps: HashMap::new(),
+            actions: Vec::new(),
+            action_handlers: HashMap::new(),
+            passes: Vec::new(),
+            debug_views: Vec::new(),
+            arena: Arena::new(),
+            #[cfg(feature = "tiny-skia")]
+            pixmap: std::cell::RefCell::new(None),
+            #[cfg(feature = "watch")]
+            file_watches: Vec::new(),
+            model_version: None,
+            drawn_version: None,
+            size_mismatch_reported: false,
+            supersample_reported: false,
+            drawn_size: (0, 0),
+            reported_frame_issues: Vec::new(),
+            reported_diagnostics: HashSet::new(),
+            mean_draw_time: 0.0,
+            frame_stats: None,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Creates a sketch whose draw function writes into a reused buffer
+    ///
+    /// Returning a new `Vec<u8>` every frame allocates a full frame each time, which
+    /// adds up for 4K sketches. An in-place draw function gets the same buffer every
+    /// frame, already cleared to `Config::clear_color` or, with `auto_clear`
+    /// disabled, holding the previous frame.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &(), frame: &mut [u8]) {
+    ///     let x = (app.time * 60.0) as usize % app.config.width as usize;
+    ///     for row in frame.chunks_exact_mut(app.config.width as usize * 4) {
+    ///         row[x * 4..x * 4 + 4].copy_from_slice(&[255, 255, 255, 255]);
+    ///     }
+    /// }
+    ///
+    /// let mut app = App::sketch_in_place(Config::with_dims(3840, 2160), draw);
+    /// app.run().unwrap();
+    /// ```
+    pub fn sketch_in_place(config: Config, draw: DrawInPlaceFn<SketchMode, ()>) -> Self {
+        let mut app = Self::sketch(config, no_draw);
+        app.draw_in_place = Some(draw);
+        app
+    }
+}
+
+/// Stateful sketches that need both model state and update functionality
+impl<M> App<AppMode, M>
+where
+    M: Clone,
+{
+    /// Creates a stateful application with model, update, and draw functions
+    ///
+    /// This method creates a full-featured application that can maintain state
+    /// between frames. The model is updated each frame via the update function,
+    /// and the draw function generates pixel data based on the current model state.
+    ///
+    /// # Arguments
+    /// * `model` - Initial state of the application
+    /// * `config` - Configuration settings for the window and rendering
+    /// * `update` - Function called each frame to update the model based on app state
+    /// * `draw` - Function called each frame to generate RGBA pixel data from the model
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config, Error};
+    /// 
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     position: f32,
+    ///     direction: f32,
+    /// }
+    /// 
+    /// fn main() -> Result<(), Error> {
+    ///     let config = Config::with_dims(800, 600);
+    ///     let model = Model { position: 0.0, direction: 1.0 };
+    ///     let mut app = App::app(model, config, update, draw);
+    ///     app.run()
+    /// }
+    /// 
+    /// fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
+    ///     model.position += model.direction * 100.0 * (1.0 / 60.0); // 60 FPS
+    ///     if model.position > app.config.width as f32 {
+    ///         model.direction = -1.0;
+    ///     } else if model.position < 0.0 {
+    ///         model.direction = 1.0;
+    ///     }
+    ///     model
+    /// }
+    /// 
+    /// fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+    ///     // Generate pixel data based on model
+    ///     vec![255; (app.config.width * app.config.height * 4) as usize]
+    /// }
+    /// ```
+    #[allow(clippy::self_named_constructors)]
+    pub fn app(
+        model: M,
+        config: Config,
+        update: UpdateFn<AppMode, M>,
+        draw: DrawFn<AppMode, M>,
+    ) -> Self {
+        let (frame_sender, frame_saver) = if config.frames_to_save > 0 {
+            let (tx, handle) = setup_frame_sender(&config);
+            (Some(tx), Some(handle))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            model,
+            config,
+            update: Some(update),
+            draw,
+            export_draw: None,
+            draw_in_place: None,
+            frame_buffer: Vec::new(),
+            previous_frame: Vec::new(),
+            dirty_rects: std::cell::RefCell::new(Vec::new()),
+            pixels_hold_frame: false,
+            compare_seeds: Vec::new(),
+            seed_fn: None,
+            time: 0.0,
+            frame_count: 0,
+            last_frame: None,
+            frame_deadline: None,
+            frame_intervals: PacingStats::new(),
+            frame_timings: Vec::new(),
+            warmup_remaining: 0,
+            session_recording: None,
+            session_replay: None,
+            stats_start: Instant::now(),
+            window: None,
+            pixels: None,
+            surface_renderer: None,
+            start_time: Instant::now(),
+            mouse_position: (0.0, 0.0),
+            frame_sender,
+            frame_saver,
+            save_start: 0,
+            dropped_frames: 0,
+            raw_frame: Vec::new(),
+            captures: Vec::new(),
+            take: None,
+            take_capture: None,
+            session_dir: None,
+            export_hook: None,
+            low_power: false,
+            checkpoint: None,
+            key_handlers: HashMap::new(),
+            mouse_handlers: HashMap::new(),
+            mouse_release_handlers: HashMap::new(),
+            mouse_double_click_handlers: HashMap::new(),
+            last_click: None,
+            mouse_drag_handlers: HashMap::new(),
+            mouse_move_handler: None,
+            text_handler: None,
+            file_drop_handler: None,
+            buttons_down: HashMap::new(),
+            key_press_handlers: HashMap::new(),
+            key_chord_handlers: HashMap::new(),
+            key_release_handlers: HashMap::new(),
+            keys_down: HashSet::new(),
+            modifiers: Modifiers::default(),
+            guides_visible: true,
+            help_visible: false,
+            key_descriptions: HashMap::new(),
+            history: VecDeque::new(),
+            history_cursor: 0,
+            paused: None,
+            scrubbing: false,
+            remaps: HashMap::new(),
+            actions: Vec::new(),
+            action_handlers: HashMap::new(),
+            passes: Vec::new(),
+            debug_views: Vec::new(),
+            arena: Arena::new(),
+            #[cfg(feature = "tiny-skia")]
+            pixmap: std::cell::RefCell::new(None),
+            #[cfg(feature = "watch")]
+            file_watches: Vec::new(),
+            model_version: None,
+            drawn_version: None,
+            size_mismatch_reported: false,
+            supersample_reported: false,
+            drawn_size: (0, 0),
+            reported_frame_issues: Vec::new(),
+            reported_diagnostics: HashSet::new(),
+            mean_draw_time: 0.0,
+            frame_stats: None,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Creates a stateful application whose draw function writes into a reused buffer
+    ///
+    /// Like `app`, but the frame is drawn in place instead of being returned, so
+    /// no frame buffer is allocated per frame. See `sketch_in_place`.
+    pub fn app_in_place(
+        model: M,
+        config: Config,
+        update: UpdateFn<AppMode, M>,
+        draw: DrawInPlaceFn<AppMode, M>,
+    ) -> Self {
+        let mut app = Self::app(model, config, update, no_draw);
+        app.draw_in_place = Some(draw);
+        app
+    }
+}
+
+/// Common methods for both sketch and app modes
+impl<Mode, M> App<Mode, M>
+where
+    M: Clone,
+{
+    /// Starts the application's main loop and runs until the window is closed
+    ///
+    /// This method creates the window, initializes the rendering context, and begins
+    /// the main event loop. It handles window events, updates the model (if in AppMode),
+    /// calls the draw function, and renders the result to the screen.
+    ///
+    /// The method will block until the application is closed and will print performance
+    /// statistics (FPS, frame count, elapsed time) when the application exits.
+    /// Passing `--keys` on the command line prints the bindings as a Markdown table
+    /// instead of opening a window, `--record-session <path>` records the run to a
+    /// session file and `--replay <path>` plays one back.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the application ran successfully and was closed normally
+    /// * `Err(Error)` - If there was an error during window creation or rendering
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config, Error};
+    /// 
+    /// fn main() -> Result<(), Error> {
+    ///     let config = Config::with_dims(800, 600);
+    ///     let mut app = App::sketch(config, draw);
+    ///     app.run() // Blocks until window is closed
+    /// }
+    /// 
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     vec![255; (app.config.width * app.config.height * 4) as usize]
+    /// }
+    /// ```
+    pub fn run(&mut self) -> Result<(), Error> {
+        if self.config.width == 0 || self.config.height == 0 {
+            let message = format!(
+                "window dimensions must be non-zero, got {}x{}",
+                self.config.width, self.config.height
+            );
+            eprintln!("Cannot start app: {}", message);
+            self.diagnose(Diagnostic::ZeroDimensions);
+            return Err(Error::UserDefined(message.into()));
+        }
+        if let Some(dir) = &self.config.frame_dir {
+            if let Err(err) = std::fs::create_dir_all(dir) {
+                eprintln!("Cannot create frame directory {}: {}", dir.display(), err);
+                return Err(Error::UserDefined(Box::new(err)));
+            }
+        }
+        if std::env::args().any(|arg| arg == "--keys") {
+            print!("{}", self.help_markdown());
+            return Ok(());
+        }
+        if let Some(path) = arg_value("--replay") {
+            if let Err(err) = self.replay(&path) {
+                eprintln!("Cannot replay {}: {}", path, err);
+                return Err(Error::UserDefined(Box::new(err)));
+            }
+        } else if let Some(path) = arg_value("--record-session") {
+            self.session_recording = Some((Session::default(), path.into()));
+        }
+        if let Some((session, _)) = &mut self.session_recording {
+            *session = Session::new(self.config.seed, self.config.wh());
+            session.settings = Some(SessionSettings::from_config(&self.config));
+        }
+        #[cfg(feature = "video")]
+        if let Some((path, fps)) = self.config.record_video.clone() {
+            let video = FfmpegVideo::new(path, fps);
+            self.add_capture(Capture::new(video).stage(self.config.capture_stage));
+        }
+        let event_loop = EventLoop::new().unwrap();
+        event_loop.set_control_flow(ControlFlow::Poll);
+        self.warmup_remaining = self.config.warmup_frames;
+        self.stats_start = Instant::now();
+        self.low_power = self.config.power_mode.is_low();
+        if self.low_power {
+            println!(
+                "Low power mode: {} fps",
+                self.target_fps().unwrap_or_default()
+            );
+        }
+        if self.checkpoint.is_some() && std::env::args().any(|arg| arg == "--resume") {
+            match self.resume() {
+                Ok(true) => println!("Resuming from frame {}", self.frame_count),
+                Ok(false) => println!("No checkpoint found, starting from the beginning"),
+                Err(err) => eprintln!("Failed to resume: {}", err),
+            }
+        }
+        let res = event_loop.run_app(self);
+
+        let elapsed = self.stats_start.elapsed().as_secs_f32();
+        println!();
+        println!("Average FPS: {}", self.frame_count as f32 / elapsed);
+        println!("Frame count: {}", self.frame_count,);
+        println!("Elapsed time: {} seconds", elapsed);
+        for timing in self.pass_timings() {
+            println!("Pass {}: {:?} per frame", timing.name, timing.mean());
+        }
+        let pacing = self.pacing_report();
+        if !pacing.is_empty() {
+            print!("{}", pacing);
+        }
+
+        if let Err(err) = self.finish_saving() {
+            eprintln!("Failed to save frames: {}", err);
+        }
+        if let Some((session, path)) = self.session_recording.take() {
+            match session.save(&path) {
+                Ok(()) => println!("Session recorded to {}", path.display()),
+                Err(err) => eprintln!("Failed to write session {}: {}", path.display(), err),
+            }
+        }
+        if self.checkpoint.is_some() && self.frame_count >= self.save_end() {
+            // The export finished, so there is nothing left to resume
+            if let Some(path) = self.checkpoint_path() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        let manifest = match self.write_timing_manifest() {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                eprintln!("Failed to write timing manifest: {}", err);
+                None
+            }
+        };
+        let mut exported = self.saved_files();
+        exported.extend(manifest.clone());
+        exported.extend(self.finish_captures());
+        if let Some(format) = self.config.package_format {
+            match self.package_session(format, manifest, elapsed) {
+                Ok(Some(path)) => {
+                    println!("Session packaged to {}", path.display());
+                    exported.push(path);
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("Failed to package session: {}", err),
+            }
+        }
+        if !exported.is_empty() {
+            self.export_complete(&exported);
+        }
+
+        res.map_err(|e| Error::UserDefined(Box::new(e)))
+    }
+
+    /// Returns a deterministic random number generator for the stream named `label`
+    ///
+    /// Each label gets its own independent sequence derived from `Config::seed`, so
+    /// each subsystem of a sketch can draw from its own stream and adding a random
+    /// call to one does not change the values another sees. Calling this again with
+    /// the same label restarts the stream.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     let mut rng = app.fork_rng("background");
+    ///     let mut frame = app.new_frame();
+    ///     for px in frame.chunks_exact_mut(4) {
+    ///         let v = (rng.gaussian(128.0, 20.0).clamp(0.0, 255.0)) as u8;
+    ///         px.copy_from_slice(&[v, v, v, 255]);
+    ///     }
+    ///     frame
+    /// }
+    ///
+    /// let app = App::sketch(Config::with_dims(100, 100).seed(7), draw);
+    /// assert_eq!(app.fork_rng("stars"), app.fork_rng("stars"));
+    /// ```
+    pub fn fork_rng(&self, label: &str) -> Rng {
+        Rng::new(self.config.seed).fork(label)
+    }
+
+    /// Returns a deterministic seed for the subsystem named `label`, e.g. a noise field
+    pub fn seed_for(&self, label: &str) -> u64 {
+        self.fork_rng(label).next_u64()
+    }
+
+    /// Returns the current x-coordinate of the mouse cursor in pixels
+    ///
+    /// The coordinate is relative to the top-left corner of the window,
+    /// with positive values extending to the right.
+    pub fn mouse_x(&self) -> f32 {
+        self.mouse_position.0
+    }
+
+    /// Returns the current y-coordinate of the mouse cursor in pixels
+    ///
+    /// The coordinate is relative to the top-left corner of the window,
+    /// with positive values extending downward.
+    pub fn mouse_y(&self) -> f32 {
+        self.mouse_position.1
+    }
+
+    /// Returns true while `key` is held down
+    ///
+    /// Polling held keys from the update function suits continuous controls such
+    /// as WASD movement better than a handler per key.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// type Position = (f32, f32);
+    ///
+    /// fn update(app: &App<AppMode, Position>, (x, y): Position) -> Position {
+    ///     let held = |c: &str| app.is_key_down(&Key::Character(c.into()));
+    ///     let dx = held("d") as i32 - held("a") as i32;
+    ///     let dy = held("s") as i32 - held("w") as i32;
+    ///     (x + 4.0 * dx as f32, y + 4.0 * dy as f32)
+    /// }
+    /// # fn draw(app: &App<AppMode, Position>, _model: &Position) -> Vec<u8> { app.new_frame() }
+    ///
+    /// let app = App::app((200.0, 200.0), Config::with_dims(400, 400), update, draw);
+    /// ```
+    pub fn is_key_down(&self, key: &Key) -> bool {
+        self.keys_down.contains(key)
+    }
+
+    /// Returns the keys currently held down, in no particular order
+    pub fn keys_down(&self) -> impl Iterator<Item = &Key> {
+        self.keys_down.iter()
+    }
+
+    /// Returns true while `button` is held down
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains_key(&button)
+    }
+
+    /// Changes the width and height of the sketch while it is running
+    ///
+    /// Resizes the window, recreates the pixel buffer and the surface at the new
+    /// size and requests a redraw, so the draw function is called again with the new
+    /// `config.width` and `config.height`. Useful for switching between preview and
+    /// export resolutions from a key handler.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(400, 400), draw);
+    /// app.on_key_press(Key::Character("f".into()), |app| app.set_dims(1600, 1600));
+    /// app.on_key_press(Key::Character("p".into()), |app| app.set_dims(400, 400));
+    /// ```
+    pub fn set_dims(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            eprintln!("Ignoring zero dimensions {}x{}", width, height);
+            self.diagnose(Diagnostic::ZeroDimensions);
+            return;
+        }
+        self.resize_buffer(width, height);
+        if let Some(window) = self.window.clone() {
+            let size = self.config.window_size();
+            window.set_min_inner_size(self.config.min_window_size());
+            // A size applied immediately is not necessarily reported with a Resized event
+            if let Some(applied) = window.request_inner_size(size) {
+                self.resize_surface(applied);
+            }
+            window.request_redraw();
+        }
+    }
+
+    /// Resizes the surface the pixel buffer is scaled onto to the window's physical size
+    fn resize_surface(&mut self, size: PhysicalSize<u32>) {
+        if let Some(pixels) = self.pixels.as_mut() {
+            if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                eprintln!("Failed to resize surface: {}", err);
+                self.diagnose(Diagnostic::Surface);
+            }
+        }
+        if let Some(renderer) = self.surface_renderer.as_mut() {
+            renderer.resize(size.width, size.height);
+        }
+    }
+
+    /// Sets the configured dimensions and recreates the pixel buffer at the new size
+    fn resize_buffer(&mut self, width: u32, height: u32) {
+        self.config.width = width;
+        self.config.height = height;
+        self.previous_frame.clear();
+        self.pixels_hold_frame = false;
+        if let Some(pixels) = self.pixels.as_mut() {
+            if let Err(err) = pixels.resize_buffer(width, height) {
+                eprintln!("Failed to resize pixel buffer: {}", err);
+            }
+        }
+    }
+
+    /// Starts saving the next `frames` frames drawn
+    ///
+    /// Replaces any capture in progress, so saving can be started from a key
+    /// handler once the visuals look right instead of from frame zero. Frames are
+    /// written to the same directory and with the same options as
+    /// `Config::frames_to_save`.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(400, 400), draw);
+    /// app.on_key_press(Key::Character("r".into()), |app| app.start_saving(300));
+    /// app.on_key_press(Key::Character("s".into()), |app| app.stop_saving());
+    /// ```
+    pub fn start_saving(&mut self, frames: u32) {
+        if self.frame_sender.is_none() {
+            let (tx, handle) = setup_frame_sender(&self.config);
+            self.frame_sender = Some(tx);
+            self.frame_saver = Some(handle);
+        }
+        self.save_start = self.frame_count;
+        self.config.frames_to_save = frames;
+        self.session_dir = None;
+    }
+
+    /// Stops saving frames, starting with the next frame drawn
+    pub fn stop_saving(&mut self) {
+        self.config.frames_to_save = self.frame_count.saturating_sub(self.save_start);
+    }
+
+    /// Returns true if the next frame drawn will be saved
+    pub fn is_saving(&self) -> bool {
+        self.frame_count >= self.save_start && self.frame_count < self.save_end()
+    }
+
+    /// Returns the number of frames still to be saved
+    pub fn frames_left_to_save(&self) -> u32 {
+        self.save_end()
+            .saturating_sub(self.frame_count.max(self.save_start))
+    }
+
+    /// Raises the limit set by `Config::frames` by `frames` and resumes drawing if it was reached
+    ///
+    /// Does nothing for apps without a frame limit.
+    pub fn extend_frames(&mut self, frames: u32) {
+        if let Some(limit) = self.config.frames.as_mut() {
+            *limit = limit.saturating_add(frames);
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
+    /// Returns the frame after the last one to be saved
+    fn save_end(&self) -> u32 {
+        self.save_start.saturating_add(self.config.frames_to_save)
+    }
+
+    /// Returns a buffer to start drawing the next frame into
+    ///
+    /// The buffer is filled with `config.clear_color`, or, when `config.auto_clear` is
+    /// disabled, holds a copy of the previous frame so trail effects only need to draw
+    /// what changed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     let mut pixels = app.new_frame();
+    ///     let i = ((app.mouse_y() as u32 * app.config.width + app.mouse_x() as u32) * 4) as usize;
+    ///     pixels[i..i + 4].copy_from_slice(&[255, 255, 255, 255]);
+    ///     pixels
+    /// }
+    ///
+    /// let config = Config::with_dims(200, 200).clear_color([20, 20, 30, 255]).auto_clear(false);
+    /// let app = App::sketch(config, draw);
+    /// ```
+    pub fn new_frame(&self) -> Vec<u8> {
+        let len = (self.config.width * self.config.height * 4) as usize;
+        if !self.config.auto_clear && self.previous_frame.len() == len {
+            return self.previous_frame.clone();
+        }
+        let mut frame = vec![0; len];
+        clear(&mut frame, self.config.clear_color);
+        frame
+    }
+
+    /// Returns the frame for a tiny-skia or wassily pixmap, ready to return from a draw function
+    ///
+    /// tiny-skia draws premultiplied alpha while artimate presents and saves straight
+    /// alpha, so returning `pixmap.take()` directly darkens translucent pixels unless
+    /// `Config::alpha_mode` declares it premultiplied. This copies the pixmap and
+    /// converts it with `buffer::unpremultiply`, which skips opaque pixels, making
+    /// it the fast path for sketches drawn with tiny-skia or wassily (use
+    /// `&canvas.pixmap`). The pixmap should match the configured dimensions.
+    ///
+    /// Requires the `tiny-skia` feature.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    /// use tiny_skia::{Color, Pixmap};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     let mut pixmap = Pixmap::new(app.config.width, app.config.height).unwrap();
+    ///     pixmap.fill(Color::from_rgba8(255, 128, 0, 128));
+    ///     app.present_pixmap(&pixmap)
+    /// }
+    ///
+    /// let app = App::sketch(Config::with_dims(200, 200), draw);
+    /// ```
+    #[cfg(feature = "tiny-skia")]
+    pub fn present_pixmap(&self, pixmap: &tiny_skia::Pixmap) -> Vec<u8> {
+        let mut frame = pixmap.data().to_vec();
+        unpremultiply(&mut frame);
+        frame
+    }
+
+    /// Draws with tiny-skia onto a pixmap owned by the app and returns the frame
+    ///
+    /// The app allocates one pixmap matching the configured dimensions and reuses it
+    /// every frame, reallocating only when the dimensions change. It is filled with
+    /// `config.clear_color` before `draw` runs, unless `config.auto_clear` is
+    /// disabled, in which case it keeps the previous frame's drawing. The result is
+    /// converted with `present_pixmap`.
+    ///
+    /// Requires the `tiny-skia` feature.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    /// use tiny_skia::{Paint, PathBuilder, FillRule, Transform};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     app.draw_pixmap(|pixmap| {
+    ///         let circle = PathBuilder::from_circle(100.0, 100.0, 50.0).unwrap();
+    ///         let mut paint = Paint::default();
+    ///         paint.set_color_rgba8(50, 107, 160, 255);
+    ///         pixmap.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+    ///     })
+    /// }
+    ///
+    /// let app = App::sketch(Config::with_dims(200, 200), draw);
+    /// ```
+    #[cfg(feature = "tiny-skia")]
+    pub fn draw_pixmap(&self, draw: impl FnOnce(&mut tiny_skia::Pixmap)) -> Vec<u8> {
+        let (width, height) = self.config.wh();
+        let [r, g, b, a] = self.config.clear_color;
+        let clear = tiny_skia::Color::from_rgba8(r, g, b, a);
+        let mut slot = self.pixmap.borrow_mut();
+        let pixmap = match slot.as_mut() {
+            Some(pixmap) if pixmap.width() == width && pixmap.height() == height => {
+                if self.config.auto_clear {
+                    pixmap.fill(clear);
+                }
+                pixmap
+            }
+            _ => {
+                let mut pixmap = tiny_skia::Pixmap::new(width.max(1), height.max(1))
+                    .expect("pixmap dimensions are valid");
+                pixmap.fill(clear);
+                slot.insert(pixmap)
+            }
+        };
+        draw(pixmap);
+        self.present_pixmap(pixmap)
+    }
+
+    /// Returns a slice of `len` default values that lives until the next frame is drawn
+    ///
+    /// The slice comes from an arena that is reset before every call to the draw
+    /// function, so temporary points and path segments cost no allocations once
+    /// the arena has grown to fit a frame. See the `arena` module.
+    ///
+    /// ```
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     let xs: &mut [f32] = app.alloc_slice(200);
+    ///     for (i, x) in xs.iter_mut().enumerate() {
+    ///         *x = i as f32 * 0.5;
+    ///     }
+    ///     app.new_frame()
+    /// }
+    ///
+    /// let app = App::sketch(Config::with_dims(200, 200), draw);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T: Copy + Default>(&self, len: usize) -> &mut [T] {
+        self.arena.alloc_slice(len)
+    }
+
+    /// Returns a copy of `values` that lives until the next frame is drawn
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        self.arena.alloc_copy(values)
+    }
+
+    /// Marks a region of the frame being drawn as changed since the previous frame
+    ///
+    /// `rect` is `(x0, y0, x1, y1)` in pixels. Once a draw function marks a region,
+    /// only the marked regions are copied into the window's pixel buffer, which
+    /// saves memory bandwidth for sketches that change a small part of a large
+    /// frame. Without any marks the whole frame is copied, as usual. The whole
+    /// frame is also copied when draw passes, overlays or seed comparison change
+    /// what is shown. Saved frames are always complete.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use artimate::buffer::fill_rect;
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     // Keep drawing over the previous frame
+    ///     let mut frame = app.new_frame();
+    ///     let x = app.frame_count % 1000;
+    ///     fill_rect(&mut frame, 1000, (x, 0, x + 1, 1000), [255, 255, 255, 255]);
+    ///     app.mark_dirty((x, 0, x + 1, 1000));
+    ///     frame
+    /// }
+    ///
+    /// let app = App::sketch(Config::with_dims(1000, 1000).auto_clear(false), draw);
+    /// ```
+    pub fn mark_dirty(&self, rect: (u32, u32, u32, u32)) {
+        self.dirty_rects.borrow_mut().push(rect);
+    }
+
+    /// Returns the statistics of the latest drawn frame
+    ///
+    /// None unless `Config::track_frame_stats` is enabled and a frame has been drawn.
+    pub fn frame_stats(&self) -> Option<FrameStats> {
+        self.frame_stats
+    }
+
+    /// Renders the current state to an RGBA buffer without a window or event loop
+    ///
+    /// Runs the draw function and the enabled draw passes at the configured size,
+    /// using the current model, `frame_count` and `time`, so stills can be rendered
+    /// on a server or checked in tests. See `render_rgba` for rendering a given
+    /// frame of a deterministic run.
+    ///
+    /// # Examples
+    /// ```
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// let mut app = App::sketch(Config::with_dims(64, 64).clear_color([10, 20, 30, 255]), draw);
+    /// let frame = app.render_frame();
+    /// assert_eq!(&frame[..4], &[10, 20, 30, 255]);
+    /// ```
+    pub fn render_frame(&mut self) -> Vec<u8> {
+        self.draw_frame()
+    }
+
+    /// Returns a hash of the frame `render_frame` produces for the current state
+    ///
+    /// The hashed frame includes the enabled draw passes and seed comparison grid,
+    /// as shown in the window, and is rendered without a window, so this can be used
+    /// to check that deterministic sketches render identically across refactors.
+    /// See `testing::assert_frame_hash`.
+    ///
+    /// # Examples
+    /// ```
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     let mut rng = app.fork_rng("noise");
+    ///     let mut frame = app.new_frame();
+    ///     frame.iter_mut().for_each(|v| *v = rng.next_u64() as u8);
+    ///     frame
+    /// }
+    ///
+    /// let hash = |seed| App::sketch(Config::with_dims(8, 8).seed(seed), draw).frame_hash();
+    /// assert_eq!(hash(1), hash(1));
+    /// assert_ne!(hash(1), hash(2));
+    /// ```
+    pub fn frame_hash(&mut self) -> u64 {
+        crate::testing::hash_frame(&self.render_frame())
+    }
+
+    /// Returns frame time percentiles, frames over budget and the slowest frames so far
+    ///
+    /// Frame times are measured from the start of one frame to the start of the
+    /// next, excluding warmup frames, pauses and sketches that don't loop. The
+    /// budget is `1 / fps` for the target frame rate, or 60 fps if none is set.
+    /// The same report is printed at exit.
+    pub fn pacing_report(&self) -> PacingReport {
+        let budget = Duration::from_secs_f32(1.0 / self.target_fps().unwrap_or(60.0).max(1.0));
+        self.frame_intervals.report(budget)
+    }
+
+    /// Returns the wall-clock timing of every frame exported so far
+    pub fn frame_timings(&self) -> &[FrameTiming] {
+        &self.frame_timings
+    }
+
+    /// Writes an ffmpeg concat manifest with the timing of every exported frame
+    ///
+    /// The manifest is written next to the frames as `timing_{timestamp}.ffconcat` and
+    /// can be encoded with `ffmpeg -f concat -i timing_{timestamp}.ffconcat out.mp4`.
+    /// Does nothing if no frames were exported, otherwise returns the manifest path.
+    fn write_timing_manifest(&self) -> std::io::Result<Option<PathBuf>> {
+        let Some(first) = self.frame_timings.first() else {
+            return Ok(None);
+        };
+        let dir = Path::new(&first.path).parent().unwrap_or(Path::new("."));
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut manifest = String::from("ffconcat version 1.0\n");
+        for (i, timing) in self.frame_timings.iter().enumerate() {
+            // The last frame has no successor, so reuse the previous frame's duration
+            let duration_us = timing
+                .duration_us
+                .or_else(|| {
+                    i.checked_sub(1)
+                        .and_then(|j| self.frame_timings[j].duration_us)
+                })
+                .unwrap_or(0);
+            let file = Path::new(&timing.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| timing.path.clone());
+            manifest.push_str(&format!(
+                "# frame {} t={}us\nfile '{}'\nduration {}us\n",
+                timing.frame, timing.timestamp_us, file, duration_us
+            ));
+        }
+        let path = dir.join(format!("timing_{}.ffconcat", timestamp));
+        std::fs::write(&path, manifest)?;
+        Ok(Some(path))
+    }
+
+    /// Finishes every capture sink and returns the paths of the files they wrote
+    fn finish_captures(&mut self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for capture in &mut self.captures {
+            match capture.finish() {
+                Ok(written) => paths.extend(written),
+                Err(err) => eprintln!("Failed to finish capture: {}", err),
+            }
+        }
+        paths
+    }
+
+    /// Closes the frame channel and waits until every sent frame has been written
+    fn finish_saving(&mut self) -> std::io::Result<()> {
+        self.frame_sender = None;
+        if self.dropped_frames > 0 {
+            eprintln!(
+                "Dropped {} frames because frame saving could not keep up",
+                self.dropped_frames
+            );
+            self.dropped_frames = 0;
+        }
+        match self.frame_saver.take() {
+            Some(saver) => saver
+                .join()
+                .map_err(|_| std::io::Error::other("frame saving thread panicked")),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the paths of the saved frames and of their thumbnails, if written
+    fn saved_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for timing in &self.frame_timings {
+            let path = PathBuf::from(&timing.path);
+            if let (Some(dir), Some(name)) = (path.parent(), path.file_name()) {
+                let thumbnail = dir.join("thumbnails").join(name).with_extension("png");
+                if thumbnail.is_file() {
+                    files.push(thumbnail);
+                }
+            }
+            files.push(path);
+        }
+        files
+    }
+
+    /// Periodically writes a checkpoint while saving frames and returns updated app
+    ///
+    /// Every `every` frames during a long export, the frame number and the model
+    /// serialized by `save` are written to `<title>.checkpoint` in the frames
+    /// directory (`Config::frame_dir` or `Downloads/frames`), once all earlier frames
+    /// are on disk. Running the sketch again with
+    /// `--resume` on the command line restores the model with `load` and continues
+    /// from that frame instead of starting over. Any random number generator state
+    /// must be part of the model, and the sketch should use `Config::deterministic` so
+    /// time continues where it left off. The checkpoint is removed once all
+    /// `frames_to_save` frames have been saved.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, AppMode, Config};
+    ///
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     walker: (f32, f32),
+    ///     rng_state: u64,
+    /// }
+    ///
+    /// fn save(model: &Model) -> Vec<u8> {
+    ///     let mut bytes = model.walker.0.to_le_bytes().to_vec();
+    ///     bytes.extend(model.walker.1.to_le_bytes());
+    ///     bytes.extend(model.rng_state.to_le_bytes());
+    ///     bytes
+    /// }
+    ///
+    /// fn load(bytes: &[u8]) -> Option<Model> {
+    ///     Some(Model {
+    ///         walker: (
+    ///             f32::from_le_bytes(bytes.get(0..4)?.try_into().ok()?),
+    ///             f32::from_le_bytes(bytes.get(4..8)?.try_into().ok()?),
+    ///         ),
+    ///         rng_state: u64::from_le_bytes(bytes.get(8..16)?.try_into().ok()?),
+    ///     })
+    /// }
+    ///
+    /// fn draw(app: &App<AppMode, Model>, _model: &Model) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// let model = Model { walker: (0.0, 0.0), rng_state: 7 };
+    /// let config = Config::with_dims(400, 400).set_frames_to_save(100_000).deterministic(60.0);
+    /// let app = App::app(model, config, |_, m| m, draw).checkpoint(500, save, load);
+    /// ```
+    pub fn checkpoint(mut self, every: u32, save: SaveStateFn<M>, load: LoadStateFn<M>) -> Self {
+        self.checkpoint = Some((every.max(1), save, load));
+        self
+    }
+
+    /// Restores the model and frame count from the checkpoint, if there is one
+    ///
+    /// Called automatically by `run` when `--resume` is passed on the command line.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If a checkpoint was found and restored
+    /// * `Ok(false)` - If checkpointing is not enabled or there is no checkpoint
+    /// * `Err(io::Error)` - If the checkpoint could not be read or is invalid
+    pub fn resume(&mut self) -> std::io::Result<bool> {
+        let (Some((_, _, load)), Some(path)) = (self.checkpoint, self.checkpoint_path()) else {
+            return Ok(false);
+        };
+        if !path.is_file() {
+            return Ok(false);
+        }
+        let contents = std::fs::read(&path)?;
+        let invalid = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid checkpoint {}", path.display()),
+            )
+        };
+        let split = contents
+            .windows(2)
+            .position(|w| w == b"\n\n")
+            .ok_or_else(invalid)?;
+        let header = std::str::from_utf8(&contents[..split]).map_err(|_| invalid())?;
+        let mut lines = header.lines();
+        if lines.next() != Some(CHECKPOINT_HEADER) {
+            return Err(invalid());
+        }
+        let frame = lines
+            .next()
+            .and_then(|line| line.strip_prefix("frame "))
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(invalid)?;
+        self.model = load(&contents[split + 2..]).ok_or_else(invalid)?;
+        self.frame_count = frame;
+        // Continue the interrupted sequence rather than starting a new session
+        self.session_dir = match &self.config.frame_dir {
+            Some(dir) => Some(dir.clone()),
+            None => frames_dir(&self.config)
+                .ok()
+                .and_then(|dir| latest_session_dir(&dir, &title_slug(&self.config.window_title))),
+        };
+        self.warmup_remaining = 0;
+        self.drawn_version = None;
+        Ok(true)
+    }
+
+    /// Records the run to a session file written at exit and returns updated app
+    ///
+    /// The session holds the seed, the canvas size, the frame rate, frame skipping
+    /// and export settings, the clock of every frame and every input event, so
+    /// `replay` can reproduce the run exactly. Also enabled
+    /// by passing `--record-session <path>` on the command line. See the `session`
+    /// module for the file format.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(800, 800).seed(7), draw)
+    ///     .record_session("run.artimate");
+    /// app.run().unwrap();
+    /// ```
+    pub fn record_session(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session_recording = Some((Session::default(), path.into()));
+        self
+    }
+
+    /// Plays back a session file recorded with `record_session` when the app runs
+    ///
+    /// Sets the seed and recorded config settings from the session, see
+    /// `session::Settings::apply`, and stops after its last frame. While
+    /// replaying, each frame gets its recorded time and the recorded input events
+    /// are delivered before the frames they arrived before; live input is ignored.
+    /// Cursor positions are scaled to the current canvas size, so a session can be
+    /// replayed at a larger size with frame saving enabled to re-export it at a
+    /// higher resolution. Also enabled by passing `--replay <path>`.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the session was loaded
+    /// * `Err(io::Error)` - If the file could not be read or is not a valid session
+    pub fn replay(&mut self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let session = Session::load(path)?;
+        self.config.seed = session.seed;
+        if let Some(settings) = &session.settings {
+            settings.apply(&mut self.config);
+        }
+        self.config.frames = Some(session.frames());
+        self.session_replay = Some((session, 0));
+        Ok(())
+    }
+
+    /// Returns the path of the checkpoint file in the frames directory
+    fn checkpoint_path(&self) -> Option<PathBuf> {
+        let name = title_slug(&self.config.window_title);
+        frames_dir(&self.config)
+            .ok()
+            .map(|dir| dir.join(format!("{}.checkpoint", name)))
+    }
+
+    /// Queues a checkpoint after the saving thread if one is due at the current frame
+    fn checkpoint_if_due(&self) {
+        let (Some((every, save, _)), Some(sender)) = (self.checkpoint, &self.frame_sender) else {
+            return;
+        };
+        if !self.frame_count.is_multiple_of(every) || self.frame_count >= self.save_end() {
+            return;
+        }
+        let Some(path) = self.checkpoint_path() else {
+            return;
+        };
+        let mut contents =
+            format!("{}\nframe {}\n\n", CHECKPOINT_HEADER, self.frame_count).into_bytes();
+        contents.extend(save(&self.model));
+        let message = FrameMessage::Checkpoint(contents, path);
+        if sender.0.push(message, SavePolicy::Block).is_some() {
+            eprintln!("Failed to send checkpoint: frame saving has stopped");
+        }
+    }
+
+    /// Adds a capture sink receiving frames while the app runs
+    ///
+    /// Any number of captures can run at once, each with its own frame range,
+    /// stride and stage, and each writing on its own thread. Captures are finished
+    /// after the last frame of their range or when the app exits, and the files
+    /// they wrote are passed to the `on_export_complete` hook when the app exits.
+    /// See the `capture` module for the available sinks.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use artimate::capture::{Capture, PngSequence};
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(400, 400), draw);
+    /// let sequence = PngSequence::new("frames").unwrap();
+    /// app.add_capture(Capture::new(sequence).frames(0..120).stride(2));
+    /// ```
+    pub fn add_capture(&mut self, capture: Capture) {
+        self.captures.push(CaptureWorker::spawn(capture));
+    }
+
+    /// Registers a hook called with the paths of the files written by each finished export
+    ///
+    /// The hook runs after a Cmd+S screenshot, after `export_presets`, and when the
+    /// application exits with every frame saved during the session, its thumbnails,
+    /// the timing manifest and the session archive. Render boxes can use it to push
+    /// results to storage; with the `s3` feature, `upload::S3Uploader` provides a
+    /// ready-made hook.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// let mut app = App::sketch(Config::with_dims(200, 200).set_frames_to_save(10), draw);
+    /// app.on_export_complete(|paths| println!("exported {} files", paths.len()));
+    /// ```
+    pub fn on_export_complete<F>(&mut self, hook: F)
+    where
+        F: Fn(&[PathBuf]) + 'static,
+    {
+        self.export_hook = Some(Rc::new(hook));
+    }
+
+    /// Calls the export hook, if any, with the written files
+    fn export_complete(&self, paths: &[PathBuf]) {
+        if let Some(hook) = &self.export_hook {
+            hook(paths);
+        }
+    }
+
+    /// Archives the saved frames, metadata and a source snapshot next to the frames
+    ///
+    /// Expects the frame saving thread to have finished. Returns the archive path,
+    /// or None if no frames were saved.
+    fn package_session(
+        &mut self,
+        format: Format,
+        manifest: Option<PathBuf>,
+        elapsed: f32,
+    ) -> std::io::Result<Option<PathBuf>> {
+        let Some(first) = self.frame_timings.first() else {
+            return Ok(None);
+        };
+        let dir = Path::new(&first.path)
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut entries = Vec::new();
+        for path in self.saved_files() {
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let folder = match path.parent().and_then(Path::file_name) {
+                Some(parent) if parent == "thumbnails" => "thumbnails",
+                _ => "frames",
+            };
+            entries.push((format!("{}/{}", folder, name), path));
+        }
+        if let Some(manifest) = manifest {
+            if let Some(name) = manifest.file_name() {
+                entries.push((format!("frames/{}", name.to_string_lossy()), manifest));
+            }
+        }
+
+        let metadata = dir.join(format!("session_{}.txt", timestamp));
+        std::fs::write(
+            &metadata,
+            format!(
+                "title: {}\nframes: {}\nelapsed: {} seconds\n\n{:#?}\n",
+                self.config.window_title,
+                self.frame_timings.len(),
+                elapsed,
+                self.config
+            ),
+        )?;
+        entries.push(("session.txt".to_string(), metadata.clone()));
+
+        let root = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        if root.join("Cargo.toml").is_file() {
+            entries.push(("source/Cargo.toml".to_string(), root.join("Cargo.toml")));
+        }
+        collect_files(&root.join("src"), "source/src", &mut entries)?;
+
+        let path = dir.join(format!("session_{}.{}", timestamp, format.extension()));
+        let result = write_archive(&path, format, &entries);
+        std::fs::remove_file(metadata)?;
+        result.map(|_| Some(path))
+    }
+
+    /// Maps normalized coordinates in [0, 1] to a pixel position inside the content area
+    ///
+    /// `(0, 0)` is the top-left corner of the content area and `(1, 1)` its
+    /// bottom-right corner, so drawings automatically respect `config.margin`.
+    pub fn content_point(&self, u: f32, v: f32) -> (f32, f32) {
+        self.config.content_rect().map(u, v)
+    }
+
+    delegate! {
+        to self.config {
+            pub fn content_rect(&self) -> Rect;
+            pub fn wh(&self) -> (u32, u32);
+            pub fn wh_f32(&self) -> (f32, f32);
+            pub fn w_f32(&self) -> f32;
+            pub fn h_f32(&self) -> f32;
+            pub fn aspect(&self) -> f32;
+            pub fn center(&self) -> (f32, f32);
+            pub fn norm(&self, x: f32, y: f32) -> (f32, f32);
+            pub fn denorm(&self, u: f32, v: f32) -> (f32, f32);
+        }
+    }
+
+    /// Sets the number of frames to save as PNG files and returns updated app
+    /// 
+    /// Frames are saved to a new `Downloads/frames/session_{timestamp}_{title}` directory,
+    /// or `Config::frame_dir`, named as set with `Config::frame_pattern` and
+    /// `Config::frame_numbering`.
+    /// Set to 0 to disable frame saving.
+    pub fn set_frames_to_save(mut self, frames_to_save: u32) -> Self {
+        self.config = self.config.set_frames_to_save(frames_to_save);
+        self
+    }
+
+    /// Sets cursor visibility in the window and returns updated app
+    pub fn set_cursor_visibility(mut self, cursor_visible: bool) -> Self {
+        self.config = self.config.set_cursor_visibility(cursor_visible);
+        self
+    }
+
+    /// Configures the app to render only one frame and returns updated app
+    /// 
+    /// Useful for generating static images or when you want to control
+    /// the animation loop manually.
+    pub fn no_loop(mut self) -> Self {
+        self.config = self.config.no_loop();
+        self
+    }
+
+    /// Sets the maximum number of frames to render and returns updated app
+    /// 
+    /// The application will exit after rendering this many frames.
+    pub fn set_frames(mut self, frames: u32) -> Self {
+        self.config = self.config.set_frames(frames);
+        self
+    }
+
+    /// Sets the window title and returns updated app
+    pub fn set_title(self, title: &str) -> Self {
+        Self {
+            config: self.config.set_title(title),
+            ..self
+        }
+    }
+
+    /// Sets the dimensions of the export buffer and returns updated app
+    ///
+    /// Saved frames are rendered by calling draw again at this size, independent
+    /// of the window size used for the interactive preview.
+    pub fn set_export_size(mut self, width: u32, height: u32) -> Self {
+        self.config = self.config.set_export_size(width, height);
+        self
+    }
+
+    /// Sets a separate draw function for the export buffer and returns updated app
+    ///
+    /// The export draw function sees `app.config.width` and `app.config.height` set
+    /// to the export dimensions, so it can render extra detail that would be too slow
+    /// for the live preview.
+    pub fn set_export_draw(mut self, draw: DrawFn<Mode, M>) -> Self {
+        self.export_draw = Some(draw);
+        self
+    }
+
+    /// Skips drawing while the model version is unchanged and returns updated app
+    ///
+    /// Before each frame `model_version` is called on the model; if it returns the
+    /// same value as for the last drawn frame, the draw function is not called and
+    /// the retained frame is reused without copying it; it is only uploaded to
+    /// the window again if overlays or a post shader are drawn over it, and only
+    /// saved while frames are being recorded. This cuts CPU and GPU use of paused
+    /// or idle interactive sketches to almost nothing. The version should change whenever
+    /// anything the draw function depends on changes, including time if the sketch
+    /// animates.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, AppMode, Config};
+    ///
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     points: Vec<(f32, f32)>,
     /// }
-    /// 
-    /// fn main() -> Result<(), Error> {
-    ///     let config = Config::with_dims(800, 600);
-    ///     let model = Model { position: 0.0, direction: 1.0 };
-    ///     let mut app = App::app(model, config, update, draw);
-    ///     app.run()
+    ///
+    /// fn draw(app: &App<AppMode, Model>, _model: &Model) -> Vec<u8> {
+    ///     app.new_frame()
     /// }
-    /// 
-    /// fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
-    ///     model.position += model.direction * 100.0 * (1.0 / 60.0); // 60 FPS
-    ///     if model.position > app.config.width as f32 {
-    ///         model.direction = -1.0;
-    ///     } else if model.position < 0.0 {
-    ///         model.direction = 1.0;
+    ///
+    /// let app = App::app(Model { points: vec![] }, Config::default(), |_, m| m, draw)
+    ///     .model_version(|model| model.points.len() as u64);
+    /// ```
+    pub fn model_version(mut self, model_version: VersionFn<M>) -> Self {
+        self.model_version = Some(model_version);
+        self
+    }
+
+    /// Registers a named draw pass and returns updated app
+    ///
+    /// Passes run in registration order after the draw function, each drawing into
+    /// the buffer it returned, so a sketch can split its work into layers such as
+    /// `background`, `main` and `overlay`. Passes can be switched on and off while the
+    /// sketch runs with `set_pass_enabled` or `toggle_pass`, which makes it easy to
+    /// skip expensive layers during iteration, and the time spent in each one is
+    /// reported by `pass_timings`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// fn background(app: &App, _model: &()) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// fn stars(_app: &App, _model: &(), frame: &mut [u8]) {
+    ///     frame[..4].copy_from_slice(&[255, 255, 255, 255]);
+    /// }
+    ///
+    /// let mut app = App::sketch(Config::with_dims(200, 200), background).add_pass("stars", stars);
+    /// app.on_key_press(Key::Character("1".into()), |app| app.toggle_pass("stars"));
+    /// ```
+    pub fn add_pass(mut self, name: &str, draw: PassFn<Mode, M>) -> Self {
+        self.passes.push(Pass {
+            draw,
+            timing: PassTiming {
+                name: name.to_string(),
+                enabled: true,
+                ..PassTiming::default()
+            },
+        });
+        self
+    }
+
+    /// Enables or disables every pass registered under `name`
+    pub fn set_pass_enabled(&mut self, name: &str, enabled: bool) {
+        self.drawn_version = None;
+        self.passes
+            .iter_mut()
+            .filter(|pass| pass.timing.name == name)
+            .for_each(|pass| pass.timing.enabled = enabled);
+    }
+
+    /// Flips whether every pass registered under `name` runs
+    pub fn toggle_pass(&mut self, name: &str) {
+        self.drawn_version = None;
+        self.passes
+            .iter_mut()
+            .filter(|pass| pass.timing.name == name)
+            .for_each(|pass| pass.timing.enabled = !pass.timing.enabled);
+    }
+
+    /// Registers a named debug view and returns updated app
+    ///
+    /// Debug views render auxiliary buffers, such as a raw noise field or a velocity
+    /// field, that are shown as small labeled insets along the right edge of the
+    /// preview so internal state can be inspected next to the artwork. Each view can
+    /// be shown or hidden with `set_debug_view_visible` or `toggle_debug_view`. Views
+    /// are only rendered while visible and are never saved or exported.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use artimate::app::{App, Config};
+    /// use artimate::buffer::Image;
+    /// use winit::keyboard::Key;
+    ///
+    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// fn gradient(_app: &App, _model: &()) -> Image {
+    ///     let mut image = Image::new(64, 64);
+    ///     for y in 0..64 {
+    ///         for x in 0..64 {
+    ///             image.set_pixel(x, y, [(x * 4) as u8, (y * 4) as u8, 0, 255]);
+    ///         }
+    ///     }
+    ///     image
+    /// }
+    ///
+    /// let mut app = App::sketch(Config::with_dims(200, 200), draw)
+    ///     .add_debug_view("field", gradient);
+    /// app.on_key_press(Key::Character("v".into()), |app| app.toggle_debug_view("field"));
+    /// ```
+    pub fn add_debug_view(mut self, name: &str, draw: DebugViewFn<Mode, M>) -> Self {
+        self.debug_views.push(DebugView {
+            name: name.to_string(),
+            draw,
+            visible: true,
+        });
+        self
+    }
+
+    /// Shows or hides every debug view registered under `name`
+    pub fn set_debug_view_visible(&mut self, name: &str, visible: bool) {
+        self.debug_views
+            .iter_mut()
+            .filter(|view| view.name == name)
+            .for_each(|view| view.visible = visible);
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Flips whether every debug view registered under `name` is shown
+    pub fn toggle_debug_view(&mut self, name: &str) {
+        self.debug_views
+            .iter_mut()
+            .filter(|view| view.name == name)
+            .for_each(|view| view.visible = !view.visible);
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Renders the visible debug views with their names
+    fn debug_insets(&self) -> Vec<(String, Image)> {
+        self.debug_views
+            .iter()
+            .filter(|view| view.visible)
+            .map(|view| (view.name.clone(), (view.draw)(self, &self.model)))
+            .collect()
+    }
+
+    /// Returns the timing statistics of every pass, in the order they run
+    pub fn pass_timings(&self) -> Vec<PassTiming> {
+        self.passes.iter().map(|pass| pass.timing.clone()).collect()
+    }
+
+    /// Renders the sketch with 2 to 4 different seeds side by side and returns updated app
+    ///
+    /// Each frame, `seed_fn` is applied to a copy of the current model for every seed
+    /// and the variants are drawn into a grid of tiles filling the window (2 side by
+    /// side, 3 or 4 in a 2x2 grid). The update function still runs once per frame on
+    /// `app.model`, so handlers and animation stay shared between the variants.
+    /// Use `choose_seed` to commit to one of them.
+    ///
+    /// # Panics
+    /// If fewer than 2 or more than 4 seeds are given.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    ///
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     seed: u64,
+    /// }
+    ///
+    /// fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+    ///     let shade = (model.seed * 60) as u8;
+    ///     vec![shade; (app.config.width * app.config.height * 4) as usize]
+    /// }
+    ///
+    /// let app = App::app(Model { seed: 0 }, Config::default(), |_, m| m, draw)
+    ///     .compare_seeds(&[1, 2, 3, 4], |model, seed| model.seed = seed);
+    /// ```
+    pub fn compare_seeds(mut self, seeds: &[u64], seed_fn: SeedFn<M>) -> Self {
+        assert!(
+            (2..=4).contains(&seeds.len()),
+            "compare_seeds needs 2 to 4 seeds, got {}",
+            seeds.len()
+        );
+        self.compare_seeds = seeds.to_vec();
+        self.seed_fn = Some(seed_fn);
+        self
+    }
+
+    /// Applies the seed shown in tile `index` to the model and leaves seed comparison mode
+    ///
+    /// Tiles are numbered left to right, top to bottom. Does nothing if the app is not
+    /// comparing seeds or `index` is out of range.
+    pub fn choose_seed(&mut self, index: usize) {
+        if let (Some(&seed), Some(seed_fn)) = (self.compare_seeds.get(index), self.seed_fn) {
+            seed_fn(&mut self.model, seed);
+            self.compare_seeds.clear();
+        }
+    }
+
+    /// Returns the seeds being compared, empty when seed comparison mode is off
+    pub fn compared_seeds(&self) -> &[u64] {
+        &self.compare_seeds
+    }
+
+    /// Calls the draw function for the current frame, tiling the variants when comparing seeds
+    fn draw_frame(&mut self) -> Vec<u8> {
+        match self.seed_fn {
+            Some(seed_fn) if !self.compare_seeds.is_empty() => {
+                let frame = self.draw_seed_grid(seed_fn);
+                // Regions marked by the tiles are not regions of the grid
+                self.dirty_rects.get_mut().clear();
+                frame
+            }
+            _ => self.render(None),
+        }
+    }
+
+    /// Advances the model `frames` frames without a window and renders the export frame
+    ///
+    /// Time advances by exactly one frame per step at the deterministic or target
+    /// frame rate, or 60 fps if neither is set, so the result is reproducible.
+    pub(crate) fn render_headless(&mut self, frames: u32) -> (Vec<u8>, u32, u32) {
+        self.advance_headless(0..frames);
+        self.render_headless_frame(frames)
+    }
+
+    /// Runs the update function once for each frame in `frames` with deterministic time
+    pub(crate) fn advance_headless(&mut self, frames: std::ops::Range<u32>) {
+        for frame in frames {
+            self.set_headless_clock(frame);
+            if let Some(update) = self.update {
+                self.model = update(self, self.model.clone());
+            }
+        }
+    }
+
+    /// Renders the export frame for frame number `frame` of a headless run
+    pub(crate) fn render_headless_frame(&mut self, frame: u32) -> (Vec<u8>, u32, u32) {
+        self.set_headless_clock(frame);
+        // The export frame is drawn separately at its own size
+        let preview = match self.config.export_size {
+            None if self.config.supersample <= 1 => self.render(None),
+            _ => Vec::new(),
+        };
+        self.export_frame(&preview, self.config.capture_stage)
+    }
+
+    /// Sets `frame_count` and `time` as they are on frame `frame` of a deterministic run
+    fn set_headless_clock(&mut self, frame: u32) {
+        let fps = self
+            .config
+            .deterministic
+            .or(self.config.fps)
+            .unwrap_or(60.0);
+        self.frame_count = frame;
+        self.time = frame as f32 / fps;
+    }
+
+    /// Prints the explanation of a failure once, if `Config::verbose_diagnostics` is enabled
+    fn diagnose(&mut self, diagnostic: Diagnostic) {
+        if self.config.verbose_diagnostics && self.reported_diagnostics.insert(diagnostic) {
+            eprintln!("hint: {}", diagnostic.hint());
+        }
+    }
+
+    /// Builds the renderer for linear scaling or a post shader, if either is configured
+    ///
+    /// A post shader that fails to compile is reported and left out.
+    fn build_surface_renderer(
+        &mut self,
+        pixels: &Pixels,
+        size: PhysicalSize<u32>,
+        clear_color: wgpu::Color,
+        blend_state: wgpu::BlendState,
+    ) -> Option<SurfaceRenderer> {
+        let filter = self.config.scaling_filter;
+        let size = (size.width, size.height);
+        let new = |shader: Option<&str>| {
+            SurfaceRenderer::new(pixels, size, clear_color, blend_state, filter, shader)
+        };
+        if let Some(shader) = self.config.post_shader.clone() {
+            match new(Some(&shader)) {
+                Ok(renderer) => return Some(renderer),
+                Err(err) => {
+                    eprintln!(
+                        "Failed to compile post shader, showing frames without it: {}",
+                        err
+                    );
+                    self.diagnose(Diagnostic::PostShader);
+                }
+            }
+        }
+        match filter {
+            Filter::Nearest => None,
+            Filter::Linear => new(None).ok(),
+        }
+    }
+
+    /// Calls `draw`, then runs the enabled passes over its output
+    fn render(&mut self, draw: Option<DrawFn<Mode, M>>) -> Vec<u8> {
+        self.arena.reset();
+        self.dirty_rects.get_mut().clear();
+        let buffer = match draw {
+            None => std::mem::take(&mut self.frame_buffer),
+            Some(_) => Vec::new(),
+        };
+        let mut frame = self.call_draw(draw, buffer);
+        let (width, height) = self.config.wh();
+        let expected = (width * height * 4) as usize;
+        if frame.len() != expected {
+            self.diagnose(Diagnostic::FrameSize);
+            debug_assert_eq!(
+                frame.len(),
+                expected,
+                "draw function returned {} bytes but a {}x{} frame needs {}; \
+                 allocate it with `app.new_frame()` or size it from `app.config`",
+                frame.len(),
+                width,
+                height,
+                expected
+            );
+            if !self.size_mismatch_reported {
+                eprintln!(
+                    "Draw function returned {} bytes, expected {} for {}x{}; fitting frame",
+                    frame.len(),
+                    expected,
+                    width,
+                    height
+                );
+                self.size_mismatch_reported = true;
+            }
+            let source = Some(self.drawn_size)
+                .filter(|&(w, h)| (w as usize) * (h as usize) * 4 == frame.len());
+            frame = fit_frame(frame, source, width, height, self.config.clear_color);
+            self.dirty_rects.get_mut().clear();
+        } else {
+            self.drawn_size = (width, height);
+        }
+        if self.config.alpha_mode == AlphaMode::Premultiplied {
+            unpremultiply(&mut frame);
+        }
+        if self.keeps_raw_frame() {
+            self.raw_frame.clone_from(&frame);
+        }
+        if cfg!(debug_assertions) && self.config.check_frames {
+            let transparent = self.config.transparent || self.config.transparent_export;
+            for issue in check_frame(&frame, width) {
+                if transparent && issue == FrameIssue::Transparent {
+                    continue;
+                }
+                let kind = std::mem::discriminant(&issue);
+                if !self.reported_frame_issues.contains(&kind) {
+                    eprintln!("Frame check: {}", issue);
+                    self.reported_frame_issues.push(kind);
+                }
+            }
+        }
+        for i in 0..self.passes.len() {
+            if !self.passes[i].timing.enabled {
+                continue;
+            }
+            let start = Instant::now();
+            // Passes may change any pixel
+            self.dirty_rects.get_mut().clear();
+            (self.passes[i].draw)(self, &self.model, &mut frame);
+            let timing = &mut self.passes[i].timing;
+            timing.last = start.elapsed();
+            timing.total += timing.last;
+            timing.calls += 1;
+        }
+        frame
+    }
+
+    /// Calls `draw`, or the sketch's own draw function if None, returning the frame
+    ///
+    /// In-place draw functions draw into `buffer`, which is resized and cleared as needed.
+    fn call_draw(&self, draw: Option<DrawFn<Mode, M>>, mut buffer: Vec<u8>) -> Vec<u8> {
+        match (draw, self.draw_in_place) {
+            (None, Some(draw_in_place)) => {
+                let len = (self.config.width * self.config.height * 4) as usize;
+                if buffer.len() != len {
+                    buffer = self.new_frame();
+                } else if self.config.auto_clear {
+                    clear(&mut buffer, self.config.clear_color);
+                }
+                draw_in_place(self, &self.model, &mut buffer);
+                buffer
+            }
+            (draw, _) => draw.unwrap_or(self.draw)(self, &self.model),
+        }
+    }
+
+    /// Draws every compared seed into its own tile of a single window-sized frame
+    fn draw_seed_grid(&mut self, seed_fn: SeedFn<M>) -> Vec<u8> {
+        let (width, height) = self.config.wh();
+        let cols = 2;
+        let rows = if self.compare_seeds.len() > 2 { 2 } else { 1 };
+        let (tile_w, tile_h) = (width / cols, height / rows);
+        let mut frame = vec![0u8; (width * height * 4) as usize];
+
+        for (i, seed) in self.compare_seeds.clone().into_iter().enumerate() {
+            let mut variant = self.model.clone();
+            seed_fn(&mut variant, seed);
+            let original = std::mem::replace(&mut self.model, variant);
+            let tile = self.draw_at_size(None, tile_w, tile_h);
+            self.model = original;
+            if tile.len() != (tile_w * tile_h * 4) as usize {
+                continue;
+            }
+
+            let (x0, y0) = ((i as u32 % cols) * tile_w, (i as u32 / cols) * tile_h);
+            let to = (x0 as i32, y0 as i32);
+            copy_rect(&mut frame, width, &tile, tile_w, (0, 0, tile_w, tile_h), to);
+        }
+        frame
+    }
+
+    /// Renders the current frame and saves it to `Config::screenshot_dir`
+    ///
+    /// The frame is cropped to `Config::screenshot_region`, if set.
+    fn save_screenshot(&mut self) -> std::io::Result<PathBuf> {
+        let stage = self.config.capture_stage;
+        let composited = self.composited_frame(stage);
+        let (mut frame_data, mut width, mut height) = match composited {
+            Some(frame) => (frame, self.config.width, self.config.height),
+            None => {
+                // The export frame is drawn separately at its own size
+                let preview = match self.config.export_size {
+                    None if self.config.supersample <= 1 => self.draw_frame(),
+                    _ => Vec::new(),
+                };
+                self.export_frame(&preview, stage)
+            }
+        };
+        if let Some((x0, y0, x1, y1)) = self.config.screenshot_region {
+            let (x0, x1) = (x0.min(width), x1.min(width));
+            let (y0, y1) = (y0.min(height), y1.min(height));
+            if x0 >= x1 || y0 >= y1 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "screenshot region is outside the frame",
+                ));
+            }
+            let mut region = vec![0; ((x1 - x0) * (y1 - y0) * 4) as usize];
+            copy_rect(
+                &mut region,
+                x1 - x0,
+                &frame_data,
+                width,
+                (x0, y0, x1, y1),
+                (0, 0),
+            );
+            (frame_data, width, height) = (region, x1 - x0, y1 - y0);
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let path = screenshot_dir(&self.config)?.join(format!("artmate_{}.png", timestamp));
+        save_frame(
+            frame_data,
+            path.to_string_lossy().to_string(),
+            width,
+            height,
+            &self.config.screenshot_options(),
+        )?;
+        self.export_complete(std::slice::from_ref(&path));
+        Ok(path)
+    }
+
+    /// Renders the current frame once per preset size and saves each as a PNG file
+    ///
+    /// The draw function is called again for every preset with `config.width` and
+    /// `config.height` set to the preset dimensions, so resolution-independent sketches
+    /// produce a native render at each size rather than a rescaled copy. Files are
+    /// written to `Config::screenshot_dir`, Downloads/artmate by default.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<PathBuf>)` - The paths of the saved files, in the order of `presets`
+    /// * `Err(io::Error)` - If the output directory or a file could not be written
+    pub fn export_presets(&mut self, presets: &[Preset]) -> std::io::Result<Vec<PathBuf>> {
+        let dir = screenshot_dir(&self.config)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let draw = self.export_draw;
+        let mut paths = Vec::with_capacity(presets.len());
+        for preset in presets {
+            let (width, height) = preset.dims();
+            let frame = self.draw_at_size(draw, width, height);
+            let mut frame = self.captured(frame, self.config.capture_stage);
+            self.flatten_alpha(&mut frame);
+            let path = dir.join(format!("artmate_{}_{}.png", timestamp, preset.name()));
+            save_frame(
+                frame,
+                path.to_string_lossy().to_string(),
+                width,
+                height,
+                &self.config.screenshot_options(),
+            )?;
+            paths.push(path);
+        }
+        self.export_complete(&paths);
+        Ok(paths)
+    }
+
+    /// Shows or hides the configured composition guides
+    pub fn toggle_guides(&mut self) {
+        self.guides_visible = !self.guides_visible;
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Sets whether the window floats above other windows while running
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.config.always_on_top = always_on_top;
+        if let Some(window) = &self.window {
+            window.set_window_level(window_level(always_on_top));
+        }
+    }
+
+    /// Makes the window float above other windows, or stop floating if it does
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(200, 200).always_on_top(true), draw);
+    /// app.on_key_press(Key::Character("t".into()), |app| app.toggle_always_on_top());
+    /// ```
+    pub fn toggle_always_on_top(&mut self) {
+        self.set_always_on_top(!self.config.always_on_top);
+    }
+
+    /// Starts or stops recording a take, also toggled with Cmd+R by default
+    ///
+    /// See `start_recording` for where takes are saved.
+    pub fn toggle_recording(&mut self) {
+        if self.take.is_some() {
+            self.stop_recording();
+        } else {
+            self.start_recording();
+        }
+    }
+
+    /// Starts recording a take with the next frame drawn, unless one is being recorded
+    ///
+    /// Each take saves every frame drawn while recording into its own numbered
+    /// directory, `Downloads/frames/take_001`, `take_002` and so on, continuing
+    /// after the highest existing take, or into a GIF of the same name with
+    /// `Config::take_format`. A recording indicator is shown over the preview; it
+    /// is never saved. Unlike `Config::set_frames_to_save`, recording can start at
+    /// any moment and runs until `stop_recording` is called or the app exits.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    /// use winit::keyboard::Key;
+    ///
+    /// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::sketch(Config::with_dims(400, 400), draw);
+    /// app.on_key_press(Key::Character("1".into()), |app| app.start_recording());
+    /// app.on_key_press(Key::Character("2".into()), |app| app.stop_recording());
+    /// ```
+    pub fn start_recording(&mut self) {
+        if self.take.is_some() {
+            return;
+        }
+        let dir = match frames_dir(&self.config) {
+            Ok(dir) => dir,
+            Err(err) => {
+                eprintln!("Failed to start recording: {}", err);
+                return;
+            }
+        };
+        let number = next_take_number(&dir);
+        let path = match self.config.take_format {
+            TakeFormat::Png => {
+                self.start_saving(u32::MAX);
+                dir.join(format!("take_{:03}", number))
+            }
+            TakeFormat::Gif => {
+                let path = dir.join(format!("take_{:03}.gif", number));
+                let fps = self
+                    .config
+                    .deterministic
+                    .or(self.target_fps())
+                    .unwrap_or(50.0);
+                let capture = Capture::new(GifAnimation::new(&path, fps))
+                    .frames(self.frame_count..u32::MAX)
+                    .stage(self.config.capture_stage);
+                self.take_capture = Some((self.captures.len(), self.frame_count));
+                self.add_capture(capture);
+                path
+            }
+        };
+        self.take = Some((number, path));
+        println!("Recording take {}", number);
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Stops recording the current take, if any, and saves it
+    ///
+    /// The last frame drawn is the last frame of the take. A GIF take is written
+    /// before this returns, and its path is passed to the `on_export_complete` hook.
+    pub fn stop_recording(&mut self) {
+        let Some((number, path)) = self.take.take() else {
+            return;
+        };
+        if let Some((i, start)) = self.take_capture.take() {
+            let frames = self.frame_count.saturating_sub(start);
+            match self.captures[i].finish() {
+                Ok(written) => {
+                    println!(
+                        "Take {} saved {} frames to {}",
+                        number,
+                        frames,
+                        path.display()
+                    );
+                    self.export_complete(&written);
+                }
+                Err(err) => eprintln!("Failed to save take {}: {}", number, err),
+            }
+        } else {
+            let frames = self.frame_count.saturating_sub(self.save_start);
+            self.stop_saving();
+            println!(
+                "Take {} saved {} frames to {}",
+                number,
+                frames,
+                path.display()
+            );
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Returns true if a take is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.take.is_some()
+    }
+
+    /// Returns the number of the take being recorded, if any
+    pub fn recording_take(&self) -> Option<u32> {
+        self.take.as_ref().map(|(number, _)| *number)
+    }
+
+    /// Shows or hides the hotkey help overlay, also toggled with `?`
+    pub fn toggle_help(&mut self) {
+        self.help_visible = !self.help_visible;
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Pauses or resumes the sketch, also toggled with Cmd+P
+    ///
+    /// While paused nothing is drawn or updated and `app.time` stands still. If a
+    /// frame history is configured, the retained frames can be scrubbed.
+    pub fn toggle_pause(&mut self) {
+        match self.paused.take() {
+            Some(paused_at) => {
+                self.start_time += paused_at.elapsed();
+                self.last_frame = None;
+                self.scrubbing = false;
+            }
+            None => {
+                self.paused = Some(Instant::now());
+                self.history_cursor = self.history.len().saturating_sub(1);
+            }
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Returns true if the sketch is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Returns the retained frame history as (frame number, image) pairs, oldest first
+    ///
+    /// Empty unless `Config::frame_history` is set.
+    pub fn history(&self) -> impl Iterator<Item = (u32, &Image)> {
+        self.history.iter().map(|(frame, image)| (*frame, image))
+    }
+
+    /// Shows the retained frame at `index` while paused, clamped to the history
+    fn scrub_to(&mut self, index: usize) {
+        self.history_cursor = index.min(self.history.len().saturating_sub(1));
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Scrubs to the history frame under the mouse x position
+    fn scrub_to_mouse(&mut self) {
+        let x = (self.mouse_position.0 / self.config.width.max(1) as f32).clamp(0.0, 1.0);
+        let last = self.history.len().saturating_sub(1);
+        self.scrub_to((x * last as f32).round() as usize);
+    }
+
+    /// Adds a downscaled copy of a drawn frame to the history, dropping the oldest
+    fn record_history(&mut self, frame: &[u8]) {
+        let Some((frames, max_edge)) = self.config.frame_history else {
+            return;
+        };
+        let (width, height) = self.config.wh();
+        if frames == 0 || frame.len() != (width * height * 4) as usize {
+            return;
+        }
+        let image = Image::from_raw(width, height, frame.to_vec())
+            .expect("frame size matches config")
+            .thumbnail(max_edge);
+        if self.history.len() == frames {
+            self.history.pop_front();
+        }
+        self.history.push_back((self.frame_count, image));
+    }
+
+    /// Presents the history frame under the scrub cursor with the scrub bar
+    fn present_history(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let (width, height) = self.config.wh();
+        let theme = self.config.overlay_theme;
+        let len = self.history.len();
+        let Some(pixels) = self.pixels.as_mut() else {
+            return;
+        };
+        self.pixels_hold_frame = false;
+        let frame = pixels.frame_mut();
+        // Without a history the last presented frame stays on screen
+        if let Some((number, image)) = self.history.get(self.history_cursor) {
+            blit_scaled(frame, width, height, image);
+            let position = (self.history_cursor, len, *number);
+            draw_scrub_bar(frame, width, height, position, theme);
+        }
+        if let Err(err) = render_pixels(pixels, self.surface_renderer.as_ref(), self.time) {
+            eprintln!("Failed to render frame: {}", err);
+            self.diagnose(Diagnostic::Surface);
+            event_loop.exit();
+        }
+    }
+
+    /// Returns every registered hotkey and mouse binding, including the built-in ones
+    ///
+    /// Built-in bindings come first, followed by the registered handlers sorted by
+    /// input name. This is the list shown by the help overlay toggled with `?`.
+    pub fn bindings(&self) -> Vec<Binding> {
+        let builtin = |input: &str, description: &str| Binding {
+            input: input.to_string(),
+            trigger: Trigger::Press,
+            action: None,
+            description: Some(description.to_string()),
+        };
+        let mut bindings = vec![
+            builtin("Cmd+G", "Toggle guides"),
+            builtin("Cmd+P", "Pause / resume"),
+            builtin("?", "Toggle this help"),
+        ];
+        if let Some((key, modifiers)) = &self.config.screenshot_key {
+            bindings.insert(0, builtin(&chord_name(key, *modifiers), "Save screenshot"));
+        }
+        if let Some(key) = self.config.record_key {
+            let input = format!("Cmd+{}", key.to_uppercase());
+            let help = bindings.len() - 1;
+            bindings.insert(help, builtin(&input, "Start / stop recording a take"));
+        }
+        if self.config.frame_history.is_some() {
+            bindings.push(builtin("Left/Right", "Scrub history while paused"));
+        }
+        let keys = [
+            (&self.key_press_handlers, Trigger::Press),
+            (&self.key_handlers, Trigger::Held),
+            (&self.key_release_handlers, Trigger::Release),
+        ];
+        let chords = self
+            .key_chord_handlers
+            .keys()
+            .map(|(key, modifiers)| Binding {
+                input: chord_name(key, *modifiers),
+                trigger: Trigger::Press,
+                action: None,
+                description: None,
+            });
+        let buttons = [
+            (&self.mouse_handlers, Trigger::Click),
+            (&self.mouse_release_handlers, Trigger::Release),
+            (&self.mouse_double_click_handlers, Trigger::DoubleClick),
+        ];
+        let drags = self
+            .mouse_drag_handlers
+            .keys()
+            .map(|button| (button, Trigger::Drag));
+        let mouse = buttons
+            .into_iter()
+            .flat_map(|(handlers, trigger)| handlers.keys().map(move |button| (button, trigger)))
+            .chain(drags)
+            .map(|(button, trigger)| Binding {
+                input: mouse_button_name(button),
+                trigger,
+                action: None,
+                description: None,
+            });
+        let mut registered: Vec<Binding> = keys
+            .iter()
+            .flat_map(|(handlers, trigger)| {
+                handlers.keys().map(|key| Binding {
+                    input: key_name(key),
+                    trigger: *trigger,
+                    action: None,
+                    description: self.key_descriptions.get(key).cloned(),
+                })
+            })
+            .chain(chords)
+            .chain(mouse)
+            .chain(self.actions.iter().map(|(name, input)| Binding {
+                input: input.to_string(),
+                trigger: match input {
+                    Input::Key(_) => Trigger::Press,
+                    Input::Mouse(_) => Trigger::Click,
+                },
+                action: Some(name.clone()),
+                description: match input {
+                    Input::Key(key) => self.key_descriptions.get(key).cloned(),
+                    Input::Mouse(_) => None,
+                },
+            }))
+            .collect();
+        registered.sort_by(|a, b| (&a.input, a.trigger as u8).cmp(&(&b.input, b.trigger as u8)));
+        bindings.extend(registered);
+        bindings
+    }
+
+    /// Returns a plain text cheat sheet of every binding, headed by the window title
+    ///
+    /// Lists the same controls as the help overlay. See `overlay::cheat_sheet`.
+    pub fn help_text(&self) -> String {
+        let title = format!("{} controls", self.config.window_title);
+        cheat_sheet(&title, &self.bindings())
+    }
+
+    /// Returns every binding as a Markdown table
+    ///
+    /// Running a sketch with `--keys` prints this table and exits without opening
+    /// a window, so the controls of an example can be pasted into its documentation.
+    pub fn help_markdown(&self) -> String {
+        bindings_markdown(&self.bindings())
+    }
+
+    /// Prints the cheat sheet returned by `help_text` to stdout
+    pub fn print_help(&self) {
+        println!("{}", self.help_text());
+    }
+
+    /// Sets the description of a key shown by `bindings` and the help overlay
+    ///
+    /// # Arguments
+    /// * `key` - The key the description applies to, for all of its handlers
+    /// * `description` - Short text describing what the key does
+    pub fn describe_key(&mut self, key: Key, description: &str) {
+        self.key_descriptions.insert(key, description.to_string());
+    }
+
+    /// Calls `draw`, or the sketch's own draw function if None, at `width` x `height`
+    ///
+    /// The config is temporarily resized for the call.
+    fn draw_at_size(&mut self, draw: Option<DrawFn<Mode, M>>, width: u32, height: u32) -> Vec<u8> {
+        let (w, h) = self.config.wh();
+        self.config.width = width;
+        self.config.height = height;
+        let frame = self.render(draw);
+        self.config.width = w;
+        self.config.height = h;
+        frame
+    }
+
+    /// Returns the frame to be saved along with its width and height
+    ///
+    /// If an export size or supersampling is configured the export buffer is
+    /// rendered now, otherwise the preview frame is used as is.
+    fn export_frame(&mut self, preview: &[u8], stage: CaptureStage) -> (Vec<u8>, u32, u32) {
+        let factor = self.config.supersample;
+        let draw = self.export_draw;
+        let (frame, width, height) = match self.config.export_size {
+            _ if factor > 1 => {
+                let (width, height) = self.config.export_size.unwrap_or(self.config.wh());
+                match supersampled_size(width, height, factor) {
+                    Some((large_width, large_height)) => {
+                        let frame = self.draw_at_size(draw, large_width, large_height);
+                        let large = Image::from_raw(large_width, large_height, frame)
+                            .expect("render returns frames of the configured size");
+                        (large.downsample(factor).into_raw(), width, height)
+                    }
+                    None => {
+                        if !self.supersample_reported {
+                            eprintln!(
+                                "Supersampling {}x{} by {} is too large; saving without it",
+                                width, height, factor
+                            );
+                            self.supersample_reported = true;
+                        }
+                        (self.draw_at_size(draw, width, height), width, height)
+                    }
+                }
+            }
+            Some((width, height)) => (self.draw_at_size(draw, width, height), width, height),
+            None => (preview.to_vec(), self.config.width, self.config.height),
+        };
+        let mut frame = self.captured(frame, stage);
+        self.flatten_alpha(&mut frame);
+        (frame, width, height)
+    }
+
+    /// Returns true if the draw output before the passes is needed by a capture
+    fn keeps_raw_frame(&self) -> bool {
+        self.config.capture_stage == CaptureStage::Raw
+            || self.captures.iter().any(|c| c.stage == CaptureStage::Raw)
+    }
+
+    /// Returns the latest draw output before the passes if `stage` is `CaptureStage::Raw`, else `post`
+    ///
+    /// Seed comparison grids are assembled from several renders, so they are
+    /// always captured after the passes.
+    fn captured(&self, post: Vec<u8>, stage: CaptureStage) -> Vec<u8> {
+        let comparing = self.seed_fn.is_some() && !self.compare_seeds.is_empty();
+        if stage == CaptureStage::Raw && !comparing && self.raw_frame.len() == post.len() {
+            self.raw_frame.clone()
+        } else {
+            post
+        }
+    }
+
+    /// Makes a frame fully opaque if opaque export is enabled and transparent export is not
+    fn flatten_alpha(&self, frame: &mut [u8]) {
+        if self.config.opaque_export && !self.config.transparent_export {
+            frame.iter_mut().skip(3).step_by(4).for_each(|a| *a = 255);
+        }
+    }
+
+    /// Registers a handler function for when a key is held down
+    ///
+    /// # Arguments
+    /// * `key` - The key to watch for
+    /// * `handler` - The function to call while the key is held
+    pub fn on_key_held<F>(&mut self, key: Key, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.key_handlers.insert(key, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a key is initially pressed
+    ///
+    /// # Arguments
+    /// * `key` - The key to watch for
+    /// * `handler` - The function to call when the key is pressed
+    pub fn on_key_press<F>(&mut self, key: Key, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.key_press_handlers.insert(key, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a key is pressed with exactly the given modifiers
+    ///
+    /// When the held modifiers match, this handler runs instead of the one
+    /// registered for the plain key with `on_key_press`, so one key can do
+    /// different things with and without modifiers. Shift changes the logical key
+    /// of characters, so register `"A"` rather than `"a"` with `ModifiersState::SHIFT`.
+    ///
+    /// # Arguments
+    /// * `key` - The key to watch for
+    /// * `modifiers` - The modifiers that must be held, and no others
+    /// * `handler` - The function to call when the key is pressed
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::keyboard::{Key, ModifiersState, NamedKey};
+    ///
+    /// # fn draw(app: &App<AppMode, f32>, _model: &f32) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::app(1.0, Config::with_dims(400, 400), |_, m| m, draw);
+    /// let up = Key::Named(NamedKey::ArrowUp);
+    /// app.on_key_press(up.clone(), |app| app.model += 0.1);
+    /// app.on_key_press_with(up, ModifiersState::SHIFT, |app| app.model += 1.0);
+    /// ```
+    pub fn on_key_press_with<F>(&mut self, key: Key, modifiers: ModifiersState, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.key_chord_handlers
+            .insert((key, modifiers), Rc::new(handler));
+    }
+
+    /// Registers a handler called when a file or directory changes while the app runs
+    ///
+    /// The path is polled a few times a second and directories are watched
+    /// recursively. The handler receives the changed path and can reload
+    /// parameters or assets into the model without restarting the sketch; a redraw
+    /// is requested afterwards. Requires the `watch` feature.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, Config};
+    ///
+    /// # fn draw(app: &App<artimate::app::AppMode, f32>, _model: &f32) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::app(1.0, Config::with_dims(400, 400), |_, m| m, draw);
+    /// app.on_file_change("radius.txt", |app, path| {
+    ///     if let Ok(text) = std::fs::read_to_string(path) {
+    ///         app.model = text.trim().parse().unwrap_or(app.model);
     ///     }
-    ///     model
-    /// }
-    /// 
-    /// fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
-    ///     // Generate pixel data based on model
-    ///     vec![255; (app.config.width * app.config.height * 4) as usize]
-    /// }
+    /// });
     /// ```
-    pub fn app(
-        model: M,
-        config: Config,
-        update: fn(&App<AppMode, M>, M) -> M,
-        draw: fn(&App<AppMode, M>, &M) -> Vec<u8>,
-    ) -> Self {
-        let maybe_tx = if config.frames_to_save > 0 {
-            setup_frame_sender()
-        } else {
-            None
-        };
+    #[cfg(feature = "watch")]
+    pub fn on_file_change<F>(&mut self, path: impl Into<PathBuf>, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, &Path) + 'static,
+    {
+        self.file_watches
+            .push((Watcher::new([path.into()]), Rc::new(handler)));
+    }
 
-        Self {
-            model,
-            config,
-            update: Some(update),
-            draw,
-            time: 0.0,
-            frame_count: 0,
-            window: None,
-            pixels: None,
-            start_time: Instant::now(),
-            mouse_position: (0.0, 0.0),
-            frame_sender: maybe_tx,
-            key_handlers: HashMap::new(),
-            mouse_handlers: HashMap::new(),
-            key_press_handlers: HashMap::new(),
-            key_release_handlers: HashMap::new(),
-            keys_down: HashSet::new(),
-            modifiers: Modifiers::default(),
-            _mode: PhantomData,
+    /// Calls the handlers of watched files that changed since the last poll
+    #[cfg(feature = "watch")]
+    fn poll_file_watches(&mut self) {
+        let mut changed = Vec::new();
+        for (watcher, handler) in &mut self.file_watches {
+            changed.extend(
+                watcher
+                    .poll()
+                    .into_iter()
+                    .map(|path| (path, handler.clone())),
+            );
+        }
+        for (path, handler) in &changed {
+            handler(self, path);
+        }
+        if !changed.is_empty() {
+            self.drawn_version = None;
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
         }
     }
-}
 
-/// Common methods for both sketch and app modes
-impl<Mode, M> App<Mode, M>
-where
-    M: Clone,
-{
-    /// Starts the application's main loop and runs until the window is closed
+    /// Registers a handler function for when a key is released
     ///
-    /// This method creates the window, initializes the rendering context, and begins
-    /// the main event loop. It handles window events, updates the model (if in AppMode),
-    /// calls the draw function, and renders the result to the screen.
+    /// # Arguments
+    /// * `key` - The key to watch for
+    /// * `handler` - The function to call when the key is released
+    pub fn on_key_release<F>(&mut self, key: Key, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.key_release_handlers.insert(key, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a mouse button is pressed
     ///
-    /// The method will block until the application is closed and will print performance
-    /// statistics (FPS, frame count, elapsed time) when the application exits.
+    /// # Arguments
+    /// * `button` - The mouse button to watch for
+    /// * `handler` - The function to call when the button is pressed
+    pub fn on_mouse_press<F>(&mut self, button: MouseButton, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.mouse_handlers.insert(button, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a mouse button is released
+    ///
+    /// # Arguments
+    /// * `button` - The mouse button to watch for
+    /// * `handler` - The function to call when the button is released
+    pub fn on_mouse_release<F>(&mut self, button: MouseButton, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.mouse_release_handlers.insert(button, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a mouse button is clicked twice in quick succession
+    ///
+    /// The second press counts as a double click if it follows the first within
+    /// `Config::double_click_interval` and close to the same position. The press
+    /// handler still runs for both clicks, and the handler runs after it. A third
+    /// click starts a new double click rather than completing another one.
+    ///
+    /// # Arguments
+    /// * `button` - The mouse button to watch for
+    /// * `handler` - The function to call on the second click
+    pub fn on_mouse_double_click<F>(&mut self, button: MouseButton, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.mouse_double_click_handlers
+            .insert(button, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when the cursor moves
+    ///
+    /// The handler receives the new cursor position, in the same coordinates as
+    /// `mouse_x` and `mouse_y`, for every move event, and a redraw is requested
+    /// afterwards. Unlike polling the position in the draw function, this sees
+    /// every movement, including movement between redraws in `no_loop` sketches.
+    /// Registering another handler replaces the previous one.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    ///
+    /// # fn draw(app: &App<AppMode, f32>, _model: &f32) -> Vec<u8> { app.new_frame() }
+    /// let config = Config::with_dims(400, 400).no_loop();
+    /// let mut app = App::app(0.0, config, |_, m| m, draw);
+    /// app.on_mouse_move(|app, x, _y| app.model = x / 400.0);
+    /// ```
+    pub fn on_mouse_move<F>(&mut self, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, f32, f32) + 'static,
+    {
+        self.mouse_move_handler = Some(Rc::new(handler));
+    }
+
+    /// Registers a handler function for text typed into the window
+    ///
+    /// The handler receives the text produced by each key press, with the
+    /// keyboard layout and dead keys applied, and text committed by input methods
+    /// for languages such as Chinese or Japanese, so sketches can take titles,
+    /// seeds or parameter values as typed. Control characters are left out and
+    /// nothing is sent while Ctrl or Cmd is held; handle Backspace and Enter with
+    /// `on_key_press`. Registering another handler replaces the previous one.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::keyboard::{Key, NamedKey};
+    ///
+    /// # fn draw(app: &App<AppMode, String>, _model: &String) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::app(String::new(), Config::with_dims(400, 400), |_, m| m, draw);
+    /// app.on_text(|app, text| app.model.push_str(text));
+    /// app.on_key_press(Key::Named(NamedKey::Backspace), |app| {
+    ///     app.model.pop();
+    /// });
+    /// ```
+    pub fn on_text<F>(&mut self, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, &str) + 'static,
+    {
+        self.text_handler = Some(Rc::new(handler));
+        if let Some(window) = &self.window {
+            window.set_ime_allowed(true);
+        }
+    }
+
+    /// Registers a handler function for files dropped onto the window
+    ///
+    /// The handler runs once for each dropped file, with its path, and a redraw
+    /// is requested afterwards, so sketches can load an image to sample colors
+    /// from or a parameter file without restarting. Registering another handler
+    /// replaces the previous one.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use std::path::PathBuf;
+    ///
+    /// type Files = Vec<PathBuf>;
+    /// # fn draw(app: &App<AppMode, Files>, _model: &Files) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::app(Vec::new(), Config::with_dims(400, 400), |_, m| m, draw);
+    /// app.on_file_drop(|app, path| app.model.push(path.to_path_buf()));
+    /// ```
+    pub fn on_file_drop<F>(&mut self, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, &Path) + 'static,
+    {
+        self.file_drop_handler = Some(Rc::new(handler));
+    }
+
+    /// Registers a handler function for when the cursor moves while a mouse button is held
+    ///
+    /// The handler receives the cursor position where the button was pressed and
+    /// its current position, in the same coordinates as `mouse_x` and `mouse_y`,
+    /// for interactive tools such as drawing a line or a selection rectangle.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::event::MouseButton;
+    ///
+    /// type Stroke = Vec<(f32, f32)>;
+    /// # fn draw(app: &App<AppMode, Stroke>, _model: &Stroke) -> Vec<u8> { app.new_frame() }
+    /// let mut app = App::app(Stroke::new(), Config::with_dims(400, 400), |_, m| m, draw);
+    /// app.on_mouse_drag(MouseButton::Left, |app, _start, (x, y)| app.model.push((x, y)));
+    /// app.on_mouse_release(MouseButton::Left, |app| app.model.clear());
+    /// ```
+    pub fn on_mouse_drag<F>(&mut self, button: MouseButton, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, (f32, f32), (f32, f32)) + 'static,
+    {
+        self.mouse_drag_handlers.insert(button, Rc::new(handler));
+    }
+
+    /// Registers a handler function for when a key or mouse button is pressed
+    ///
+    /// # Arguments
+    /// * `input` - The key or mouse button to watch for, e.g. from `App::input`
+    /// * `handler` - The function to call when the input is pressed
+    pub fn on_input_press<F>(&mut self, input: Input, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        match input {
+            Input::Key(key) => self.on_key_press(key, handler),
+            Input::Mouse(button) => self.on_mouse_press(button, handler),
+        }
+    }
+
+    /// Loads a `bindings.toml` file remapping action names to keys or mouse buttons
+    ///
+    /// Actions registered with `App::register_action` are rebound immediately, so the
+    /// file can be reloaded while the sketch runs. Remaps also apply to inputs looked
+    /// up with `App::input` afterwards. See the `input` module for the file format.
     ///
     /// # Returns
-    /// * `Ok(())` - If the application ran successfully and was closed normally
-    /// * `Err(Error)` - If there was an error during window creation or rendering
+    /// * `Ok(usize)` - The number of remapped actions
+    /// * `Err(io::Error)` - If the file could not be read or is malformed
+    pub fn load_bindings(&mut self, path: impl AsRef<Path>) -> std::io::Result<usize> {
+        let bindings = load_bindings(path)?;
+        let count = bindings.len();
+        self.remaps.extend(bindings);
+        for (name, input) in self.actions.iter_mut() {
+            if let Some(remapped) = self.remaps.get(name) {
+                *input = remapped.clone();
+            }
+        }
+        Ok(count)
+    }
+
+    /// Registers a named action bound to a default key or mouse button
+    ///
+    /// Handlers attach to the action with `App::on_action` instead of to a physical
+    /// input, so a bindings file, the help overlay and other input sources all share
+    /// one mapping. If a loaded bindings file remaps `name`, the action is bound to
+    /// that input instead of `default`. Registering an action again rebinds it.
     ///
     /// # Examples
     /// ```rust,no_run
-    /// use artimate::app::{App, Config, Error};
-    /// 
-    /// fn main() -> Result<(), Error> {
-    ///     let config = Config::with_dims(800, 600);
-    ///     let mut app = App::sketch(config, draw);
-    ///     app.run() // Blocks until window is closed
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::keyboard::{Key, NamedKey};
+    ///
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     density: u32,
     /// }
-    /// 
-    /// fn draw(app: &App, _model: &()) -> Vec<u8> {
-    ///     vec![255; (app.config.width * app.config.height * 4) as usize]
+    ///
+    /// fn draw(app: &App<AppMode, Model>, _model: &Model) -> Vec<u8> {
+    ///     app.new_frame()
     /// }
+    ///
+    /// let config = Config::with_dims(800, 800);
+    /// let mut app = App::app(Model { density: 10 }, config, |_, model| model, draw);
+    /// app.register_action("increase_density", Key::Named(NamedKey::ArrowUp));
+    /// app.on_action("increase_density", |app| app.model.density += 1);
     /// ```
-    pub fn run(&mut self) -> Result<(), Error> {
-        let event_loop = EventLoop::new().unwrap();
-        event_loop.set_control_flow(ControlFlow::Poll);
-        let now = Instant::now();
-        let res = event_loop.run_app(self);
+    pub fn register_action(&mut self, name: &str, default: impl Into<Input>) {
+        let input = self.input(name, default);
+        match self.actions.iter_mut().find(|(action, _)| action == name) {
+            Some((_, bound)) => *bound = input,
+            None => self.actions.push((name.to_string(), input)),
+        }
+    }
+
+    /// Registers a handler function for when the input bound to an action is pressed
+    ///
+    /// # Arguments
+    /// * `name` - The action name, registered with `App::register_action`
+    /// * `handler` - The function to call when the action is triggered
+    pub fn on_action<F>(&mut self, name: &str, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>) + 'static,
+    {
+        self.on_action_step(name, move |app, _| handler(app));
+    }
+
+    /// Registers a handler function for an action that scales with the held modifiers
+    ///
+    /// The handler receives `Step::Coarse` while Shift is held, `Step::Fine` while
+    /// Alt is held and `Step::Normal` otherwise, so one action replaces separate
+    /// bindings for each adjustment size.
+    ///
+    /// # Arguments
+    /// * `name` - The action name, registered with `App::register_action`
+    /// * `handler` - The function to call with the step when the action is triggered
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use artimate::app::{App, AppMode, Config};
+    /// use winit::keyboard::{Key, NamedKey};
+    ///
+    /// #[derive(Clone)]
+    /// struct Model {
+    ///     angle: f32,
+    /// }
+    ///
+    /// fn draw(app: &App<AppMode, Model>, _model: &Model) -> Vec<u8> {
+    ///     app.new_frame()
+    /// }
+    ///
+    /// let config = Config::with_dims(800, 800);
+    /// let mut app = App::app(Model { angle: 0.0 }, config, |_, model| model, draw);
+    /// app.register_action("rotate", Key::Named(NamedKey::ArrowRight));
+    /// app.on_action_step("rotate", |app, step| app.model.angle += step.pick(0.1, 1.0, 10.0));
+    /// ```
+    pub fn on_action_step<F>(&mut self, name: &str, handler: F)
+    where
+        F: Fn(&mut App<Mode, M>, Step) + 'static,
+    {
+        self.action_handlers
+            .insert(name.to_string(), Rc::new(handler));
+    }
 
-        println!();
-        println!(
-            "Average FPS: {}",
-            self.frame_count as f32 / now.elapsed().as_secs_f32(),
-        );
-        println!("Frame count: {}", self.frame_count,);
-        println!("Elapsed time: {} seconds", now.elapsed().as_secs_f32(),);
+    /// Returns the input an action is currently bound to, or None if it is not registered
+    pub fn action_input(&self, name: &str) -> Option<&Input> {
+        self.actions
+            .iter()
+            .find(|(action, _)| action == name)
+            .map(|(_, input)| input)
+    }
 
-        res.map_err(|e| Error::UserDefined(Box::new(e)))
+    /// Runs the handlers of all actions bound to `input`
+    fn trigger_actions(&mut self, input: &Input) {
+        let step = Step::from_modifiers(self.modifiers.state());
+        let handlers: Vec<StepHandler<Mode, M>> = self
+            .actions
+            .iter()
+            .filter(|(_, bound)| bound == input)
+            .filter_map(|(name, _)| self.action_handlers.get(name).cloned())
+            .collect();
+        for handler in &handlers {
+            handler(self, step);
+        }
+        if !handlers.is_empty() {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
     }
 
-    /// Returns the current x-coordinate of the mouse cursor in pixels
+    /// Returns the input bound to an action name, or `default` if it is not remapped
     ///
-    /// The coordinate is relative to the top-left corner of the window,
-    /// with positive values extending to the right.
-    pub fn mouse_x(&self) -> f32 {
-        self.mouse_position.0
+    /// # Arguments
+    /// * `name` - The action name used in the bindings file
+    /// * `default` - The key or mouse button used when no bindings file remaps the action
+    pub fn input(&self, name: &str, default: impl Into<Input>) -> Input {
+        self.remaps
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| default.into())
     }
 
-    /// Returns the current y-coordinate of the mouse cursor in pixels
+    /// Sets `time` from the virtual clock in deterministic mode, or the wall clock otherwise
     ///
-    /// The coordinate is relative to the top-left corner of the window,
-    /// with positive values extending downward.
-    pub fn mouse_y(&self) -> f32 {
-        self.mouse_position.1
+    /// While replaying a session, the recorded time of the current frame is used.
+    fn update_time(&mut self) {
+        if let Some(tick) = self
+            .session_replay
+            .as_ref()
+            .and_then(|(session, _)| session.tick(self.frame_count))
+        {
+            self.time = tick.time;
+            return;
+        }
+        self.time = match self.config.deterministic {
+            _ if self.warmup_remaining > 0 => 0.0,
+            Some(fps) => self.frame_count as f32 / fps,
+            None => self.start_time.elapsed().as_secs_f32(),
+        };
     }
 
-    delegate! {
-        to self.config {
-            pub fn wh(&self) -> (u32, u32);
-            pub fn wh_f32(&self) -> (f32, f32);
-            pub fn w_f32(&self) -> f32;
-            pub fn h_f32(&self) -> f32;
+    /// Returns the number of frame budget ticks missed since the previous frame
+    ///
+    /// Always 0 when no target frame rate is set.
+    fn missed_ticks(&mut self) -> u32 {
+        let now = Instant::now();
+        let last = self.last_frame.replace(now);
+        // Sketches that don't loop sit idle between redraws, which isn't a slow frame
+        if let (Some(last), 0, false) = (last, self.warmup_remaining, self.config.no_loop) {
+            self.frame_intervals
+                .push(self.frame_count, now.duration_since(last));
+        }
+        if let Some(timing) = self.frame_timings.last_mut() {
+            if timing.duration_us.is_none() {
+                let start = self.start_time + Duration::from_micros(timing.timestamp_us);
+                timing.duration_us = Some(now.duration_since(start).as_micros() as u64);
+            }
+        }
+        match (self.target_fps(), last) {
+            (Some(fps), Some(last)) if fps > 0.0 => {
+                let ticks = (now.duration_since(last).as_secs_f32() * fps).floor() as u32;
+                ticks.saturating_sub(1)
+            }
+            _ => 0,
         }
     }
 
-    /// Sets the number of frames to save as PNG files and returns updated app
-    /// 
-    /// Frames are saved to the Downloads/frames directory with timestamps.
-    /// Set to 0 to disable frame saving.
-    pub fn set_frames_to_save(mut self, frames_to_save: u32) -> Self {
-        self.config = self.config.set_frames_to_save(frames_to_save);
-        self
+    /// Returns the frame rate frames are paced at, halved in low power mode
+    fn target_fps(&self) -> Option<f32> {
+        if self.low_power {
+            Some(self.config.fps.unwrap_or(60.0) / 2.0)
+        } else {
+            self.config.fps
+        }
     }
 
-    /// Sets cursor visibility in the window and returns updated app
-    pub fn set_cursor_visibility(mut self, cursor_visible: bool) -> Self {
-        self.config = self.config.set_cursor_visibility(cursor_visible);
-        self
+    /// Requests the next frame, immediately or, with a target frame rate, when it is due
+    fn request_next_frame(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window: &Window,
+    ) {
+        let fps = self.target_fps().filter(|fps| *fps > 0.0);
+        let (Some(fps), Some(start)) = (fps, self.last_frame) else {
+            self.frame_deadline = None;
+            event_loop.set_control_flow(ControlFlow::Poll);
+            window.request_redraw();
+            return;
+        };
+        let period = Duration::from_secs_f64(1.0 / fps as f64);
+        let now = Instant::now();
+        let mut deadline = self.frame_deadline.unwrap_or(start) + period;
+        if deadline + period < now {
+            // More than a frame behind, e.g. after a pause: restart the schedule
+            // instead of drawing a burst of frames to catch up
+            deadline = now;
+        }
+        self.frame_deadline = Some(deadline);
+        if deadline > now {
+            event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+        } else {
+            event_loop.set_control_flow(ControlFlow::Poll);
+            window.request_redraw();
+        }
     }
 
-    /// Configures the app to render only one frame and returns updated app
-    /// 
-    /// Useful for generating static images or when you want to control
-    /// the animation loop manually.
-    pub fn no_loop(mut self) -> Self {
-        self.config = self.config.no_loop();
-        self
+    /// Updates the running mean draw time and explains the first large spike, if diagnostics are verbose
+    fn note_draw_time(&mut self, secs: f32) {
+        if !self.config.verbose_diagnostics {
+            return;
+        }
+        if !self.reported_diagnostics.contains(&Diagnostic::FrameSpike)
+            && self.mean_draw_time > 0.0
+            && secs > SPIKE_MIN_SECS
+            && secs > SPIKE_FACTOR * self.mean_draw_time
+        {
+            eprintln!(
+                "Frame {} took {:.1} ms to draw, {:.0}x the average",
+                self.frame_count,
+                secs * 1000.0,
+                secs / self.mean_draw_time
+            );
+            self.diagnose(Diagnostic::FrameSpike);
+        }
+        self.mean_draw_time = if self.mean_draw_time > 0.0 {
+            0.9 * self.mean_draw_time + 0.1 * secs
+        } else {
+            secs
+        };
     }
 
-    /// Sets the maximum number of frames to render and returns updated app
-    /// 
-    /// The application will exit after rendering this many frames.
-    pub fn set_frames(mut self, frames: u32) -> Self {
-        self.config = self.config.set_frames(frames);
-        self
-    }
+    /// Draws, saves and presents a frame, then updates the model
+    fn redraw(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, window: &Window) {
+        if self.paused.is_some() {
+            self.present_history(event_loop);
+            return;
+        }
+        let missed = self.missed_ticks();
+        let missed = self.session_tick(missed);
+        let present = !(self.config.frame_skip == FrameSkip::SkipPresent && missed > 0);
 
-    /// Sets the window title and returns updated app
-    pub fn set_title(self, title: &str) -> Self {
-        Self {
-            config: self.config.set_title(title),
-            ..self
+        if present {
+            let version = self.model_version.map(|version| version(&self.model));
+            let frame_len = (self.config.width * self.config.height * 4) as usize;
+            let unchanged = version.is_some()
+                && version == self.drawn_version
+                && self.previous_frame.len() == frame_len;
+            let draw_result = if unchanged {
+                self.dirty_rects.get_mut().clear();
+                // Moved back into `previous_frame` once presented, instead of cloned
+                std::mem::take(&mut self.previous_frame)
+            } else {
+                let start = Instant::now();
+                let frame = self.draw_frame();
+                self.note_draw_time(start.elapsed().as_secs_f32());
+                frame
+            };
+            let dirty_rects = std::mem::take(self.dirty_rects.get_mut());
+            if self.config.track_frame_stats && !(unchanged && self.frame_stats.is_some()) {
+                self.frame_stats = Some(FrameStats::from_frame(
+                    &draw_result,
+                    self.config.clear_color,
+                ));
+            }
+            self.record_history(&draw_result);
+            if !unchanged && (!self.config.auto_clear || version.is_some()) {
+                self.previous_frame.clone_from(&draw_result);
+            }
+            self.drawn_version = version;
+
+            let help = self.help_visible.then(|| self.bindings());
+            let insets = self.debug_insets();
+            let checkerboard = self.config.transparent_export && !self.config.transparent;
+            let overlaid =
+                self.guides_visible || !insets.is_empty() || self.take.is_some() || help.is_some();
+            let pixels_held_frame = self.pixels_hold_frame;
+            self.pixels_hold_frame = !checkerboard && !overlaid;
+            // The window already shows this frame, unless a post shader animates it
+            let presented = unchanged
+                && pixels_held_frame
+                && self.pixels_hold_frame
+                && self.config.post_shader.is_none();
+            if let Some(pixels) = self.pixels.as_mut().filter(|_| !presented) {
+                let width = self.config.width;
+                match dirty_rects {
+                    _ if checkerboard => {
+                        composite_checkerboard(pixels.frame_mut(), &draw_result, width);
+                    }
+                    // The rest of the pixel buffer still shows the previous frame
+                    rects if pixels_held_frame && !rects.is_empty() => {
+                        for rect in rects {
+                            let to = (rect.0 as i32, rect.1 as i32);
+                            copy_rect(pixels.frame_mut(), width, &draw_result, width, rect, to);
+                        }
+                    }
+                    _ => pixels.frame_mut().copy_from_slice(draw_result.as_ref()),
+                }
+                let (width, height) = self.config.wh();
+                let theme = self.config.overlay_theme;
+                if self.guides_visible {
+                    let (guides, color) = (&self.config.guides, theme.guide_color());
+                    draw_guides(pixels.frame_mut(), width, height, guides, color);
+                }
+                if !insets.is_empty() {
+                    draw_insets(pixels.frame_mut(), width, height, &insets, theme);
+                }
+                if let Some((number, _)) = &self.take {
+                    let start = self
+                        .take_capture
+                        .map_or(self.save_start, |(_, start)| start);
+                    let frame = self.frame_count - start;
+                    let label = format!("REC take {:03}  frame {}", number, frame);
+                    draw_recording(pixels.frame_mut(), width, height, &label, theme);
+                }
+                if let Some(bindings) = &help {
+                    draw_help(pixels.frame_mut(), width, height, bindings, theme);
+                }
+
+                if let Err(err) = render_pixels(pixels, self.surface_renderer.as_ref(), self.time) {
+                    eprintln!("Failed to render frame: {}", err);
+                    self.diagnose(Diagnostic::Surface);
+                    event_loop.exit();
+                    return;
+                }
+            }
+            let stage = self.config.capture_stage;
+            let composited = self
+                .is_saving()
+                .then(|| self.composited_frame(stage))
+                .flatten();
+            self.save_frame_to_downloads(&draw_result, composited);
+            self.send_captures(&draw_result);
+            if unchanged {
+                self.previous_frame = draw_result;
+            } else if self.draw_in_place.is_some() {
+                // Draw the next frame into this one's memory instead of allocating
+                self.frame_buffer = draw_result;
+            }
+        }
+
+        let steps = match self.config.frame_skip {
+            FrameSkip::CatchUp => 1 + missed.min(MAX_CATCH_UP_STEPS - 1),
+            _ => 1,
+        };
+        if let Some(update) = self.update {
+            for _ in 0..steps {
+                self.model = update(self, self.model.clone());
+            }
+        }
+        if self.config.frame_skip == FrameSkip::SkipFrames {
+            self.frame_count += missed;
+        }
+
+        if self.warmup_remaining > 0 {
+            self.warmup_remaining -= 1;
+            if self.warmup_remaining == 0 {
+                self.finish_warmup();
+            }
+            window.request_redraw();
+            return;
+        }
+
+        if !self.config.no_loop {
+            if let Some(frames) = self.config.frames {
+                if self.frame_count < frames {
+                    self.request_next_frame(event_loop, window);
+                }
+            } else {
+                self.request_next_frame(event_loop, window);
+            }
         }
+        self.frame_count += 1;
+        self.checkpoint_if_due();
     }
 
-    /// Registers a handler function for when a key is held down
+    /// Replays the session events due before this frame and records its tick
     ///
-    /// # Arguments
-    /// * `key` - The key to watch for
-    /// * `handler` - The function to call while the key is held
-    pub fn on_key_held<F>(&mut self, key: Key, handler: F)
-    where
-        F: Fn(&mut App<Mode, M>) + 'static,
-    {
-        self.key_handlers.insert(key, Rc::new(handler));
+    /// Returns the number of missed frame budget ticks, taken from the session
+    /// while replaying.
+    fn session_tick(&mut self, missed: u32) -> u32 {
+        if self.warmup_remaining > 0 {
+            return missed;
+        }
+        let frame = self.frame_count;
+        let missed = match &self.session_replay {
+            Some((session, _)) => session.tick(frame).map_or(0, |tick| tick.missed),
+            None => missed,
+        };
+        self.replay_events();
+        if let Some((session, _)) = &mut self.session_recording {
+            let time = self.time;
+            session.ticks.push(Tick {
+                frame,
+                time,
+                missed,
+            });
+        }
+        missed
     }
 
-    /// Registers a handler function for when a key is initially pressed
-    ///
-    /// # Arguments
-    /// * `key` - The key to watch for
-    /// * `handler` - The function to call when the key is pressed
-    pub fn on_key_press<F>(&mut self, key: Key, handler: F)
-    where
-        F: Fn(&mut App<Mode, M>) + 'static,
-    {
-        self.key_press_handlers.insert(key, Rc::new(handler));
+    /// Delivers the replayed events that arrived before the current frame
+    fn replay_events(&mut self) {
+        let Some((session, next)) = &mut self.session_replay else {
+            return;
+        };
+        let scale = (
+            self.config.width as f32 / session.size.0.max(1) as f32,
+            self.config.height as f32 / session.size.1.max(1) as f32,
+        );
+        let due: Vec<_> = session.events[*next..]
+            .iter()
+            .take_while(|event| event.frame <= self.frame_count)
+            .cloned()
+            .collect();
+        *next += due.len();
+        if due.is_empty() {
+            return;
+        }
+        for event in due {
+            self.time = event.time;
+            match event.event {
+                SessionEvent::KeyDown { key, repeat } => {
+                    self.handle_keyboard_input(key, winit::event::ElementState::Pressed, repeat)
+                }
+                SessionEvent::KeyUp(key) => {
+                    self.handle_keyboard_input(key, winit::event::ElementState::Released, false)
+                }
+                SessionEvent::MouseDown(button) => {
+                    self.handle_mouse_input(button, winit::event::ElementState::Pressed)
+                }
+                SessionEvent::MouseUp(button) => {
+                    self.handle_mouse_input(button, winit::event::ElementState::Released)
+                }
+                SessionEvent::MouseMove(x, y) => self.handle_mouse_move((x * scale.0, y * scale.1)),
+                SessionEvent::Modifiers(state) => self.modifiers = state.into(),
+                SessionEvent::Text(text) => self.handle_text(&text),
+                SessionEvent::FileDrop(path) => self.handle_file_drop(path),
+            }
+        }
+        self.update_time();
     }
 
-    /// Registers a handler function for when a key is released
+    /// Adds a live input event to the session being recorded, if any
+    fn record_event(&mut self, event: SessionEvent) {
+        if self.warmup_remaining > 0 {
+            return;
+        }
+        if let Some((session, _)) = &mut self.session_recording {
+            session.push_event(self.frame_count, self.time, event);
+        }
+    }
+
+    /// Restarts time, frame counting and statistics once the warmup frames have run
+    fn finish_warmup(&mut self) {
+        let now = Instant::now();
+        self.start_time = now;
+        self.stats_start = now;
+        self.last_frame = None;
+        self.frame_count = 0;
+        self.frame_intervals.clear();
+    }
+
+    /// Returns a copy of the window contents if `stage` is `CaptureStage::Final` and a window is open
+    fn composited_frame(&self, stage: CaptureStage) -> Option<Vec<u8>> {
+        if stage != CaptureStage::Final {
+            return None;
+        }
+        self.pixels.as_ref().map(|pixels| pixels.frame().to_vec())
+    }
+
+    /// Sends the current frame to every capture due at this frame, rendering each stage once
+    fn send_captures(&mut self, draw_result: &[u8]) {
+        if self.warmup_remaining > 0 {
+            return;
+        }
+        let number = self.frame_count;
+        let mut rendered: Vec<(CaptureStage, Arc<CapturedFrame>)> = Vec::new();
+        for i in 0..self.captures.len() {
+            if !self.captures[i].is_due(number) {
+                continue;
+            }
+            let stage = self.captures[i].stage;
+            let frame = match rendered.iter().find(|(s, _)| *s == stage) {
+                Some((_, frame)) => frame.clone(),
+                None => {
+                    let (data, width, height) = match self.composited_frame(stage) {
+                        Some(frame) => (frame, self.config.width, self.config.height),
+                        None => self.export_frame(draw_result, stage),
+                    };
+                    let frame = Arc::new(CapturedFrame {
+                        number,
+                        width,
+                        height,
+                        data,
+                    });
+                    rendered.push((stage, frame.clone()));
+                    frame
+                }
+            };
+            self.captures[i].send(frame);
+        }
+        for capture in &mut self.captures {
+            capture.close_after(number);
+        }
+    }
+
+    /// Sends the current frame to the frame saving thread if it is within `frames_to_save`
     ///
-    /// # Arguments
-    /// * `key` - The key to watch for
-    /// * `handler` - The function to call when the key is released
-    pub fn on_key_release<F>(&mut self, key: Key, handler: F)
-    where
-        F: Fn(&mut App<Mode, M>) + 'static,
-    {
-        self.key_release_handlers.insert(key, Rc::new(handler));
+    /// `composited` is the window contents, saved instead of `draw_result` when
+    /// capturing `CaptureStage::Final`.
+    fn save_frame_to_downloads(&mut self, draw_result: &[u8], composited: Option<Vec<u8>>) {
+        if self.warmup_remaining > 0 || !self.is_saving() || self.frame_sender.is_none() {
+            return;
+        }
+        let (frame_data, width, height) = match composited {
+            Some(frame) => (frame, self.config.width, self.config.height),
+            None => self.export_frame(draw_result, self.config.capture_stage),
+        };
+        let output_dir = match (&self.take, self.take_capture) {
+            (Some((_, dir)), None) => Some(dir.clone()),
+            _ => self.session_dir(),
+        };
+        let Some(output_dir) = output_dir else {
+            eprintln!("Failed to save frames: no downloads directory; stopping frame saving");
+            self.abort_saving();
+            return;
+        };
+        if let Err(err) = std::fs::create_dir_all(&output_dir) {
+            eprintln!(
+                "Failed to create frames directory {}: {}; stopping frame saving",
+                output_dir.display(),
+                err
+            );
+            self.abort_saving();
+            return;
+        }
+        let number = self.config.frame_start + (self.frame_count - self.save_start);
+        let filename = output_dir.join(self.config.frame_filename(number));
+        let path = filename.to_string_lossy().to_string();
+        let frame_start = self.last_frame.unwrap_or(self.start_time);
+        self.frame_timings.push(FrameTiming {
+            frame: self.frame_count,
+            path: path.clone(),
+            timestamp_us: frame_start.duration_since(self.start_time).as_micros() as u64,
+            duration_us: None,
+        });
+        if let Some(sender) = &self.frame_sender {
+            let options = self.config.save_options();
+            let message = FrameMessage::Frame(frame_data, path, width, height, options);
+            // A checkpoint promises every earlier frame is on disk
+            let policy = match self.checkpoint {
+                Some(_) => SavePolicy::Block,
+                None => self.config.save_queue.1,
+            };
+            let dropped = sender.0.push(message, policy);
+            if let Some(FrameMessage::Frame(_, dropped, ..)) = dropped {
+                self.drop_saved_frame(&dropped);
+            }
+        }
     }
 
-    /// Registers a handler function for when a mouse button is pressed
+    /// Stops saving frames after a failure, ending a PNG take without reporting it as saved
+    fn abort_saving(&mut self) {
+        if self.take_capture.is_none() {
+            self.take = None;
+        }
+        self.stop_saving();
+    }
+
+    /// Forgets a frame the frame saving queue dropped, warning the first time
+    fn drop_saved_frame(&mut self, path: &str) {
+        if self.dropped_frames == 0 {
+            eprintln!(
+                "Warning: frame saving cannot keep up, dropping frames like {} \
+                 (see Config::save_queue)",
+                path
+            );
+        }
+        self.dropped_frames += 1;
+        if let Some(i) = self
+            .frame_timings
+            .iter()
+            .rposition(|timing| timing.path == path)
+        {
+            self.frame_timings.remove(i);
+        }
+    }
+
+    /// Returns the directory of the current session's frames, choosing a new one if needed
     ///
-    /// # Arguments
-    /// * `button` - The mouse button to watch for
-    /// * `handler` - The function to call when the button is pressed
-    pub fn on_mouse_press<F>(&mut self, button: MouseButton, handler: F)
-    where
-        F: Fn(&mut App<Mode, M>) + 'static,
-    {
-        self.mouse_handlers.insert(button, Rc::new(handler));
+    /// Each run of saved frames outside a take gets its own
+    /// `Downloads/frames/session_{timestamp}_{title}` directory, so frame numbers
+    /// always start from `Config::frame_start`, unless `Config::frame_dir` is set.
+    fn session_dir(&mut self) -> Option<PathBuf> {
+        if let Some(dir) = &self.config.frame_dir {
+            return Some(dir.clone());
+        }
+        if self.session_dir.is_none() {
+            let slug = title_slug(&self.config.window_title);
+            self.session_dir = frames_dir(&self.config)
+                .and_then(|dir| new_session_dir(&dir, &slug))
+                .ok();
+        }
+        self.session_dir.clone()
     }
 
     /// Processes keyboard input events and triggers appropriate handlers
@@ -589,24 +5288,28 @@ where
     /// # Arguments
     /// * `event` - The keyboard event to process
     /// * `_event_loop` - The event loop instance
-    fn handle_keyboard_input(
-        &mut self,
-        event: winit::event::KeyEvent,
-        _event_loop: &winit::event_loop::ActiveEventLoop,
-    ) {
-        match event.state {
+    fn handle_keyboard_input(&mut self, key: Key, state: winit::event::ElementState, repeat: bool) {
+        match state {
             winit::event::ElementState::Pressed => {
-                self.keys_down.insert(event.logical_key.clone());
-                // Handle one-time press events
-                if let Some(handler) = self.key_press_handlers.get(&event.logical_key).cloned() {
+                self.keys_down.insert(key.clone());
+                // Handle one-time press events, preferring a handler for the held modifiers
+                let chord = (key.clone(), self.modifiers.state());
+                let handler = match self.key_chord_handlers.get(&chord) {
+                    Some(handler) => Some(handler.clone()),
+                    None => self.key_press_handlers.get(&key).cloned(),
+                };
+                if let Some(handler) = handler {
                     handler(self);
                     self.window.as_ref().unwrap().request_redraw();
                 }
+                if !repeat {
+                    self.trigger_actions(&Input::Key(key.clone()));
+                }
             }
             winit::event::ElementState::Released => {
-                self.keys_down.remove(&event.logical_key);
+                self.keys_down.remove(&key);
                 // Handle release events
-                if let Some(handler) = self.key_release_handlers.get(&event.logical_key).cloned() {
+                if let Some(handler) = self.key_release_handlers.get(&key).cloned() {
                     handler(self);
                     self.window.as_ref().unwrap().request_redraw();
                 }
@@ -614,8 +5317,8 @@ where
         }
 
         // Handle continuous key holding in the update/draw loop
-        if event.state == winit::event::ElementState::Pressed {
-            if let Some(handler) = self.key_handlers.get(&event.logical_key).cloned() {
+        if state == winit::event::ElementState::Pressed {
+            if let Some(handler) = self.key_handlers.get(&key).cloned() {
                 handler(self);
                 self.window.as_ref().unwrap().request_redraw();
             }
@@ -625,13 +5328,95 @@ where
     /// Processes mouse input events and triggers appropriate handlers
     ///
     /// # Arguments
-    /// * `button` - The mouse button that was pressed
-    fn handle_mouse_input(&mut self, button: MouseButton) {
-        let handler = self.mouse_handlers.get(&button).cloned();
-        if let Some(handler) = handler {
+    /// * `button` - The mouse button that was pressed or released
+    /// * `state` - Whether the button was pressed or released
+    fn handle_mouse_input(&mut self, button: MouseButton, state: winit::event::ElementState) {
+        let (handler, double_click) = match state {
+            winit::event::ElementState::Pressed => {
+                self.buttons_down.insert(button, self.mouse_position);
+                let double_click = self.is_double_click(button);
+                let handler = self.mouse_handlers.get(&button).cloned();
+                (
+                    handler,
+                    double_click.then(|| self.mouse_double_click_handlers.get(&button)),
+                )
+            }
+            winit::event::ElementState::Released => {
+                self.buttons_down.remove(&button);
+                (self.mouse_release_handlers.get(&button).cloned(), None)
+            }
+        };
+        let double_click = double_click.flatten().cloned();
+        for handler in handler.iter().chain(&double_click) {
             handler(self);
             self.window.as_ref().unwrap().request_redraw();
         }
+        if state == winit::event::ElementState::Pressed {
+            self.trigger_actions(&Input::Mouse(button));
+        }
+    }
+
+    /// Returns true if a press of `button` completes a double click, and remembers it otherwise
+    fn is_double_click(&mut self, button: MouseButton) -> bool {
+        // Replays use the recorded time so they recognize the same double clicks
+        let now = match self.session_replay {
+            Some(_) => self.time,
+            None => self.start_time.elapsed().as_secs_f32(),
+        };
+        let (x, y) = self.mouse_position;
+        let double_click = self.last_click.is_some_and(|(last, time, (lx, ly))| {
+            last == button
+                && now - time <= self.config.double_click_interval.as_secs_f32()
+                && (x - lx).abs() <= DOUBLE_CLICK_DISTANCE
+                && (y - ly).abs() <= DOUBLE_CLICK_DISTANCE
+        });
+        self.last_click = (!double_click).then_some((button, now, (x, y)));
+        double_click
+    }
+
+    /// Calls the text handler with the printable characters of `text`
+    fn handle_text(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        if text.is_empty() {
+            return;
+        }
+        if let Some(handler) = self.text_handler.clone() {
+            self.record_event(SessionEvent::Text(text.clone()));
+            handler(self, &text);
+            self.window.as_ref().unwrap().request_redraw();
+        }
+    }
+
+    /// Calls the file drop handler with `path`
+    fn handle_file_drop(&mut self, path: PathBuf) {
+        if let Some(handler) = self.file_drop_handler.clone() {
+            self.record_event(SessionEvent::FileDrop(path.clone()));
+            handler(self, &path);
+            self.window.as_ref().unwrap().request_redraw();
+        }
+    }
+
+    /// Moves the cursor and calls the move handler and the drag handlers of the held buttons
+    fn handle_mouse_move(&mut self, position: (f32, f32)) {
+        self.mouse_position = position;
+        let moved = self.mouse_move_handler.clone();
+        if let Some(handler) = &moved {
+            handler(self, position.0, position.1);
+        }
+        let drags: Vec<_> = self
+            .buttons_down
+            .iter()
+            .filter_map(|(button, start)| {
+                let handler = self.mouse_drag_handlers.get(button)?;
+                Some((*start, handler.clone()))
+            })
+            .collect();
+        for (start, handler) in &drags {
+            handler(self, *start, position);
+        }
+        if moved.is_some() || !drags.is_empty() {
+            self.window.as_ref().unwrap().request_redraw();
+        }
     }
 }
 
@@ -640,17 +5425,34 @@ impl<Mode, M> ApplicationHandler for App<Mode, M>
 where
     M: Clone,
 {
+    fn new_events(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop, cause: StartCause) {
+        #[cfg(feature = "watch")]
+        self.poll_file_watches();
+        // With a target frame rate the next frame is requested once it is due
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        let size = LogicalSize::new(self.config.width as f64, self.config.height as f64);
+        let size = self.config.window_size();
         self.window.get_or_insert_with(|| {
-            Arc::new(event_loop
-                .create_window(
-                    Window::default_attributes()
-                        .with_title(self.config.window_title.clone())
-                        .with_inner_size(size)
-                        .with_min_inner_size(size),
-                )
-                .unwrap())
+            let mut attributes = Window::default_attributes()
+                .with_title(self.config.window_title.clone())
+                .with_inner_size(size)
+                .with_transparent(self.config.transparent)
+                .with_window_level(window_level(self.config.always_on_top));
+            if let Some(min_size) = self.config.min_window_size() {
+                attributes = attributes.with_min_inner_size(min_size);
+            }
+            if let Some(max_size) = self.config.max_window_size() {
+                attributes = attributes.with_max_inner_size(max_size);
+            }
+            let window = event_loop.create_window(attributes).unwrap();
+            window.set_ime_allowed(self.text_handler.is_some());
+            Arc::new(window)
         });
     }
 
@@ -660,67 +5462,150 @@ where
         _window_id: WindowId,
         event: WindowEvent,
     ) {
-        let window = self.window.as_ref().unwrap();
+        let window = self.window.clone().unwrap();
         let window_size = window.inner_size();
 
-        self.time = self.start_time.elapsed().as_secs_f32();
+        self.update_time();
 
         match event {
             WindowEvent::CloseRequested => {
                 println!("Close Requested");
                 event_loop.exit();
             }
-            WindowEvent::ModifiersChanged(new_mods) => {
+            WindowEvent::Resized(size) => {
+                if self.config.resizable {
+                    // The buffer is sized in logical pixels divided by the pixel size
+                    let logical = size.to_logical::<f64>(window.scale_factor());
+                    let pixel_size = self.config.pixel_size.max(1) as f64;
+                    let width = (logical.width / pixel_size).round() as u32;
+                    let height = (logical.height / pixel_size).round() as u32;
+                    // Minimized windows report a zero size, keep the buffer until restored
+                    if width > 0 && height > 0 && (width, height) != self.config.wh() {
+                        self.resize_buffer(width, height);
+                    }
+                }
+                self.resize_surface(size);
+                window.request_redraw();
+            }
+            WindowEvent::Focused(false) if self.session_replay.is_none() => {
+                // Releases are not reported to unfocused windows, so nothing stays held
+                self.keys_down.clear();
+                self.buttons_down.clear();
+            }
+            WindowEvent::ModifiersChanged(new_mods) if self.session_replay.is_none() => {
+                self.record_event(SessionEvent::Modifiers(new_mods.state()));
                 self.modifiers = new_mods; // Update stored modifier state
             }
             WindowEvent::KeyboardInput { event, .. } => {
-                if event.state == winit::event::ElementState::Pressed {
-                    if let Key::Character(ref text) = event.logical_key {
-                        if text == "s" {
-                            if self.modifiers.lsuper_state() == ModifiersKeyState::Pressed
-                                || self.modifiers.rsuper_state() == ModifiersKeyState::Pressed
-                            {
-                                let draw_result = (self.draw)(&self, &self.model);
-                                if let Some(pixels) = self.pixels.as_mut() {
-                                    pixels.frame_mut().copy_from_slice(draw_result.as_ref());
-                                    let frame_data: Vec<u8> = pixels.frame().to_vec();
-                                    if let Some(downloads_dir) = dirs::download_dir() {
-                                        let output_dir = downloads_dir.join("artmate");
-                                        if let Err(err) = std::fs::create_dir_all(&output_dir) {
-                                            eprintln!("Failed to create frames directory: {}", err);
-                                        } else {
-                                            let timestamp = SystemTime::now()
-                                                .duration_since(UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_secs();
-                                            let filename = output_dir
-                                                .join(format!("artmate_{}.png", timestamp));
-                                            save_frame(
-                                                frame_data,
-                                                filename.to_string_lossy().to_string(),
-                                                self.config.width,
-                                                self.config.height,
-                                            )
-                                            .unwrap();
-                                        }
-                                    }
-                                }
-                            }
+                let command = event.state == winit::event::ElementState::Pressed
+                    && (self.modifiers.lsuper_state() == ModifiersKeyState::Pressed
+                        || self.modifiers.rsuper_state() == ModifiersKeyState::Pressed);
+                let screenshot = self
+                    .config
+                    .screenshot_key
+                    .as_ref()
+                    .is_some_and(|(key, mods)| {
+                        event.state == winit::event::ElementState::Pressed
+                            && event.logical_key == *key
+                            && self.modifiers.state() == *mods
+                    });
+                if screenshot {
+                    if let Err(err) = self.save_screenshot() {
+                        eprintln!("Failed to save screenshot: {}", err);
+                    }
+                }
+                if command && event.logical_key == Key::Character("g".into()) {
+                    self.toggle_guides();
+                }
+                if command && !event.repeat && event.logical_key == Key::Character("p".into()) {
+                    self.toggle_pause();
+                }
+                if let Some(key) = self.config.record_key {
+                    let key = Key::Character(key.to_string().into());
+                    if command && !event.repeat && event.logical_key == key {
+                        self.toggle_recording();
+                    }
+                }
+                if self.paused.is_some()
+                    && !self.history.is_empty()
+                    && event.state == winit::event::ElementState::Pressed
+                {
+                    match event.logical_key {
+                        Key::Named(NamedKey::ArrowLeft) => {
+                            self.scrub_to(self.history_cursor.saturating_sub(1));
+                            return;
+                        }
+                        Key::Named(NamedKey::ArrowRight) => {
+                            self.scrub_to(self.history_cursor + 1);
+                            return;
                         }
+                        _ => {}
                     }
                 }
-                self.handle_keyboard_input(event, event_loop);
+                if !command
+                    && !event.repeat
+                    && event.state == winit::event::ElementState::Pressed
+                    && event.logical_key == Key::Character("?".into())
+                {
+                    self.toggle_help();
+                }
+                if self.session_replay.is_none() {
+                    let key = event.logical_key;
+                    self.record_event(match event.state {
+                        winit::event::ElementState::Pressed => SessionEvent::KeyDown {
+                            key: key.clone(),
+                            repeat: event.repeat,
+                        },
+                        winit::event::ElementState::Released => SessionEvent::KeyUp(key.clone()),
+                    });
+                    self.handle_keyboard_input(key, event.state, event.repeat);
+                    let shortcut =
+                        self.modifiers.state().control_key() || self.modifiers.state().super_key();
+                    if let (Some(text), false) = (&event.text, shortcut) {
+                        self.handle_text(text);
+                    }
+                }
+            }
+            WindowEvent::Ime(Ime::Commit(text)) if self.session_replay.is_none() => {
+                self.handle_text(&text);
             }
-            WindowEvent::MouseInput { button, state, .. } => {
-                if state == winit::event::ElementState::Pressed {
-                    self.handle_mouse_input(button);
+            WindowEvent::DroppedFile(path) if self.session_replay.is_none() => {
+                self.handle_file_drop(path);
+            }
+            WindowEvent::MouseInput {
+                button: MouseButton::Left,
+                state,
+                ..
+            } if self.paused.is_some() && !self.history.is_empty() => {
+                let bar_top = self.config.height.saturating_sub(SCRUB_BAR_HEIGHT) as f32;
+                self.scrubbing = state == winit::event::ElementState::Pressed
+                    && self.mouse_position.1 >= bar_top;
+                if self.scrubbing {
+                    self.scrub_to_mouse();
                 }
             }
-            WindowEvent::CursorMoved { position, .. } => {
+            WindowEvent::MouseInput { button, state, .. } if self.session_replay.is_none() => {
+                self.record_event(match state {
+                    winit::event::ElementState::Pressed => SessionEvent::MouseDown(button),
+                    winit::event::ElementState::Released => SessionEvent::MouseUp(button),
+                });
+                self.handle_mouse_input(button, state);
+            }
+            WindowEvent::CursorMoved { position, .. } if self.session_replay.is_none() => {
                 if let Some(window) = &self.window {
-                    let scale_factor = window.scale_factor();
-                    let logical_position = position.to_logical(scale_factor);
-                    self.mouse_position = (logical_position.x, logical_position.y);
+                    // The buffer may be scaled and letterboxed in the window
+                    let surface = window.inner_size();
+                    let (x, y) = surface_to_buffer(
+                        (position.x as f32, position.y as f32),
+                        self.config.wh(),
+                        (surface.width, surface.height),
+                        self.config.scaling_filter,
+                    );
+                    self.record_event(SessionEvent::MouseMove(x, y));
+                    self.handle_mouse_move((x, y));
+                }
+                if self.scrubbing {
+                    self.scrub_to_mouse();
                 }
             }
             WindowEvent::CursorEntered { .. } => {
@@ -740,66 +5625,49 @@ where
                 }
             }
             WindowEvent::RedrawRequested => {
-                self.pixels.get_or_insert_with(|| {
+                if self.pixels.is_none() {
                     let surface_texture =
                         SurfaceTexture::new(window_size.width, window_size.height, window.clone());
-                    Pixels::new(self.config.width, self.config.height, surface_texture).unwrap()
-                });
-
-                let draw_result = (self.draw)(&self, &self.model);
-
-                if let Some(pixels) = self.pixels.as_mut() {
-                    pixels.frame_mut().copy_from_slice(draw_result.as_ref());
-
-                    if self.frame_count < self.config.frames_to_save {
-                        if let Some(sender) = &self.frame_sender {
-                            let frame_data: Vec<u8> = pixels.frame().to_vec();
-                            if let Some(downloads_dir) = dirs::download_dir() {
-                                let output_dir = downloads_dir.join("frames");
-                                if let Err(err) = std::fs::create_dir_all(&output_dir) {
-                                    eprintln!("Failed to create frames directory: {}", err);
-                                } else {
-                                    let timestamp = SystemTime::now()
-                                        .duration_since(UNIX_EPOCH)
-                                        .unwrap()
-                                        .as_secs();
-                                    let filename = output_dir.join(format!(
-                                        "frame_{}_{:04}.png",
-                                        timestamp, self.frame_count
-                                    ));
-                                    if let Err(err) = sender.send((
-                                        frame_data,
-                                        filename.to_string_lossy().to_string(),
-                                        self.config.width,
-                                        self.config.height,
-                                    )) {
-                                        eprintln!("Failed to send frame data: {}", err);
-                                    }
-                                }
-                            }
+                    let (width, height) = self.config.wh();
+                    let (clear_color, blend_state) = if self.config.transparent {
+                        // Replace rather than blend, so the frame's alpha reaches the compositor
+                        (wgpu::Color::TRANSPARENT, wgpu::BlendState::REPLACE)
+                    } else {
+                        (wgpu::Color::BLACK, wgpu::BlendState::ALPHA_BLENDING)
+                    };
+                    let clear_color = self.config.surface_clear_color.map_or(clear_color, |c| {
+                        // The surface is sRGB encoded, so the clear color is given in linear light
+                        let channel = |v: u8| srgb_to_linear(v as f32 / 255.0) as f64;
+                        wgpu::Color {
+                            r: channel(c[0]),
+                            g: channel(c[1]),
+                            b: channel(c[2]),
+                            a: c[3] as f64 / 255.0,
                         }
-                    }
-
-                    if let Err(_err) = pixels.render() {
-                        event_loop.exit();
-                        return;
-                    }
-                }
-
-                if let Some(update) = self.update {
-                    self.model = update(&self, self.model.clone());
-                }
-
-                if !self.config.no_loop {
-                    if let Some(frames) = self.config.frames {
-                        if self.frame_count < frames {
-                            window.request_redraw();
+                    });
+                    let pixels = PixelsBuilder::new(width, height, surface_texture)
+                        .clear_color(clear_color)
+                        .blend_state(blend_state)
+                        .build();
+                    match pixels {
+                        Ok(pixels) => {
+                            self.surface_renderer = self.build_surface_renderer(
+                                &pixels,
+                                window_size,
+                                clear_color,
+                                blend_state,
+                            );
+                            self.pixels = Some(pixels);
+                        }
+                        Err(err) => {
+                            eprintln!("Failed to create pixel surface: {}", err);
+                            self.diagnose(Diagnostic::Surface);
+                            event_loop.exit();
+                            return;
                         }
-                    } else {
-                        window.request_redraw();
                     }
                 }
-                self.frame_count += 1;
+                self.redraw(event_loop, &window);
             }
             _ => (),
         }
@@ -0,0 +1,188 @@
+//! # Frame Arena
+//!
+//! A bump allocator for temporary data that only lives while a frame is drawn.
+//! Sketches that build thousands of points or path segments every frame would
+//! otherwise allocate and free the same buffers over and over; allocating from
+//! the arena is a pointer bump, and the memory is reused once the arena is reset.
+//!
+//! The app owns an arena and resets it before each call to the draw function, so
+//! sketches use it through `App::alloc_slice` and `App::alloc_copy`:
+//!
+//! ```
+//! use artimate::app::{App, Config};
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     let points: &mut [(f32, f32)] = app.alloc_slice(1000);
+//!     for (i, p) in points.iter_mut().enumerate() {
+//!         let t = i as f32 * 0.01;
+//!         *p = (100.0 + 80.0 * t.cos(), 100.0 + 80.0 * t.sin());
+//!     }
+//!     let mut frame = app.new_frame();
+//!     for &(x, y) in points.iter() {
+//!         let i = ((y as u32 * app.config.width + x as u32) * 4) as usize;
+//!         frame[i..i + 4].copy_from_slice(&[255, 255, 255, 255]);
+//!     }
+//!     frame
+//! }
+//!
+//! let app = App::sketch(Config::with_dims(200, 200), draw);
+//! ```
+//!
+//! Only `Copy` types can be allocated, since nothing in the arena is dropped.
+
+use std::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use std::cell::{Cell, RefCell};
+use std::mem::{align_of, size_of};
+use std::ptr::NonNull;
+
+/// Alignment of every chunk, and the largest alignment the arena supports
+const CHUNK_ALIGN: usize = 16;
+
+/// Size of the first chunk in bytes
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A block of memory the arena hands out slices from
+#[derive(Debug)]
+struct Chunk {
+    ptr: NonNull<u8>,
+    size: usize,
+}
+
+impl Chunk {
+    fn new(size: usize) -> Self {
+        let layout = Self::layout(size);
+        // Safety: the layout has a non-zero size
+        let ptr = unsafe { alloc(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        Self { ptr, size }
+    }
+
+    fn layout(size: usize) -> Layout {
+        Layout::from_size_align(size, CHUNK_ALIGN).expect("arena chunk size overflows")
+    }
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        // Safety: the pointer was allocated in `Chunk::new` with the same layout
+        unsafe { dealloc(self.ptr.as_ptr(), Self::layout(self.size)) }
+    }
+}
+
+/// A bump allocator whose memory is reclaimed all at once by [`Arena::reset`]
+///
+/// Allocating takes `&self`, so slices can be handed out while the owner is
+/// shared, and resetting takes `&mut self`, so no slice outlives a reset.
+#[derive(Debug, Default)]
+pub struct Arena {
+    /// Allocated chunks, each at least twice the size of the one before
+    chunks: RefCell<Vec<Chunk>>,
+    /// Index of the chunk being allocated from
+    current: Cell<usize>,
+    /// Bytes used in the current chunk
+    offset: Cell<usize>,
+}
+
+impl Arena {
+    /// Creates an empty arena, which allocates its first chunk on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a slice of `len` default values, valid until the next reset
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` needs an alignment above 16 bytes, or if `len` values of `T`
+    /// do not fit in memory.
+    ///
+    /// ```should_panic
+    /// use artimate::arena::Arena;
+    ///
+    /// let arena = Arena::new();
+    /// arena.alloc_slice::<u64>(1);
+    /// // Would overflow the end of the first chunk
+    /// arena.alloc_slice::<u64>(usize::MAX / std::mem::size_of::<u64>());
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T: Copy + Default>(&self, len: usize) -> &mut [T] {
+        let ptr = self.alloc_raw::<T>(len);
+        for i in 0..len {
+            // Safety: `alloc_raw` returned room for `len` values of `T`
+            unsafe { ptr.as_ptr().add(i).write(T::default()) };
+        }
+        // Safety: the values are initialized and the memory is not handed out again before a reset
+        unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), len) }
+    }
+
+    /// Returns a copy of `values` in the arena, valid until the next reset
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` needs an alignment above 16 bytes, or if `values` do not fit in memory.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_copy<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        let ptr = self.alloc_raw::<T>(values.len());
+        // Safety: `alloc_raw` returned room for the values, in memory not shared with `values`
+        unsafe {
+            std::ptr::copy_nonoverlapping(values.as_ptr(), ptr.as_ptr(), values.len());
+            std::slice::from_raw_parts_mut(ptr.as_ptr(), values.len())
+        }
+    }
+
+    /// Frees everything allocated since the last reset, keeping the memory for reuse
+    ///
+    /// If the last frame needed more than one chunk, they are replaced with a
+    /// single chunk big enough for all of them, so a steady workload settles on
+    /// one allocation.
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        if chunks.len() > 1 {
+            let total = chunks.iter().map(|chunk| chunk.size).sum();
+            chunks.clear();
+            chunks.push(Chunk::new(total));
+        }
+        self.current.set(0);
+        self.offset.set(0);
+    }
+
+    /// Returns the number of bytes the arena holds, used or not
+    pub fn capacity(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.size).sum()
+    }
+
+    /// Returns uninitialized, aligned room for `len` values of `T`
+    fn alloc_raw<T>(&self, len: usize) -> NonNull<T> {
+        let align = align_of::<T>();
+        assert!(
+            align <= CHUNK_ALIGN,
+            "arena supports alignments up to {} bytes, not {}",
+            CHUNK_ALIGN,
+            align
+        );
+        let size = size_of::<T>()
+            .checked_mul(len)
+            .expect("arena allocation size overflows");
+        if size == 0 {
+            return NonNull::dangling();
+        }
+        let mut chunks = self.chunks.borrow_mut();
+        while let Some(chunk) = chunks.get(self.current.get()) {
+            let start = self.offset.get().next_multiple_of(align);
+            if start.checked_add(size).is_some_and(|end| end <= chunk.size) {
+                self.offset.set(start + size);
+                // Safety: `start + size` is within the chunk
+                return unsafe { NonNull::new_unchecked(chunk.ptr.as_ptr().add(start).cast()) };
+            }
+            self.current.set(self.current.get() + 1);
+            self.offset.set(0);
+        }
+        let previous = chunks.last().map_or(0, |chunk| chunk.size);
+        let chunk = Chunk::new(size.max(previous.saturating_mul(2)).max(MIN_CHUNK_SIZE));
+        let ptr = chunk.ptr.cast();
+        chunks.push(chunk);
+        self.current.set(chunks.len() - 1);
+        self.offset.set(size);
+        ptr
+    }
+}
@@ -0,0 +1,176 @@
+//! # Batch Rendering
+//!
+//! Headless seed sweeps with automatic curation. Each seed is applied to a copy of
+//! the model, the sketch is advanced a fixed number of frames without opening a
+//! window, and the resulting frame is kept only if it passes every rule of a
+//! [`Curation`]. An overnight sweep then leaves a folder of pre-filtered candidates.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, AppMode, Config};
+//! use artimate::batch::Curation;
+//!
+//! #[derive(Clone)]
+//! struct Model {
+//!     seed: u64,
+//! }
+//!
+//! fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+//!     vec![(model.seed % 256) as u8; (app.config.width * app.config.height * 4) as usize]
+//! }
+//!
+//! let mut app = App::app(Model { seed: 0 }, Config::with_dims(512, 512), |_, m| m, draw);
+//! let curation = Curation::new()
+//!     .min_brightness(0.1)
+//!     .min_coverage(0.05)
+//!     .max_symmetry(0.95)
+//!     .require("not too bright", |stats, _| stats.mean_luminance < 0.9);
+//! let report = app
+//!     .sweep_seeds(0..1000, |model, seed| model.seed = seed, 120, &curation, "sweep")
+//!     .unwrap();
+//! println!("kept {} of 1000 seeds", report.kept.len());
+//! ```
+
+use crate::app::{save_frame, App, FrameStats, SeedFn};
+use crate::buffer::Image;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Predicate deciding whether a rendered frame is kept
+pub type Predicate = Box<dyn Fn(&FrameStats, &Image) -> bool>;
+
+/// A set of named rules a rendered frame must pass to be kept
+#[derive(Default)]
+pub struct Curation {
+    rules: Vec<(String, Predicate)>,
+}
+
+impl Curation {
+    /// Creates a curation that keeps every frame
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule that keeps only frames for which `predicate` returns true
+    ///
+    /// `name` describes the rule and is reported for frames that fail it.
+    pub fn require<F>(mut self, name: &str, predicate: F) -> Self
+    where
+        F: Fn(&FrameStats, &Image) -> bool + 'static,
+    {
+        self.rules.push((name.to_string(), Box::new(predicate)));
+        self
+    }
+
+    /// Rejects frames whose mean luminance is below `mean`
+    pub fn min_brightness(self, mean: f32) -> Self {
+        self.require("too dark", move |stats, _| stats.mean_luminance >= mean)
+    }
+
+    /// Rejects frames in which less than `coverage` of the pixels differ from the background
+    pub fn min_coverage(self, coverage: f32) -> Self {
+        self.require("too empty", move |stats, _| stats.coverage >= coverage)
+    }
+
+    /// Rejects frames whose mirror symmetry, as measured by `symmetry`, exceeds `max`
+    pub fn max_symmetry(self, max: f32) -> Self {
+        self.require("too symmetric", move |_, image| symmetry(image) <= max)
+    }
+
+    /// Returns the name of the first rule the frame fails, or None if it passes them all
+    pub fn check(&self, stats: &FrameStats, image: &Image) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(_, predicate)| !predicate(stats, image))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Returns how closely an image matches its left-right mirror image, from 0 to 1
+///
+/// 1 means the image is perfectly symmetric about its vertical center line.
+pub fn symmetry(image: &Image) -> f32 {
+    let (w, h) = (image.width, image.height);
+    if w < 2 || h == 0 {
+        return 1.0;
+    }
+    let mut diff = 0u64;
+    for y in 0..h {
+        for x in 0..w / 2 {
+            let a = image.pixel(x, y);
+            let b = image.pixel(w - 1 - x, y);
+            diff += (0..3).map(|c| a[c].abs_diff(b[c]) as u64).sum::<u64>();
+        }
+    }
+    let count = (w / 2) as u64 * h as u64 * 3;
+    1.0 - diff as f32 / (count as f32 * 255.0)
+}
+
+/// Outcome of a seed sweep
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SweepReport {
+    /// Seeds whose frames passed curation, with the path each frame was saved to
+    pub kept: Vec<(u64, PathBuf)>,
+    /// Seeds whose frames were discarded, with the name of the rule they failed
+    pub rejected: Vec<(u64, String)>,
+}
+
+impl<Mode, M> App<Mode, M>
+where
+    M: Clone,
+{
+    /// Renders one frame per seed without a window, saving those that pass `curation`
+    ///
+    /// For every seed, `seed_fn` is applied to a copy of the current model, the update
+    /// function runs for `frames` frames with deterministic time, and the export frame
+    /// is curated against `config.clear_color` as background. Kept frames are saved
+    /// to `dir` as `seed_<seed>.png`, or with the extension of `Config::export_format`
+    /// if another format is configured. The app's model, time and frame count are left
+    /// unchanged.
+    ///
+    /// # Returns
+    /// * `Ok(SweepReport)` - Which seeds were kept and which were rejected
+    /// * `Err(io::Error)` - If `dir` could not be created or a frame could not be written
+    pub fn sweep_seeds(
+        &mut self,
+        seeds: impl IntoIterator<Item = u64>,
+        seed_fn: SeedFn<M>,
+        frames: u32,
+        curation: &Curation,
+        dir: impl AsRef<Path>,
+    ) -> io::Result<SweepReport> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let original = (self.model.clone(), self.time, self.frame_count);
+        let mut report = SweepReport::default();
+
+        for seed in seeds {
+            let mut model = original.0.clone();
+            seed_fn(&mut model, seed);
+            self.model = model;
+            let (data, width, height) = self.render_headless(frames);
+            let Some(image) = Image::from_raw(width, height, data) else {
+                report.rejected.push((seed, "wrong frame size".to_string()));
+                continue;
+            };
+            let stats = FrameStats::from_frame(&image.data, self.config.clear_color);
+            if let Some(rule) = curation.check(&stats, &image) {
+                report.rejected.push((seed, rule.to_string()));
+                continue;
+            }
+            let extension = self.config.export_format.extension();
+            let path = dir.join(format!("seed_{}.{}", seed, extension));
+            save_frame(
+                image.into_raw(),
+                path.to_string_lossy().to_string(),
+                width,
+                height,
+                &self.config.save_options(),
+            )?;
+            report.kept.push((seed, path));
+        }
+
+        (self.model, self.time, self.frame_count) = original;
+        Ok(report)
+    }
+}
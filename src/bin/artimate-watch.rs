@@ -0,0 +1,98 @@
+//! # artimate-watch
+//!
+//! Restarts a sketch whenever its source, parameters or assets change.
+//!
+//! ## Usage
+//! ```bash
+//! cargo install artimate --features watch
+//! artimate-watch
+//! artimate-watch --watch params.txt --watch assets -- cargo run --release --example flow
+//! ```
+
+use artimate::watch::{supervise, Watcher};
+use std::process::{Command, ExitCode};
+use std::time::Duration;
+
+const USAGE: &str =
+    "usage: artimate-watch [--watch <path>]... [--interval <ms>] [-- <command> [args]...]";
+
+/// Paths watched when none are given
+const DEFAULT_PATHS: [&str; 3] = ["src", "assets", "Cargo.toml"];
+
+/// Shortest polling interval accepted, so the source tree is not walked in a busy loop
+const MIN_INTERVAL_MS: u64 = 50;
+
+/// Command run when none is given
+const DEFAULT_COMMAND: [&str; 3] = ["cargo", "run", "--release"];
+
+struct Args {
+    paths: Vec<String>,
+    interval: Option<Duration>,
+    command: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut watcher = Watcher::new(&args.paths);
+    if let Some(interval) = args.interval {
+        watcher = watcher.interval(interval);
+    }
+    println!("Watching {}", args.paths.join(", "));
+    let mut command = Command::new(&args.command[0]);
+    command.args(&args.command[1..]);
+    match supervise(&mut command, &mut watcher) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: could not run {}: {}", args.command[0], err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut paths = Vec::new();
+    let mut interval = None;
+    let mut command = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--watch" | "-w" => {
+                let value = iter.next().ok_or("missing value for --watch")?;
+                paths.push(value.clone());
+            }
+            "--interval" | "-i" => {
+                let value = iter.next().ok_or("missing value for --interval")?;
+                let ms: u64 = value
+                    .parse()
+                    .map_err(|_| format!("invalid interval '{}'", value))?;
+                if ms < MIN_INTERVAL_MS {
+                    return Err(format!("interval must be at least {} ms", MIN_INTERVAL_MS));
+                }
+                interval = Some(Duration::from_millis(ms));
+            }
+            "--" => {
+                command.extend(iter.by_ref().cloned());
+            }
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    if paths.is_empty() {
+        paths = DEFAULT_PATHS.map(String::from).to_vec();
+    }
+    if command.is_empty() {
+        command = DEFAULT_COMMAND.map(String::from).to_vec();
+    }
+    Ok(Args {
+        paths,
+        interval,
+        command,
+    })
+}
@@ -0,0 +1,67 @@
+//! # cargo-artimate
+//!
+//! Cargo subcommand for creating new Artimate sketches.
+//!
+//! ## Usage
+//! ```bash
+//! cargo install artimate --features cli
+//! cargo artimate new my_sketch
+//! cargo artimate new my_sketch --template sketch
+//! ```
+
+use artimate::scaffold::{new_sketch, Template};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: cargo artimate new <name> [--template sketch|app]";
+
+fn main() -> ExitCode {
+    // When run as `cargo artimate`, cargo passes "artimate" as the first argument
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("artimate") {
+        args.remove(0);
+    }
+
+    let (name, template) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            eprintln!("{}\n{}", msg, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match new_sketch(&name, template) {
+        Ok(dir) => {
+            println!("Created {} from the {} template", dir.display(), template);
+            println!("Run it with: cd {} && cargo run --release", dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<(String, Template), String> {
+    let mut iter = args.iter();
+    match iter.next().map(String::as_str) {
+        Some("new") => {}
+        Some(other) => return Err(format!("unknown command '{}'", other)),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut name = None;
+    let mut template = Template::default();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--template" | "-t" => {
+                let value = iter.next().ok_or("missing value for --template")?;
+                template = value.parse()?;
+            }
+            _ if name.is_none() => name = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    let name = name.ok_or("missing sketch name")?;
+    Ok((name, template))
+}
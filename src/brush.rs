@@ -0,0 +1,686 @@
+//! # Brush
+//!
+//! Anti-aliased strokes with width and opacity profiles, flow field painting
+//! (tracing strokes along a vector field and rasterizing them in a single call),
+//! and a stamp-based `Brush` with textured tips, jitter and pressure for
+//! painterly and mouse-drawn work.
+//!
+//! ```
+//! use artimate::brush::{flow_strokes, StrokeStyle};
+//! use artimate::noise::{curl2, Simplex};
+//! use artimate::random::Rng;
+//!
+//! let (width, height) = (320, 240);
+//! let mut frame = vec![255; (width * height * 4) as usize];
+//! let noise = Simplex::new(3);
+//! let field = |x: f32, y: f32| curl2(&noise, x * 0.005, y * 0.005);
+//! let palette = [[20, 60, 120, 255], [200, 80, 40, 255], [30, 30, 30, 255]];
+//! let mut rng = Rng::new(3);
+//!
+//! flow_strokes(&mut frame, width, field, 200, 80.0, &palette, &mut rng);
+//! ```
+
+use crate::buffer::Image;
+use crate::random::Rng;
+
+/// How a stroke property varies from the start (`t = 0`) to the end (`t = 1`) of a stroke
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Profile {
+    /// The same along the whole stroke
+    Constant,
+    /// Thin at both ends and full in the middle, like a pressed and lifted brush
+    #[default]
+    Taper,
+    /// Full at the start, fading linearly to nothing at the end
+    Fade,
+    /// A custom function of `t` returning a factor in [0, 1]
+    Custom(fn(f32) -> f32),
+}
+
+impl Profile {
+    /// Returns the factor at `t` in [0, 1] along the stroke
+    pub fn at(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Profile::Constant => 1.0,
+            Profile::Taper => (std::f32::consts::PI * t).sin(),
+            Profile::Fade => 1.0 - t,
+            Profile::Custom(f) => f(t).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Width and opacity of a stroke and their profiles along it
+#[derive(Debug, Clone, Copy)]
+pub struct StrokeStyle {
+    /// Widest width of the stroke in pixels
+    pub weight: f32,
+    /// Highest opacity of the stroke, in [0, 1], multiplied with the color's alpha
+    pub opacity: f32,
+    /// How the width varies along the stroke
+    pub width_profile: Profile,
+    /// How the opacity varies along the stroke
+    pub opacity_profile: Profile,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StrokeStyle {
+    /// Creates a tapered 3 pixel stroke at 80% opacity
+    pub fn new() -> Self {
+        Self {
+            weight: 3.0,
+            opacity: 0.8,
+            width_profile: Profile::Taper,
+            opacity_profile: Profile::Constant,
+        }
+    }
+
+    /// Sets the widest width of the stroke in pixels
+    pub fn weight(self, weight: f32) -> Self {
+        Self { weight, ..self }
+    }
+
+    /// Sets the highest opacity of the stroke, in [0, 1]
+    pub fn opacity(self, opacity: f32) -> Self {
+        Self { opacity, ..self }
+    }
+
+    /// Sets how the width varies along the stroke
+    pub fn width_profile(self, width_profile: Profile) -> Self {
+        Self {
+            width_profile,
+            ..self
+        }
+    }
+
+    /// Sets how the opacity varies along the stroke
+    pub fn opacity_profile(self, opacity_profile: Profile) -> Self {
+        Self {
+            opacity_profile,
+            ..self
+        }
+    }
+}
+
+/// Traces a streamline of a vector field from `start` for up to `length` pixels
+///
+/// Steps one pixel at a time along the normalized field direction with the
+/// midpoint method. Tracing stops early where the field vanishes or the line
+/// leaves the `width` x `height` area.
+///
+/// # Returns
+/// The points of the line, starting with `start`
+pub fn trace(
+    field: impl Fn(f32, f32) -> [f32; 2],
+    start: (f32, f32),
+    length: f32,
+    width: u32,
+    height: u32,
+) -> Vec<(f32, f32)> {
+    let direction = |x: f32, y: f32| {
+        let [vx, vy] = field(x, y);
+        let len = (vx * vx + vy * vy).sqrt();
+        (len > f32::EPSILON && len.is_finite()).then(|| (vx / len, vy / len))
+    };
+    let inside = |(x, y): (f32, f32)| x >= 0.0 && y >= 0.0 && x < width as f32 && y < height as f32;
+    let mut points = vec![start];
+    let (mut x, mut y) = start;
+    for _ in 0..length.max(0.0) as usize {
+        let Some((dx, dy)) = direction(x, y) else {
+            break;
+        };
+        let Some((dx, dy)) = direction(x + dx * 0.5, y + dy * 0.5) else {
+            break;
+        };
+        (x, y) = (x + dx, y + dy);
+        if !inside((x, y)) {
+            break;
+        }
+        points.push((x, y));
+    }
+    points
+}
+
+/// Draws an anti-aliased stroke through `points` into an RGBA frame
+///
+/// The stroke is built from overlapping discs along the line, sized and faded
+/// by the style's profiles, and blended into the frame once so it does not
+/// darken where it overlaps itself. The profiles advance by point index, so
+/// points should be roughly evenly spaced.
+pub fn stroke(
+    frame: &mut [u8],
+    width: u32,
+    points: &[(f32, f32)],
+    color: [u8; 4],
+    style: &StrokeStyle,
+) {
+    if width == 0 || points.is_empty() {
+        return;
+    }
+    let height = (frame.len() / 4) as u32 / width;
+    let reach = style.weight * 0.5 + 1.0;
+    let (mut x0, mut y0, mut x1, mut y1) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for &(x, y) in points {
+        (x0, y0) = (x0.min(x - reach), y0.min(y - reach));
+        (x1, y1) = (x1.max(x + reach), y1.max(y + reach));
+    }
+    let x0 = x0.floor().max(0.0) as u32;
+    let y0 = y0.floor().max(0.0) as u32;
+    let x1 = (x1.ceil().max(0.0) as u32).min(width);
+    let y1 = (y1.ceil().max(0.0) as u32).min(height);
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    // Coverage of the whole stroke, the maximum over its discs
+    let box_width = (x1 - x0) as usize;
+    let mut coverage = vec![0.0f32; box_width * (y1 - y0) as usize];
+    let last = (points.len() - 1).max(1) as f32;
+    // Discs every half pixel, so points far apart still join into a line
+    let discs = points.iter().enumerate().flat_map(|(i, &(ax, ay))| {
+        let (bx, by) = points.get(i + 1).copied().unwrap_or((ax, ay));
+        let steps = ((bx - ax).hypot(by - ay) * 2.0).ceil().max(1.0) as usize;
+        (0..steps).map(move |k| {
+            let s = k as f32 / steps as f32;
+            (
+                ax + (bx - ax) * s,
+                ay + (by - ay) * s,
+                (i as f32 + s) / last,
+            )
+        })
+    });
+    for (px, py, t) in discs {
+        let radius = style.weight * 0.5 * style.width_profile.at(t);
+        let alpha = style.opacity * style.opacity_profile.at(t);
+        if alpha <= 0.0 {
+            continue;
+        }
+        let bx0 = (px - radius - 1.0).floor().max(x0 as f32) as u32;
+        let by0 = (py - radius - 1.0).floor().max(y0 as f32) as u32;
+        let bx1 = ((px + radius + 1.0).ceil().max(0.0) as u32).min(x1);
+        let by1 = ((py + radius + 1.0).ceil().max(0.0) as u32).min(y1);
+        for y in by0..by1 {
+            for x in bx0..bx1 {
+                let (dx, dy) = (x as f32 + 0.5 - px, y as f32 + 0.5 - py);
+                let c = (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0) * alpha;
+                let cell = &mut coverage[(y - y0) as usize * box_width + (x - x0) as usize];
+                *cell = cell.max(c);
+            }
+        }
+    }
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let c = coverage[(y - y0) as usize * box_width + (x - x0) as usize];
+            if c <= 0.0 {
+                continue;
+            }
+            let alpha = c.min(1.0) * color[3] as f32 / 255.0;
+            let i = ((y * width + x) * 4) as usize;
+            for (dst, src) in frame[i..i + 3].iter_mut().zip(color) {
+                *dst = (src as f32 * alpha + *dst as f32 * (1.0 - alpha)).round() as u8;
+            }
+            frame[i + 3] = frame[i + 3].max((alpha * 255.0).round() as u8);
+        }
+    }
+}
+
+/// Paints `count` strokes that follow a vector field, "flow field painting" in one call
+///
+/// Each stroke starts at a random point of the frame, follows `field` for up to
+/// `length` pixels and is drawn in a random color from `palette` with the default
+/// `StrokeStyle`. The field maps pixel coordinates to a direction; its magnitude
+/// is ignored.
+///
+/// # Arguments
+/// * `frame` - RGBA frame buffer, `width` pixels wide
+/// * `width` - Width of the frame in pixels
+/// * `field` - Vector field giving the stroke direction at a pixel position
+/// * `count` - Number of strokes
+/// * `length` - Length of each stroke in pixels
+/// * `palette` - Stroke colors; nothing is drawn if it is empty
+/// * `rng` - Source of the start points and colors
+pub fn flow_strokes(
+    frame: &mut [u8],
+    width: u32,
+    field: impl Fn(f32, f32) -> [f32; 2],
+    count: usize,
+    length: f32,
+    palette: &[[u8; 4]],
+    rng: &mut Rng,
+) {
+    flow_strokes_styled(
+        frame,
+        width,
+        field,
+        count,
+        length,
+        palette,
+        rng,
+        &StrokeStyle::default(),
+    );
+}
+
+/// Like `flow_strokes`, drawing every stroke with `style`
+#[allow(clippy::too_many_arguments)]
+pub fn flow_strokes_styled(
+    frame: &mut [u8],
+    width: u32,
+    field: impl Fn(f32, f32) -> [f32; 2],
+    count: usize,
+    length: f32,
+    palette: &[[u8; 4]],
+    rng: &mut Rng,
+    style: &StrokeStyle,
+) {
+    if width == 0 || palette.is_empty() {
+        return;
+    }
+    let height = (frame.len() / 4) as u32 / width;
+    for _ in 0..count {
+        let start = (rng.range(0.0, width as f32), rng.range(0.0, height as f32));
+        let color = palette[rng.below(palette.len())];
+        let points = trace(&field, start, length, width, height);
+        stroke(frame, width, &points, color, style);
+    }
+}
+
+/// The shape of the mark a `Brush` stamps along its path
+#[derive(Debug, Clone)]
+pub enum Stamp {
+    /// A round tip; `hardness` in [0, 1] is the fraction of the radius drawn solid
+    /// before the edge falls off, 1 for a crisp anti-aliased disc
+    Circle {
+        /// Fraction of the radius at full opacity
+        hardness: f32,
+    },
+    /// A grayscale tip image: white paints, black is transparent, and the
+    /// image's alpha is applied on top. Stretched to the brush size.
+    Texture(Image),
+}
+
+/// A stamp-based brush for painterly strokes and mouse-drawn sketches
+///
+/// The brush walks a path, placing a stamp every `spacing` of its size. Stamps
+/// composite one by one, so opacity builds up where they overlap, as with a
+/// real brush. Jitter scatters the position, size and opacity of every stamp.
+///
+/// ```
+/// use artimate::brush::{Brush, Profile, Stamp};
+/// use artimate::random::Rng;
+///
+/// let (width, height) = (320, 240);
+/// let mut frame = vec![255; (width * height * 4) as usize];
+/// let brush = Brush::new(12.0)
+///     .stamp(Stamp::Circle { hardness: 0.3 })
+///     .flow(0.15)
+///     .jitter(0.2)
+///     .pressure(Profile::Taper);
+///
+/// // A path, e.g. the mouse positions recorded while a button was held
+/// let path: Vec<(f32, f32)> = (0..200).map(|i| (20.0 + i as f32 * 1.4, 120.0)).collect();
+/// brush.paint(&mut frame, width, &path, [40, 40, 90, 255], &mut Rng::new(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Brush {
+    /// Shape of each stamp
+    pub stamp: Stamp,
+    /// Diameter of a stamp in pixels
+    pub size: f32,
+    /// Distance between stamps as a fraction of the stamp size
+    pub spacing: f32,
+    /// Opacity of a single stamp in [0, 1]; overlapping stamps build up
+    pub flow: f32,
+    /// Random offset of each stamp as a fraction of the stamp size
+    pub jitter: f32,
+    /// Random size variation of each stamp as a fraction of the size
+    pub size_jitter: f32,
+    /// Random opacity variation of each stamp as a fraction of the flow
+    pub flow_jitter: f32,
+    /// Scales the size and flow along the path, like pen pressure
+    pub pressure: Profile,
+    /// Whether textured stamps turn to follow the direction of the path
+    pub follow_path: bool,
+}
+
+impl Brush {
+    /// Creates a round brush of the given diameter with no jitter and constant pressure
+    pub fn new(size: f32) -> Self {
+        Self {
+            stamp: Stamp::Circle { hardness: 0.8 },
+            size,
+            spacing: 0.15,
+            flow: 0.5,
+            jitter: 0.0,
+            size_jitter: 0.0,
+            flow_jitter: 0.0,
+            pressure: Profile::Constant,
+            follow_path: true,
+        }
+    }
+
+    /// Sets the shape of each stamp
+    pub fn stamp(self, stamp: Stamp) -> Self {
+        Self { stamp, ..self }
+    }
+
+    /// Sets the distance between stamps as a fraction of the stamp size
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Sets the opacity of a single stamp
+    pub fn flow(self, flow: f32) -> Self {
+        Self { flow, ..self }
+    }
+
+    /// Sets the random offset of each stamp as a fraction of the stamp size
+    pub fn jitter(self, jitter: f32) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Sets the random size variation of each stamp as a fraction of the size
+    pub fn size_jitter(self, size_jitter: f32) -> Self {
+        Self {
+            size_jitter,
+            ..self
+        }
+    }
+
+    /// Sets the random opacity variation of each stamp as a fraction of the flow
+    pub fn flow_jitter(self, flow_jitter: f32) -> Self {
+        Self {
+            flow_jitter,
+            ..self
+        }
+    }
+
+    /// Sets how the size and flow vary along the path
+    pub fn pressure(self, pressure: Profile) -> Self {
+        Self { pressure, ..self }
+    }
+
+    /// Sets whether textured stamps turn to follow the direction of the path
+    pub fn follow_path(self, follow_path: bool) -> Self {
+        Self {
+            follow_path,
+            ..self
+        }
+    }
+
+    /// Paints the brush along `path` into an RGBA frame
+    ///
+    /// A single point paints one stamp.
+    pub fn paint(
+        &self,
+        frame: &mut [u8],
+        width: u32,
+        path: &[(f32, f32)],
+        color: [u8; 4],
+        rng: &mut Rng,
+    ) {
+        let Some(&first) = path.first() else {
+            return;
+        };
+        let total: f32 = path
+            .windows(2)
+            .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+            .sum();
+        if total <= 0.0 {
+            self.dab(frame, width, first, 0.0, 1.0, color, rng);
+            return;
+        }
+
+        // Walk the path by arc length, stamping whenever the next spacing is reached
+        let mut travelled = 0.0;
+        let mut next = 0.0;
+        for w in path.windows(2) {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            let segment = (x1 - x0).hypot(y1 - y0);
+            if segment <= 0.0 {
+                continue;
+            }
+            let angle = (y1 - y0).atan2(x1 - x0);
+            while next <= travelled + segment {
+                let s = (next - travelled) / segment;
+                let pressure = self.pressure.at(next / total);
+                let point = (x0 + (x1 - x0) * s, y0 + (y1 - y0) * s);
+                self.dab(frame, width, point, angle, pressure, color, rng);
+                next += (self.spacing * self.size * pressure).max(0.5);
+            }
+            travelled += segment;
+        }
+    }
+
+    /// Composites one jittered stamp centered at `point`
+    #[allow(clippy::too_many_arguments)]
+    fn dab(
+        &self,
+        frame: &mut [u8],
+        width: u32,
+        point: (f32, f32),
+        angle: f32,
+        pressure: f32,
+        color: [u8; 4],
+        rng: &mut Rng,
+    ) {
+        let size = self.size * pressure * (1.0 + self.size_jitter * rng.range(-1.0, 1.0));
+        let flow = self.flow * pressure * (1.0 + self.flow_jitter * rng.range(-1.0, 1.0));
+        let cx = point.0 + self.jitter * self.size * rng.range(-1.0, 1.0);
+        let cy = point.1 + self.jitter * self.size * rng.range(-1.0, 1.0);
+        let radius = size * 0.5;
+        let flow = flow.clamp(0.0, 1.0) * color[3] as f32 / 255.0;
+        if width == 0 || radius <= 0.0 || flow <= 0.0 {
+            return;
+        }
+        let height = (frame.len() / 4) as u32 / width;
+        let reach = match self.stamp {
+            Stamp::Circle { .. } => radius + 1.0,
+            // The corners of a rotated square tip
+            Stamp::Texture(_) => radius * std::f32::consts::SQRT_2 + 1.0,
+        };
+        let x0 = (cx - reach).floor().max(0.0) as u32;
+        let y0 = (cy - reach).floor().max(0.0) as u32;
+        let x1 = ((cx + reach).ceil().max(0.0) as u32).min(width);
+        let y1 = ((cy + reach).ceil().max(0.0) as u32).min(height);
+        let (sin, cos) = if self.follow_path {
+            (-angle).sin_cos()
+        } else {
+            (0.0, 1.0)
+        };
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                let mask = match &self.stamp {
+                    Stamp::Circle { hardness } => {
+                        let d = (dx * dx + dy * dy).sqrt();
+                        let solid = radius * hardness.clamp(0.0, 1.0);
+                        let edge = (radius - solid).max(0.5);
+                        ((radius + 0.5 - d) / (edge + 0.5)).clamp(0.0, 1.0)
+                    }
+                    Stamp::Texture(tip) => {
+                        let u = (dx * cos - dy * sin) / size + 0.5;
+                        let v = (dx * sin + dy * cos) / size + 0.5;
+                        texture_mask(tip, u, v)
+                    }
+                };
+                let alpha = mask * flow;
+                if alpha <= 0.0 {
+                    continue;
+                }
+                let i = ((y * width + x) * 4) as usize;
+                for (dst, src) in frame[i..i + 3].iter_mut().zip(color) {
+                    *dst = (src as f32 * alpha + *dst as f32 * (1.0 - alpha)).round() as u8;
+                }
+                let a = frame[i + 3] as f32 / 255.0;
+                frame[i + 3] = ((alpha + a * (1.0 - alpha)) * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+/// Returns the opacity of a texture tip at `(u, v)` in [0, 1], 0 outside the tip
+fn texture_mask(tip: &Image, u: f32, v: f32) -> f32 {
+    if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) || tip.width == 0 || tip.height == 0 {
+        return 0.0;
+    }
+    let [r, g, b, a] = tip.pixel(
+        (u * tip.width as f32) as u32,
+        (v * tip.height as f32) as u32,
+    );
+    let luminance = 0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32;
+    luminance / 255.0 * a as f32 / 255.0
+}
+
+/// Fills a polygon into an RGBA frame with anti-aliased edges, using the even-odd rule
+///
+/// `polygon` lists the vertices in order; the last connects back to the first.
+pub fn fill_polygon(frame: &mut [u8], width: u32, polygon: &[(f32, f32)], color: [u8; 4]) {
+    if width == 0 || polygon.len() < 3 {
+        return;
+    }
+    let height = (frame.len() / 4) as u32 / width;
+    let (mut top, mut bottom) = (f32::MAX, f32::MIN);
+    for &(_, y) in polygon {
+        (top, bottom) = (top.min(y), bottom.max(y));
+    }
+    let y0 = top.floor().max(0.0) as u32;
+    let y1 = (bottom.ceil().max(0.0) as u32).min(height);
+    let alpha = color[3] as f32 / 255.0;
+    let mut coverage = vec![0.0f32; width as usize + 1];
+    let mut crossings = Vec::new();
+    for y in y0..y1 {
+        coverage.fill(0.0);
+        // Four scanlines per pixel row, with exact horizontal coverage at span ends
+        for sub in 0..SUBSAMPLES {
+            let sy = y as f32 + (sub as f32 + 0.5) / SUBSAMPLES as f32;
+            crossings.clear();
+            for (i, &(ax, ay)) in polygon.iter().enumerate() {
+                let (bx, by) = polygon[(i + 1) % polygon.len()];
+                if (ay <= sy) != (by <= sy) {
+                    crossings.push(ax + (sy - ay) / (by - ay) * (bx - ax));
+                }
+            }
+            crossings.sort_by(f32::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let (left, right) = (
+                    span[0].clamp(0.0, width as f32),
+                    span[1].clamp(0.0, width as f32),
+                );
+                add_span(&mut coverage, left, right, 1.0 / SUBSAMPLES as f32);
+            }
+        }
+        for x in 0..width {
+            let c = coverage[x as usize].min(1.0) * alpha;
+            if c <= 0.0 {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            for (dst, src) in frame[i..i + 3].iter_mut().zip(color) {
+                *dst = (src as f32 * c + *dst as f32 * (1.0 - c)).round() as u8;
+            }
+            let a = frame[i + 3] as f32 / 255.0;
+            frame[i + 3] = ((c + a * (1.0 - c)) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// Vertical samples per pixel row when filling polygons
+const SUBSAMPLES: u32 = 4;
+
+/// Adds `weight` times the covered fraction of each pixel between `left` and `right`
+fn add_span(coverage: &mut [f32], left: f32, right: f32, weight: f32) {
+    if right <= left {
+        return;
+    }
+    let (first, last) = (left.floor() as usize, right.floor() as usize);
+    if first == last {
+        coverage[first] += (right - left) * weight;
+        return;
+    }
+    coverage[first] += (first as f32 + 1.0 - left) * weight;
+    for c in &mut coverage[first + 1..last] {
+        *c += weight;
+    }
+    coverage[last] += (right - last as f32) * weight;
+}
+
+/// Paints a watercolor blotch: many translucent, randomly deformed copies of a polygon
+///
+/// Following Tyler Hobbs' technique, the polygon is first deformed into an
+/// irregular base shape by recursively displacing edge midpoints. Each of the
+/// `layers` passes deforms the base again and fills it faintly, so the blotch is
+/// dense in the middle with soft, feathery edges. The layers build up to about 70%
+/// of the color's alpha where they all overlap. Around 30 to 60 layers look best.
+///
+/// ```
+/// use artimate::brush::watercolor;
+/// use artimate::random::Rng;
+///
+/// let (width, height) = (320, 240);
+/// let mut frame = vec![255; (width * height * 4) as usize];
+/// let hexagon: Vec<(f32, f32)> = (0..6)
+///     .map(|i| {
+///         let a = i as f32 * std::f32::consts::TAU / 6.0;
+///         (160.0 + 70.0 * a.cos(), 120.0 + 70.0 * a.sin())
+///     })
+///     .collect();
+/// watercolor(&mut frame, width, &hexagon, [200, 60, 50, 255], 40, &mut Rng::new(9));
+/// ```
+pub fn watercolor(
+    frame: &mut [u8],
+    width: u32,
+    polygon: &[(f32, f32)],
+    color: [u8; 4],
+    layers: u32,
+    rng: &mut Rng,
+) {
+    if polygon.len() < 3 || layers == 0 {
+        return;
+    }
+    let mut vertices: Vec<((f32, f32), f32)> = polygon
+        .iter()
+        .map(|&p| (p, rng.gaussian(1.0, 0.3).max(0.1)))
+        .collect();
+    for _ in 0..3 {
+        vertices = deform(&vertices, rng);
+    }
+    let alpha = 1.0 - 0.3f32.powf(1.0 / layers as f32);
+    let mut layer_color = color;
+    layer_color[3] = (color[3] as f32 * alpha).round().max(1.0) as u8;
+    for _ in 0..layers {
+        let mut layer = vertices.clone();
+        for _ in 0..3 {
+            layer = deform(&layer, rng);
+        }
+        let points: Vec<(f32, f32)> = layer.iter().map(|&(p, _)| p).collect();
+        fill_polygon(frame, width, &points, layer_color);
+    }
+}
+
+/// Inserts a randomly displaced midpoint into every edge of a polygon
+///
+/// Each vertex carries a variance that scales the displacement of its edges, and
+/// is passed on, slightly randomized, to the new midpoints, so some parts of the
+/// outline stay smooth while others spread.
+fn deform(vertices: &[((f32, f32), f32)], rng: &mut Rng) -> Vec<((f32, f32), f32)> {
+    let mut out = Vec::with_capacity(vertices.len() * 2);
+    for (i, &((ax, ay), va)) in vertices.iter().enumerate() {
+        let ((bx, by), vb) = vertices[(i + 1) % vertices.len()];
+        let length = (bx - ax).hypot(by - ay);
+        let variance = ((va + vb) * 0.5 * rng.gaussian(1.0, 0.1)).max(0.05);
+        let spread = 0.15 * length * variance;
+        let mid = (
+            (ax + bx) * 0.5 + rng.gaussian(0.0, spread),
+            (ay + by) * 0.5 + rng.gaussian(0.0, spread),
+        );
+        out.push(((ax, ay), va));
+        out.push((mid, variance));
+    }
+    out
+}
@@ -0,0 +1,558 @@
+//! # Pixel Buffers
+//!
+//! An owned RGBA image type used by artimate's utilities for loading, saving and
+//! comparing frames. The pixel layout matches the buffers returned by draw
+//! functions: rows from top to bottom, 4 bytes (R, G, B, A) per pixel.
+//!
+//! The frame operations [`clear`], [`fill_rect`], [`copy_rect`] and
+//! [`blend_rect`] work on raw frames and are used by the app for clearing and
+//! compositing. They move whole rows at a time and blend with SIMD, since these
+//! memory bound passes dominate frame time on large canvases.
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::ops::Range;
+use std::path::Path;
+
+/// An owned RGBA8 image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA pixel data, `width * height * 4` bytes
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a fully transparent black image
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Wraps an existing RGBA buffer, returning None if its length does not match the dimensions
+    pub fn from_raw(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        (data.len() == (width * height * 4) as usize).then_some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Returns the RGBA value of the pixel at `(x, y)`
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * self.width + x) * 4) as usize;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    /// Sets the RGBA value of the pixel at `(x, y)`
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = ((y * self.width + x) * 4) as usize;
+        self.data[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Consumes the image and returns its RGBA buffer
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns a copy scaled down so its longer edge is at most `max_edge` pixels
+    ///
+    /// Each output pixel is the average of the source pixels it covers. Images that
+    /// already fit are returned unchanged.
+    ///
+    /// ```
+    /// use artimate::buffer::Image;
+    ///
+    /// let thumb = Image::new(1920, 1080).thumbnail(256);
+    /// assert_eq!((thumb.width, thumb.height), (256, 144));
+    /// ```
+    pub fn thumbnail(&self, max_edge: u32) -> Self {
+        let longest = self.width.max(self.height);
+        if longest <= max_edge || max_edge == 0 {
+            return self.clone();
+        }
+        let scale = longest as f64 / max_edge as f64;
+        let width = ((self.width as f64 / scale).round() as u32).max(1);
+        let height = ((self.height as f64 / scale).round() as u32).max(1);
+        let mut thumb = Self::new(width, height);
+        for ty in 0..height {
+            let (y0, y1) = span(ty, height, self.height);
+            for tx in 0..width {
+                let (x0, x1) = span(tx, width, self.width);
+                let mut sum = [0u64; 4];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        for (total, v) in sum.iter_mut().zip(self.pixel(x, y)) {
+                            *total += v as u64;
+                        }
+                    }
+                }
+                let count = ((y1 - y0) * (x1 - x0)) as u64;
+                thumb.set_pixel(tx, ty, sum.map(|total| (total / count) as u8));
+            }
+        }
+        thumb
+    }
+
+    /// Returns a copy scaled down by a whole `factor`, averaging each block of pixels
+    ///
+    /// Colors are weighted by alpha, so transparent pixels do not darken
+    /// antialiased edges. Edge pixels left over when the size is not a multiple of
+    /// `factor` are dropped.
+    ///
+    /// ```
+    /// use artimate::buffer::Image;
+    ///
+    /// let mut image = Image::new(2, 2);
+    /// image.set_pixel(0, 0, [255, 0, 0, 255]);
+    /// image.set_pixel(1, 1, [255, 0, 0, 255]);
+    /// assert_eq!(image.downsample(2).pixel(0, 0), [255, 0, 0, 128]);
+    /// ```
+    pub fn downsample(&self, factor: u32) -> Self {
+        let factor = factor.max(1);
+        let mut small = Self::new(self.width / factor, self.height / factor);
+        let count = (factor * factor) as u64;
+        for sy in 0..small.height {
+            for sx in 0..small.width {
+                let (mut rgb, mut alpha) = ([0u64; 3], 0u64);
+                for y in sy * factor..(sy + 1) * factor {
+                    for x in sx * factor..(sx + 1) * factor {
+                        let [r, g, b, a] = self.pixel(x, y).map(|v| v as u64);
+                        rgb = [rgb[0] + r * a, rgb[1] + g * a, rgb[2] + b * a];
+                        alpha += a;
+                    }
+                }
+                let [r, g, b] =
+                    rgb.map(|total| (total + alpha / 2).checked_div(alpha).unwrap_or(0));
+                let a = (alpha + count / 2) / count;
+                small.set_pixel(sx, sy, [r, g, b, a].map(|v| v as u8));
+            }
+        }
+        small
+    }
+
+    /// Loads a PNG file, converting any bit depth and color type to RGBA8
+    pub fn load_png(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(io::Error::other)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(io::Error::other)?;
+        buf.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buf,
+            png::ColorType::Rgb => buf
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "indexed PNG was not expanded",
+                ))
+            }
+        };
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            data,
+        })
+    }
+
+    /// Saves the image as an 8-bit RGBA PNG file
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.data)
+            .map_err(io::Error::other)
+    }
+}
+
+/// Sets every pixel of an RGBA frame to `color`
+///
+/// Fills by doubling copies, which run at memory bandwidth.
+///
+/// ```
+/// use artimate::buffer::clear;
+///
+/// let mut frame = vec![0; 3 * 4];
+/// clear(&mut frame, [10, 20, 30, 255]);
+/// assert_eq!(frame, [10, 20, 30, 255].repeat(3));
+/// ```
+pub fn clear(frame: &mut [u8], color: [u8; 4]) {
+    let len = frame.len() / 4 * 4;
+    if len == 0 {
+        return;
+    }
+    frame[..4].copy_from_slice(&color);
+    let mut filled = 4;
+    while filled < len {
+        let n = filled.min(len - filled);
+        frame.copy_within(..n, filled);
+        filled += n;
+    }
+}
+
+/// Sets the pixels of an RGBA frame within `(x0, y0, x1, y1)` to `color`, clipped to the frame
+pub fn fill_rect(
+    frame: &mut [u8],
+    width: u32,
+    (x0, y0, x1, y1): (u32, u32, u32, u32),
+    color: [u8; 4],
+) {
+    let height = frame_height(frame, width);
+    let (x0, x1) = (x0.min(width), x1.min(width));
+    if x0 >= x1 {
+        return;
+    }
+    for y in y0.min(height)..y1.min(height) {
+        let start = ((y * width + x0) * 4) as usize;
+        clear(&mut frame[start..start + ((x1 - x0) * 4) as usize], color);
+    }
+}
+
+/// Copies the pixels of `src` within `rect` into `dst` with the rectangle's corner at `to`
+///
+/// `rect` is `(x0, y0, x1, y1)` in `src` and `to` may be negative; the copy is
+/// clipped to both frames. Rows are copied with single memory moves.
+///
+/// ```
+/// use artimate::buffer::copy_rect;
+///
+/// let src = [1, 1, 1, 255, 2, 2, 2, 255, 3, 3, 3, 255, 4, 4, 4, 255];
+/// let mut dst = vec![0; 3 * 3 * 4];
+/// copy_rect(&mut dst, 3, &src, 2, (0, 0, 2, 2), (1, 1));
+/// assert_eq!(&dst[16..24], &[1, 1, 1, 255, 2, 2, 2, 255]);
+/// assert_eq!(&dst[28..36], &[3, 3, 3, 255, 4, 4, 4, 255]);
+/// ```
+pub fn copy_rect(
+    dst: &mut [u8],
+    dst_width: u32,
+    src: &[u8],
+    src_width: u32,
+    rect: (u32, u32, u32, u32),
+    to: (i32, i32),
+) {
+    for (d, s) in clipped_rows(dst, dst_width, src, src_width, rect, to) {
+        dst[d.clone()].copy_from_slice(&src[s]);
+    }
+}
+
+/// Blends the pixels of `src` within `rect` over `dst` with the rectangle's corner at `to`
+///
+/// Straight alpha source over: colors are mixed by the source alpha and the
+/// alpha channels are combined, so layers stack like the window composites them.
+/// Arguments and clipping are as for [`copy_rect`]. On x86_64 four pixels are
+/// blended at a time with SSE2; elsewhere a scalar loop the compiler vectorizes
+/// is used, giving identical results.
+///
+/// ```
+/// use artimate::buffer::blend_rect;
+///
+/// let layer = [255, 0, 0, 128];
+/// let mut frame = vec![0, 0, 255, 255];
+/// blend_rect(&mut frame, 1, &layer, 1, (0, 0, 1, 1), (0, 0));
+/// assert_eq!(frame, [128, 0, 127, 255]);
+/// ```
+pub fn blend_rect(
+    dst: &mut [u8],
+    dst_width: u32,
+    src: &[u8],
+    src_width: u32,
+    rect: (u32, u32, u32, u32),
+    to: (i32, i32),
+) {
+    for (d, s) in clipped_rows(dst, dst_width, src, src_width, rect, to) {
+        blend_row(&mut dst[d], &src[s]);
+    }
+}
+
+/// Returns the height of an RGBA frame of the given width
+fn frame_height(frame: &[u8], width: u32) -> u32 {
+    if width == 0 {
+        0
+    } else {
+        (frame.len() / (width as usize * 4)) as u32
+    }
+}
+
+/// Returns the byte ranges of matching rows in `dst` and `src` for a clipped rectangle copy
+fn clipped_rows(
+    dst: &[u8],
+    dst_width: u32,
+    src: &[u8],
+    src_width: u32,
+    (x0, y0, x1, y1): (u32, u32, u32, u32),
+    (tx, ty): (i32, i32),
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let (dst_height, src_height) = (frame_height(dst, dst_width), frame_height(src, src_width));
+    // Clip the source rectangle to the source frame, then both to the destination
+    let (x1, y1) = (x1.min(src_width) as i64, y1.min(src_height) as i64);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let (dx, dy) = (tx as i64 - x0, ty as i64 - y0);
+    let left = x0.max(-dx);
+    let right = x1.min(dst_width as i64 - dx);
+    let top = y0.max(-dy);
+    let bottom = y1.min(dst_height as i64 - dy);
+    if left >= right {
+        return Vec::new();
+    }
+    (top..bottom)
+        .map(|y| {
+            let s = ((y * src_width as i64 + left) * 4) as usize;
+            let d = (((y + dy) * dst_width as i64 + left + dx) * 4) as usize;
+            let n = ((right - left) * 4) as usize;
+            (d..d + n, s..s + n)
+        })
+        .collect()
+}
+
+/// Blends a row of straight alpha pixels over another of the same length
+fn blend_row(dst: &mut [u8], src: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    let done = {
+        let n = dst.len() / 16 * 16;
+        // SAFETY: SSE2 is part of the x86_64 baseline
+        unsafe { blend_row_sse2(&mut dst[..n], &src[..n]) };
+        n
+    };
+    #[cfg(not(target_arch = "x86_64"))]
+    let done = 0;
+    for (d, s) in dst[done..]
+        .chunks_exact_mut(4)
+        .zip(src[done..].chunks_exact(4))
+    {
+        let a = s[3] as u32;
+        for c in 0..3 {
+            d[c] = div255(s[c] as u32 * a + d[c] as u32 * (255 - a)) as u8;
+        }
+        d[3] = div255(255 * a + d[3] as u32 * (255 - a)) as u8;
+    }
+}
+
+/// Returns `x / 255` rounded to nearest, for `x` up to 255 * 255
+#[inline]
+fn div255(x: u32) -> u32 {
+    let x = x + 128;
+    (x + (x >> 8)) >> 8
+}
+
+/// Blends rows four pixels at a time, with the same arithmetic as the scalar loop
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn blend_row_sse2(dst: &mut [u8], src: &[u8]) {
+    use std::arch::x86_64::*;
+    let zero = _mm_setzero_si128();
+    let max = _mm_set1_epi16(255);
+    let bias = _mm_set1_epi16(128);
+    // Blending 255 into the alpha lanes turns the color formula into alpha over
+    let opaque = _mm_set1_epi32(0xFF00_0000_u32 as i32);
+    for (d, s) in dst.chunks_exact_mut(16).zip(src.chunks_exact(16)) {
+        let sv = _mm_loadu_si128(s.as_ptr() as *const __m128i);
+        let dv = _mm_loadu_si128(d.as_ptr() as *const __m128i);
+        let colors = _mm_or_si128(sv, opaque);
+        let half = |color: __m128i, dest: __m128i, alpha: __m128i| {
+            // Broadcast each pixel's alpha to its four lanes
+            let a = _mm_shufflehi_epi16::<0xFF>(_mm_shufflelo_epi16::<0xFF>(alpha));
+            let x = _mm_add_epi16(
+                _mm_mullo_epi16(color, a),
+                _mm_mullo_epi16(dest, _mm_sub_epi16(max, a)),
+            );
+            let x = _mm_add_epi16(x, bias);
+            _mm_srli_epi16::<8>(_mm_add_epi16(x, _mm_srli_epi16::<8>(x)))
+        };
+        let lo = half(
+            _mm_unpacklo_epi8(colors, zero),
+            _mm_unpacklo_epi8(dv, zero),
+            _mm_unpacklo_epi8(sv, zero),
+        );
+        let hi = half(
+            _mm_unpackhi_epi8(colors, zero),
+            _mm_unpackhi_epi8(dv, zero),
+            _mm_unpackhi_epi8(sv, zero),
+        );
+        _mm_storeu_si128(d.as_mut_ptr() as *mut __m128i, _mm_packus_epi16(lo, hi));
+    }
+}
+
+/// How the color channels of a frame relate to its alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// Colors are independent of alpha, as PNG files store them
+    #[default]
+    Straight,
+    /// Colors are already multiplied by alpha, as tiny-skia and wassily pixmaps store them
+    Premultiplied,
+}
+
+/// Minimum number of translucent pixels before [`looks_premultiplied`] decides
+const MIN_TRANSLUCENT: usize = 16;
+
+/// Returns true if the translucent pixels of an RGBA frame look premultiplied
+///
+/// Premultiplied pixels never have a color channel above their alpha, and the
+/// brightest channel of a saturated color sits at the alpha itself. A frame is
+/// considered premultiplied when no pixel has a channel above its alpha and at
+/// least a quarter of its translucent pixels have one equal to it. Frames with
+/// few translucent pixels are never flagged.
+///
+/// ```
+/// use artimate::buffer::looks_premultiplied;
+///
+/// let edge = [128, 64, 0, 128].repeat(32);
+/// assert!(looks_premultiplied(&edge));
+/// let straight = [255, 128, 0, 128].repeat(32);
+/// assert!(!looks_premultiplied(&straight));
+/// ```
+pub fn looks_premultiplied(frame: &[u8]) -> bool {
+    let (mut translucent, mut at_alpha) = (0, 0);
+    for px in frame.chunks_exact(4) {
+        let a = px[3];
+        let max = px[0].max(px[1]).max(px[2]);
+        if max > a {
+            return false;
+        }
+        if a > 0 && a < 255 {
+            translucent += 1;
+            if max + 1 >= a {
+                at_alpha += 1;
+            }
+        }
+    }
+    translucent >= MIN_TRANSLUCENT && at_alpha * 4 >= translucent
+}
+
+/// Converts premultiplied RGBA pixels, as drawn by tiny-skia and wassily, to straight alpha in place
+///
+/// Opaque and fully transparent pixels are left untouched, so frames without
+/// translucency cost a single pass of alpha checks.
+///
+/// ```
+/// use artimate::buffer::unpremultiply;
+///
+/// let mut pixels = [64, 32, 0, 128, 10, 20, 30, 255];
+/// unpremultiply(&mut pixels);
+/// assert_eq!(pixels, [128, 64, 0, 128, 10, 20, 30, 255]);
+/// ```
+pub fn unpremultiply(data: &mut [u8]) {
+    for px in data.chunks_exact_mut(4) {
+        let a = px[3] as u32;
+        if a == 0 || a == 255 {
+            continue;
+        }
+        for c in &mut px[..3] {
+            *c = ((*c as u32 * 255 + a / 2) / a).min(255) as u8;
+        }
+    }
+}
+
+/// A likely mistake found in a drawn frame by [`check_frame`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameIssue {
+    /// Every pixel has zero alpha, so nothing is visible
+    Transparent,
+    /// The last `count` rows are all zero bytes, as if they were never written
+    BlankRows {
+        /// Index of the first blank row
+        first: u32,
+        /// Number of blank rows
+        count: u32,
+    },
+    /// Translucent pixels look premultiplied, see [`looks_premultiplied`]
+    Premultiplied,
+}
+
+impl std::fmt::Display for FrameIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameIssue::Transparent => write!(
+                f,
+                "every pixel has zero alpha, so the frame is invisible; set the alpha byte \
+                 (every 4th value) to 255 for opaque pixels"
+            ),
+            FrameIssue::BlankRows { first, count } => write!(
+                f,
+                "the last {} rows (from row {}) were never written; check the loop bounds \
+                 of the draw function",
+                count, first
+            ),
+            FrameIssue::Premultiplied => write!(
+                f,
+                "translucent pixels look premultiplied, as drawn by tiny-skia, so saved frames \
+                 will be too dark at soft edges; return `app.present_pixmap(&pixmap)` or set \
+                 `Config::alpha_mode(AlphaMode::Premultiplied)`"
+            ),
+        }
+    }
+}
+
+/// Checks an RGBA frame of the given width for common mistakes that produce invisible or wrong output
+///
+/// A frame is flagged when all of its alpha values are zero, when it ends in
+/// rows of zero bytes below rows that were drawn, or when it
+/// [looks premultiplied](looks_premultiplied). Frames that are entirely zero
+/// are only reported as transparent.
+///
+/// ```
+/// use artimate::buffer::{check_frame, FrameIssue};
+///
+/// let mut frame = vec![0u8; 4 * 4 * 4];
+/// assert_eq!(check_frame(&frame, 4), vec![FrameIssue::Transparent]);
+///
+/// frame[..2 * 4 * 4].fill(255);
+/// assert_eq!(check_frame(&frame, 4), vec![FrameIssue::BlankRows { first: 2, count: 2 }]);
+/// ```
+pub fn check_frame(frame: &[u8], width: u32) -> Vec<FrameIssue> {
+    let mut issues = Vec::new();
+    if frame.is_empty() || width == 0 {
+        return issues;
+    }
+    if frame.chunks_exact(4).all(|px| px[3] == 0) {
+        issues.push(FrameIssue::Transparent);
+    }
+    let rows = frame.chunks_exact(width as usize * 4);
+    let height = rows.len() as u32;
+    let blank = rows
+        .rev()
+        .take_while(|row| row.iter().all(|&v| v == 0))
+        .count() as u32;
+    if blank > 0 && blank < height {
+        issues.push(FrameIssue::BlankRows {
+            first: height - blank,
+            count: blank,
+        });
+    }
+    if looks_premultiplied(frame) {
+        issues.push(FrameIssue::Premultiplied);
+    }
+    issues
+}
+
+/// Returns the range of source pixels covered by output pixel `i` of `out` when
+/// scaling `src` pixels down, always at least one pixel wide
+fn span(i: u32, out: u32, src: u32) -> (u32, u32) {
+    let start = (i as u64 * src as u64 / out as u64) as u32;
+    let end = ((i as u64 + 1) * src as u64 / out as u64) as u32;
+    (start, end.max(start + 1).min(src))
+}
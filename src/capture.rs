@@ -0,0 +1,653 @@
+//! # Capture Sinks
+//!
+//! A capture pipeline that sends frames to any number of sinks at once, such as a
+//! PNG sequence, a video encoder, an animated GIF or PNG and a network stream.
+//! Each [`Capture`] pairs a [`Sink`] with its own frame range, stride and
+//! [`CaptureStage`], and runs on its own thread so a slow encoder never blocks
+//! drawing or the other sinks. Custom encoders plug in by implementing [`Sink`].
+//!
+//! Captures run alongside `Config::frames_to_save`, which keeps its timing
+//! manifest, checkpoints and session packaging.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, CaptureStage, Config, Error};
+//! use artimate::capture::{Capture, FfmpegVideo, GifAnimation, PngSequence, RawStream};
+//! use std::net::TcpStream;
+//!
+//! fn main() -> Result<(), Error> {
+//!     let mut app = App::sketch(Config::with_dims(800, 600), draw);
+//!     // Every frame of the first ten seconds as video
+//!     app.add_capture(Capture::new(FfmpegVideo::new("loop.mp4", 60.0)).frames(0..600));
+//!     // The first two seconds as a looping GIF, written once frame 120 is drawn
+//!     app.add_capture(Capture::new(GifAnimation::new("loop.gif", 60.0)).frames(0..120));
+//!     // Every tenth frame as a PNG, with overlays
+//!     let stills = PngSequence::new("stills").expect("stills directory");
+//!     app.add_capture(Capture::new(stills).stride(10).stage(CaptureStage::Final));
+//!     // Raw frames to a live viewer
+//!     let viewer = TcpStream::connect("127.0.0.1:9000").expect("viewer");
+//!     app.add_capture(Capture::new(RawStream::new(viewer)));
+//!     app.run()
+//! }
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     app.new_frame()
+//! }
+//! ```
+
+use crate::app::CaptureStage;
+use crate::buffer::Image;
+use color_quant::NeuQuant;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+
+/// A frame queued for the capture threads, shared by every sink capturing its stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CapturedFrame {
+    pub(crate) number: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+impl CapturedFrame {
+    /// Borrows the frame as handed to sinks
+    fn frame_ref(&self) -> FrameRef<'_> {
+        FrameRef {
+            number: self.number,
+            width: self.width,
+            height: self.height,
+            data: &self.data,
+        }
+    }
+}
+
+/// A captured frame borrowed by a sink
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRef<'a> {
+    /// Frame number, the app's `frame_count` when the frame was drawn
+    pub number: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Straight alpha RGBA pixel data, `width * height * 4` bytes
+    pub data: &'a [u8],
+}
+
+impl FrameRef<'_> {
+    /// Copies the frame into an owned image
+    pub fn to_image(&self) -> Image {
+        Image {
+            width: self.width,
+            height: self.height,
+            data: self.data.to_vec(),
+        }
+    }
+}
+
+/// A destination for captured frames, implemented to plug in custom encoders
+///
+/// Sinks run on their own thread, so encoding never blocks drawing. `on_frame`
+/// is called for every frame in the capture's range and stride, in order; after
+/// the first error the sink receives no more frames. `finish` is called once,
+/// after the last frame of the range or when the app exits, and the paths it
+/// returns are passed to the app's `on_export_complete` hook.
+///
+/// ```rust
+/// use artimate::capture::{FrameRef, Sink};
+/// use std::io;
+/// use std::path::PathBuf;
+///
+/// /// Writes every frame as a binary PPM file
+/// struct Ppm {
+///     paths: Vec<PathBuf>,
+/// }
+///
+/// impl Sink for Ppm {
+///     fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+///         let mut bytes = format!("P6 {} {} 255\n", frame.width, frame.height).into_bytes();
+///         bytes.extend(frame.data.chunks_exact(4).flat_map(|px| [px[0], px[1], px[2]]));
+///         let path = PathBuf::from(format!("frame_{:05}.ppm", frame.number));
+///         std::fs::write(&path, bytes)?;
+///         self.paths.push(path);
+///         Ok(())
+///     }
+///
+///     fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+///         Ok(std::mem::take(&mut self.paths))
+///     }
+/// }
+/// ```
+pub trait Sink: Send {
+    /// Encodes or sends a single frame
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()>;
+
+    /// Completes the output and returns the paths of the files written, if any
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+}
+
+/// A sink together with the frames it receives
+pub struct Capture {
+    sink: Box<dyn Sink>,
+    frames: Range<u32>,
+    stride: u32,
+    stage: CaptureStage,
+}
+
+impl Capture {
+    /// Creates a capture sending every frame after the passes to `sink`
+    pub fn new(sink: impl Sink + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+            frames: 0..u32::MAX,
+            stride: 1,
+            stage: CaptureStage::Post,
+        }
+    }
+
+    /// Sets the range of frame numbers captured and returns updated capture
+    pub fn frames(self, frames: Range<u32>) -> Self {
+        Self { frames, ..self }
+    }
+
+    /// Sets the capture to every `stride`-th frame of its range and returns updated capture
+    pub fn stride(self, stride: u32) -> Self {
+        Self {
+            stride: stride.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the stage of the frame captured and returns updated capture
+    pub fn stage(self, stage: CaptureStage) -> Self {
+        Self { stage, ..self }
+    }
+
+    /// Returns true if frame `number` is captured
+    ///
+    /// ```
+    /// use artimate::capture::{Capture, RawStream};
+    ///
+    /// let capture = Capture::new(RawStream::new(Vec::new())).frames(10..20).stride(5);
+    /// let due: Vec<u32> = (0..30).filter(|&n| capture.is_due(n)).collect();
+    /// assert_eq!(due, vec![10, 15]);
+    /// ```
+    pub fn is_due(&self, number: u32) -> bool {
+        self.frames.contains(&number) && (number - self.frames.start).is_multiple_of(self.stride)
+    }
+}
+
+/// A capture running on its own thread
+pub(crate) struct CaptureWorker {
+    frames: Range<u32>,
+    stride: u32,
+    pub(crate) stage: CaptureStage,
+    sender: Option<mpsc::Sender<Arc<CapturedFrame>>>,
+    handle: Option<JoinHandle<io::Result<Vec<PathBuf>>>>,
+}
+
+impl CaptureWorker {
+    /// Starts the thread writing the frames of `capture` to its sink
+    pub(crate) fn spawn(capture: Capture) -> Self {
+        let (sender, receiver) = mpsc::channel::<Arc<CapturedFrame>>();
+        let mut sink = capture.sink;
+        let handle = thread::spawn(move || {
+            let mut failed = false;
+            for frame in receiver {
+                if failed {
+                    continue;
+                }
+                if let Err(err) = sink.on_frame(frame.frame_ref()) {
+                    eprintln!("Failed to capture frame {}: {}", frame.number, err);
+                    failed = true;
+                }
+            }
+            sink.finish()
+        });
+        Self {
+            frames: capture.frames,
+            stride: capture.stride,
+            stage: capture.stage,
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+
+    /// Returns true if frame `number` is captured
+    pub(crate) fn is_due(&self, number: u32) -> bool {
+        self.sender.is_some()
+            && self.frames.contains(&number)
+            && (number - self.frames.start).is_multiple_of(self.stride)
+    }
+
+    /// Queues a frame for the sink
+    pub(crate) fn send(&self, frame: Arc<CapturedFrame>) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(frame);
+        }
+    }
+
+    /// Lets the sink finish on its thread once frame `number` is the last of the range
+    pub(crate) fn close_after(&mut self, number: u32) {
+        if number.saturating_add(1) >= self.frames.end {
+            self.sender = None;
+        }
+    }
+
+    /// Waits for every queued frame to be written and finishes the sink
+    pub(crate) fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        self.sender = None;
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .map_err(|_| io::Error::other("capture thread panicked"))?,
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Saves each frame as a numbered PNG file in a directory
+#[derive(Debug, Clone)]
+pub struct PngSequence {
+    dir: PathBuf,
+    prefix: String,
+    paths: Vec<PathBuf>,
+}
+
+impl PngSequence {
+    /// Creates a sequence writing `frame_00000.png`, `frame_00001.png`, ... into `dir`
+    ///
+    /// The directory is created if it does not exist. Files are numbered by frame
+    /// number, so strided captures leave gaps.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            prefix: "frame".to_string(),
+            paths: Vec::new(),
+        })
+    }
+
+    /// Sets the file name prefix and returns updated sequence
+    pub fn prefix(self, prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            ..self
+        }
+    }
+}
+
+impl Sink for PngSequence {
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+        let path = self
+            .dir
+            .join(format!("{}_{:05}.png", self.prefix, frame.number));
+        frame.to_image().save_png(&path)?;
+        self.paths.push(path);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        Ok(std::mem::take(&mut self.paths))
+    }
+}
+
+/// Encodes frames to a video file by piping them to an `ffmpeg` process
+///
+/// `ffmpeg` must be on the `PATH`. The encoder starts with the size of the first
+/// frame; later frames of a different size end the capture with an error.
+#[derive(Debug)]
+pub struct FfmpegVideo {
+    path: PathBuf,
+    fps: f32,
+    args: Vec<String>,
+    encoder: Option<(Child, u32, u32)>,
+}
+
+impl FfmpegVideo {
+    /// Creates a sink encoding video at `fps` frames per second to `path`
+    ///
+    /// Paths ending in `.webm` are encoded with VP9, others with H.264.
+    pub fn new(path: impl Into<PathBuf>, fps: f32) -> Self {
+        let path = path.into();
+        let webm = path.extension().is_some_and(|ext| ext == "webm");
+        let codec: &[&str] = match webm {
+            true => &["-c:v", "libvpx-vp9", "-crf", "30", "-b:v", "0"],
+            false => &["-c:v", "libx264", "-crf", "18"],
+        };
+        // yuv420p needs even dimensions, so odd ones are padded by a pixel
+        let format = [
+            "-pix_fmt",
+            "yuv420p",
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+        ];
+        Self {
+            path,
+            fps,
+            args: codec
+                .iter()
+                .chain(&format)
+                .map(|arg| arg.to_string())
+                .collect(),
+            encoder: None,
+        }
+    }
+
+    /// Replaces the output encoding arguments passed to ffmpeg and returns updated sink
+    ///
+    /// The default for H.264 is
+    /// `-c:v libx264 -crf 18 -pix_fmt yuv420p -vf pad=ceil(iw/2)*2:ceil(ih/2)*2`.
+    pub fn args(self, args: &[&str]) -> Self {
+        Self {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// Starts ffmpeg reading raw RGBA frames of the given size from its standard input
+    fn start(&self, width: u32, height: u32) -> io::Result<Child> {
+        Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error"])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{}x{}", width, height)])
+            .args(["-r", &self.fps.to_string(), "-i", "-"])
+            .args(&self.args)
+            .arg(&self.path)
+            .stdin(Stdio::piped())
+            .spawn()
+    }
+}
+
+impl Sink for FfmpegVideo {
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+        if self.encoder.is_none() {
+            let child = self.start(frame.width, frame.height)?;
+            self.encoder = Some((child, frame.width, frame.height));
+        }
+        let Some((child, width, height)) = self.encoder.as_mut() else {
+            return Ok(());
+        };
+        if (frame.width, frame.height) != (*width, *height) {
+            return Err(io::Error::other(format!(
+                "frame size changed from {}x{} to {}x{}",
+                width, height, frame.width, frame.height
+            )));
+        }
+        match child.stdin.as_mut() {
+            Some(stdin) => stdin.write_all(frame.data),
+            None => Err(io::Error::other("ffmpeg input closed")),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        let Some((mut child, _, _)) = self.encoder.take() else {
+            return Ok(Vec::new());
+        };
+        drop(child.stdin.take());
+        let status = child.wait()?;
+        if status.success() {
+            Ok(vec![self.path.clone()])
+        } else {
+            Err(io::Error::other(format!("ffmpeg exited with {}", status)))
+        }
+    }
+}
+
+/// Encodes frames to an animated GIF file with a palette shared by every frame
+///
+/// GIFs hold at most 256 colors, so frames are kept until the sink finishes,
+/// then quantized together to one palette, which avoids the flicker of a palette
+/// per frame in looping animations. Pixels with alpha below 128 are written as
+/// transparent. All frames are held in memory, so keep captures short and small,
+/// as GIFs usually are. Frames of a different size than the first end the
+/// capture with an error.
+#[derive(Debug, Clone)]
+pub struct GifAnimation {
+    path: PathBuf,
+    fps: f32,
+    repeat: bool,
+    frames: Vec<Vec<u8>>,
+    size: Option<(u16, u16)>,
+}
+
+impl GifAnimation {
+    /// Creates a sink writing a looping GIF played at `fps` frames per second to `path`
+    ///
+    /// GIF frame delays are whole hundredths of a second and viewers slow down
+    /// delays under two, so the frame rate is rounded to at most 50.
+    pub fn new(path: impl Into<PathBuf>, fps: f32) -> Self {
+        Self {
+            path: path.into(),
+            fps,
+            repeat: true,
+            frames: Vec::new(),
+            size: None,
+        }
+    }
+
+    /// Sets whether the animation loops forever or plays once and returns updated sink
+    pub fn repeat(self, repeat: bool) -> Self {
+        Self { repeat, ..self }
+    }
+
+    /// Returns the delay of each frame in hundredths of a second
+    fn delay(&self) -> u16 {
+        (100.0 / self.fps.max(0.01))
+            .round()
+            .clamp(2.0, u16::MAX as f32) as u16
+    }
+
+    /// Writes the kept frames, quantized to a shared palette
+    fn encode(&self, width: u16, height: u16) -> io::Result<()> {
+        let transparent = self
+            .frames
+            .iter()
+            .any(|frame| frame.chunks_exact(4).any(|px| px[3] < 128));
+        // Sample at most about a million pixels to train the palette
+        let total = self.frames.len() * width as usize * height as usize;
+        let step = (total / 1_000_000).max(1);
+        let mut sample: Vec<u8> = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.chunks_exact(4).step_by(step))
+            .filter(|px| px[3] >= 128)
+            .flat_map(|px| [px[0], px[1], px[2], 255])
+            .collect();
+        if sample.is_empty() {
+            sample.extend([0, 0, 0, 255]);
+        }
+        // One palette entry is kept for transparent pixels
+        let colors = if transparent { 255 } else { 256 };
+        // Train on every pixel of small samples, and on a tenth of large ones
+        let sample_factor = (sample.len() / 4 / 100_000).clamp(1, 10) as i32;
+        let quantizer = NeuQuant::new(sample_factor, colors, &sample);
+        let mut palette = quantizer.color_map_rgb();
+        palette.resize(256 * 3, 0);
+
+        let file = std::io::BufWriter::new(std::fs::File::create(&self.path)?);
+        let mut encoder =
+            gif::Encoder::new(file, width, height, &palette).map_err(io::Error::other)?;
+        let repeat = match self.repeat {
+            true => gif::Repeat::Infinite,
+            false => gif::Repeat::Finite(0),
+        };
+        encoder.set_repeat(repeat).map_err(io::Error::other)?;
+        for data in &self.frames {
+            let indices = data
+                .chunks_exact(4)
+                .map(|px| match px[3] < 128 {
+                    true => 255,
+                    false => quantizer.index_of(&[px[0], px[1], px[2], 255]) as u8,
+                })
+                .collect::<Vec<u8>>();
+            let frame = gif::Frame {
+                width,
+                height,
+                delay: self.delay(),
+                dispose: gif::DisposalMethod::Background,
+                transparent: transparent.then_some(255),
+                buffer: indices.into(),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+impl Sink for GifAnimation {
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+        let (Ok(width), Ok(height)) = (u16::try_from(frame.width), u16::try_from(frame.height))
+        else {
+            return Err(io::Error::other(format!(
+                "{}x{} frames are too large for a GIF",
+                frame.width, frame.height
+            )));
+        };
+        let size = *self.size.get_or_insert((width, height));
+        if size != (width, height) {
+            return Err(io::Error::other(format!(
+                "frame size changed from {}x{} to {}x{}",
+                size.0, size.1, width, height
+            )));
+        }
+        self.frames.push(frame.data.to_vec());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        let Some((width, height)) = self.size else {
+            return Ok(Vec::new());
+        };
+        self.encode(width, height)?;
+        self.frames.clear();
+        Ok(vec![self.path.clone()])
+    }
+}
+
+/// Encodes frames to a lossless animated PNG file
+///
+/// APNG keeps every color and the alpha channel of each frame, unlike GIF, and
+/// plays in all major browsers. Frames are kept until the sink finishes, since
+/// the file starts with the number of frames, so keep captures short. Frames of a
+/// different size than the first end the capture with an error.
+///
+/// ```rust,no_run
+/// use artimate::app::{App, Config};
+/// use artimate::capture::{ApngAnimation, Capture};
+///
+/// # fn draw(app: &App, _model: &()) -> Vec<u8> { app.new_frame() }
+/// let mut app = App::sketch(Config::with_dims(400, 400), draw);
+/// // One loop of 120 frames, written as soon as it has been drawn
+/// app.add_capture(Capture::new(ApngAnimation::new("loop.png", 60.0)).frames(0..120));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ApngAnimation {
+    path: PathBuf,
+    fps: f32,
+    repeat: bool,
+    frames: Vec<Vec<u8>>,
+    size: Option<(u32, u32)>,
+}
+
+impl ApngAnimation {
+    /// Creates a sink writing a looping animated PNG played at `fps` frames per second to `path`
+    pub fn new(path: impl Into<PathBuf>, fps: f32) -> Self {
+        Self {
+            path: path.into(),
+            fps,
+            repeat: true,
+            frames: Vec::new(),
+            size: None,
+        }
+    }
+
+    /// Sets whether the animation loops forever or plays once and returns updated sink
+    pub fn repeat(self, repeat: bool) -> Self {
+        Self { repeat, ..self }
+    }
+
+    /// Writes the kept frames
+    fn encode(&self, width: u32, height: u32) -> io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(&self.path)?);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Zero plays loops forever
+        let plays = if self.repeat { 0 } else { 1 };
+        encoder
+            .set_animated(self.frames.len() as u32, plays)
+            .map_err(io::Error::other)?;
+        // A delay of 100 / (fps * 100) seconds keeps rates such as 29.97 exact
+        let hundredths = (self.fps * 100.0).round().clamp(1.0, u16::MAX as f32) as u16;
+        encoder
+            .set_frame_delay(100, hundredths)
+            .map_err(io::Error::other)?;
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        for data in &self.frames {
+            writer.write_image_data(data).map_err(io::Error::other)?;
+        }
+        writer.finish().map_err(io::Error::other)
+    }
+}
+
+impl Sink for ApngAnimation {
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+        let size = *self.size.get_or_insert((frame.width, frame.height));
+        if size != (frame.width, frame.height) {
+            return Err(io::Error::other(format!(
+                "frame size changed from {}x{} to {}x{}",
+                size.0, size.1, frame.width, frame.height
+            )));
+        }
+        self.frames.push(frame.data.to_vec());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        let Some((width, height)) = self.size else {
+            return Ok(Vec::new());
+        };
+        self.encode(width, height)?;
+        self.frames.clear();
+        Ok(vec![self.path.clone()])
+    }
+}
+
+/// Streams raw frames to any writer, such as a `TcpStream` to a live viewer
+///
+/// Each frame is written as a 12 byte header of little-endian `u32` values, the
+/// frame number, width and height, followed by the RGBA pixel data.
+#[derive(Debug)]
+pub struct RawStream<W> {
+    writer: W,
+}
+
+impl<W: Write + Send> RawStream<W> {
+    /// Creates a sink writing frames to `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: Write + Send> Sink for RawStream<W> {
+    fn on_frame(&mut self, frame: FrameRef) -> io::Result<()> {
+        for value in [frame.number, frame.width, frame.height] {
+            self.writer.write_all(&value.to_le_bytes())?;
+        }
+        self.writer.write_all(frame.data)
+    }
+
+    fn finish(&mut self) -> io::Result<Vec<PathBuf>> {
+        self.writer.flush()?;
+        Ok(Vec::new())
+    }
+}
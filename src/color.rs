@@ -0,0 +1,280 @@
+//! # Color Management
+//!
+//! Color profiles embedded into exported PNGs. Draw functions produce sRGB pixels;
+//! exporting with [`ColorProfile::DisplayP3`] converts them into the wider Display P3
+//! space and tags the file accordingly, so the colors seen on screen survive into the
+//! saved artwork.
+//!
+//! Colors themselves are plain `[u8; 4]` RGBA values. [`from_hex`] and [`named`]
+//! parse them from CSS notation, every CSS named color is available as a
+//! constant such as [`CORNFLOWERBLUE`], and [`with_alpha`] and [`with_opacity`]
+//! adjust transparency. [`Oklab`] and [`Okhsl`] are perceptual color spaces;
+//! [`lerp`] and [`gradient`] interpolate in Oklab so blends keep their hue and
+//! brightness.
+//!
+//! ```
+//! use artimate::color::{self, CORNFLOWERBLUE};
+//!
+//! assert_eq!(color::from_hex("#1e90ff"), Some(color::DODGERBLUE));
+//! assert_eq!(color::named("CornflowerBlue"), Some(CORNFLOWERBLUE));
+//! assert_eq!(color::with_opacity(CORNFLOWERBLUE, 0.5), [100, 149, 237, 128]);
+//! ```
+
+mod css;
+mod oklab;
+
+pub use css::*;
+pub use oklab::{gradient, lerp, Okhsl, Oklab};
+
+/// The color profile embedded into saved frames
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ColorProfile {
+    /// Tag exports as sRGB, pixels are written unchanged
+    #[default]
+    Srgb,
+    /// Convert exports from sRGB to Display P3 and tag them as Display P3
+    DisplayP3,
+    /// Embed the given ICC profile, pixels are written unchanged
+    Icc(Vec<u8>),
+}
+
+/// sRGB primaries adapted to the D50 profile connection space
+const SRGB_D50: [[f64; 3]; 3] = [
+    [0.4360747, 0.2225045, 0.0139322],
+    [0.3850649, 0.7168786, 0.0971045],
+    [0.1430804, 0.0606169, 0.7141733],
+];
+
+/// Display P3 primaries adapted to the D50 profile connection space
+const P3_D50: [[f64; 3]; 3] = [
+    [0.5151187, 0.2411892, -0.0010491],
+    [0.2919778, 0.6922441, 0.0418791],
+    [0.1571035, 0.0665668, 0.7840713],
+];
+
+/// Linear sRGB to linear Display P3, both relative to D65
+const SRGB_TO_P3: [[f32; 3]; 3] = [
+    [0.822_462, 0.177_538, 0.0],
+    [0.033_194, 0.966_806, 0.0],
+    [0.017_083, 0.072_397, 0.910_520],
+];
+
+const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+impl ColorProfile {
+    /// Returns the ICC profile bytes to embed in an exported image
+    ///
+    /// ```
+    /// use artimate::color::ColorProfile;
+    ///
+    /// let icc = ColorProfile::DisplayP3.icc();
+    /// assert_eq!(&icc[36..40], b"acsp");
+    /// assert_eq!(u32::from_be_bytes(icc[0..4].try_into().unwrap()) as usize, icc.len());
+    /// ```
+    pub fn icc(&self) -> Vec<u8> {
+        match self {
+            ColorProfile::Srgb => matrix_profile("sRGB", &SRGB_D50),
+            ColorProfile::DisplayP3 => matrix_profile("Display P3", &P3_D50),
+            ColorProfile::Icc(bytes) => bytes.clone(),
+        }
+    }
+
+    /// Converts an sRGB RGBA buffer in place into this profile's color space
+    pub fn convert(&self, data: &mut [u8]) {
+        if *self != ColorProfile::DisplayP3 {
+            return;
+        }
+        let to_linear: Vec<f32> = (0..256).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+        for px in data.chunks_exact_mut(4) {
+            let rgb = [
+                to_linear[px[0] as usize],
+                to_linear[px[1] as usize],
+                to_linear[px[2] as usize],
+            ];
+            for (c, row) in SRGB_TO_P3.iter().enumerate() {
+                let linear = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+                px[c] = (linear_to_srgb(linear) * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+/// Decodes an sRGB transfer encoded value in [0, 1] to linear light
+pub(crate) fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear light value in [0, 1] with the sRGB transfer function
+pub(crate) fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Builds an ICC v2 RGB display profile from D50 primaries and the sRGB tone curve
+fn matrix_profile(description: &str, primaries: &[[f64; 3]; 3]) -> Vec<u8> {
+    let mut curve = b"curv\0\0\0\0".to_vec();
+    curve.extend_from_slice(&1024u32.to_be_bytes());
+    for i in 0..1024 {
+        let v = srgb_to_linear(i as f32 / 1023.0);
+        curve.extend_from_slice(&((v * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", text_description(description)),
+        (b"cprt", text("No copyright, use freely")),
+        (b"wtpt", xyz(&D50)),
+        (b"rXYZ", xyz(&primaries[0])),
+        (b"gXYZ", xyz(&primaries[1])),
+        (b"bXYZ", xyz(&primaries[2])),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve),
+    ];
+
+    let mut table = Vec::new();
+    let mut body = Vec::new();
+    let mut offset = 128 + 4 + 12 * tags.len();
+    for (signature, data) in &tags {
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        body.extend_from_slice(data);
+        while body.len() % 4 != 0 {
+            body.push(0);
+        }
+        offset = 128 + 4 + 12 * tags.len() + body.len();
+    }
+
+    let size = 128 + 4 + table.len() + body.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // preferred CMM
+    profile.extend_from_slice(&0x0210_0000u32.to_be_bytes());
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    profile.extend_from_slice(&[0; 12]); // creation date
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // platform, flags, device and attributes
+    profile.extend_from_slice(&0u32.to_be_bytes()); // perceptual intent
+    for v in D50 {
+        profile.extend_from_slice(&s15_fixed16(v));
+    }
+    profile.resize(128, 0);
+    profile.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&body);
+    profile
+}
+
+fn s15_fixed16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz(v: &[f64; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for c in v {
+        tag.extend_from_slice(&s15_fixed16(*c));
+    }
+    tag
+}
+
+fn text(s: &str) -> Vec<u8> {
+    let mut tag = b"text\0\0\0\0".to_vec();
+    tag.extend_from_slice(s.as_bytes());
+    tag.push(0);
+    tag
+}
+
+fn text_description(s: &str) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(s.len() as u32 + 1).to_be_bytes());
+    tag.extend_from_slice(s.as_bytes());
+    tag.push(0);
+    // Empty Unicode and ScriptCode descriptions
+    tag.extend_from_slice(&[0; 8]);
+    tag.extend_from_slice(&[0; 3]);
+    tag.extend_from_slice(&[0; 67]);
+    tag
+}
+
+/// Parses a CSS hex color, `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, into RGBA
+///
+/// The leading `#` is optional. Colors without an alpha component are opaque.
+///
+/// ```
+/// use artimate::color::from_hex;
+///
+/// assert_eq!(from_hex("#1e90ff"), Some([30, 144, 255, 255]));
+/// assert_eq!(from_hex("f80"), Some([255, 136, 0, 255]));
+/// assert_eq!(from_hex("#00000080"), Some([0, 0, 0, 128]));
+/// assert_eq!(from_hex("#12345"), None);
+/// ```
+pub fn from_hex(hex: &str) -> Option<[u8; 4]> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.is_ascii() {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok();
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 | 4 => {
+            let mut rgba = [255; 4];
+            for (i, c) in rgba.iter_mut().take(hex.len()).enumerate() {
+                *c = digit(i)? * 17;
+            }
+            Some(rgba)
+        }
+        6 | 8 => {
+            let mut rgba = [255; 4];
+            for (i, c) in rgba.iter_mut().take(hex.len() / 2).enumerate() {
+                *c = byte(2 * i)?;
+            }
+            Some(rgba)
+        }
+        _ => None,
+    }
+}
+
+/// Formats a color as `#rrggbb`, or `#rrggbbaa` if it is not opaque
+///
+/// ```
+/// use artimate::color::{to_hex, DODGERBLUE};
+///
+/// assert_eq!(to_hex(DODGERBLUE), "#1e90ff");
+/// assert_eq!(to_hex([0, 0, 0, 128]), "#00000080");
+/// ```
+pub fn to_hex(color: [u8; 4]) -> String {
+    let [r, g, b, a] = color;
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// Returns the CSS named color `name`, ignoring case
+pub fn named(name: &str) -> Option<[u8; 4]> {
+    let name = name.to_ascii_lowercase();
+    NAMED_COLORS
+        .binary_search_by(|(candidate, _)| (*candidate).cmp(name.as_str()))
+        .ok()
+        .map(|i| NAMED_COLORS[i].1)
+}
+
+/// Returns `color` with its alpha replaced by `alpha`
+pub fn with_alpha(color: [u8; 4], alpha: u8) -> [u8; 4] {
+    let [r, g, b, _] = color;
+    [r, g, b, alpha]
+}
+
+/// Returns `color` with its alpha scaled by `opacity`, clamped to [0, 1]
+pub fn with_opacity(color: [u8; 4], opacity: f32) -> [u8; 4] {
+    let alpha = (color[3] as f32 * opacity.clamp(0.0, 1.0)).round() as u8;
+    with_alpha(color, alpha)
+}
@@ -0,0 +1,601 @@
+//! CSS named colors as opaque RGBA values
+
+/// CSS color `aliceblue`, `#f0f8ff`
+pub const ALICEBLUE: [u8; 4] = [240, 248, 255, 255];
+
+/// CSS color `antiquewhite`, `#faebd7`
+pub const ANTIQUEWHITE: [u8; 4] = [250, 235, 215, 255];
+
+/// CSS color `aqua`, `#00ffff`
+pub const AQUA: [u8; 4] = [0, 255, 255, 255];
+
+/// CSS color `aquamarine`, `#7fffd4`
+pub const AQUAMARINE: [u8; 4] = [127, 255, 212, 255];
+
+/// CSS color `azure`, `#f0ffff`
+pub const AZURE: [u8; 4] = [240, 255, 255, 255];
+
+/// CSS color `beige`, `#f5f5dc`
+pub const BEIGE: [u8; 4] = [245, 245, 220, 255];
+
+/// CSS color `bisque`, `#ffe4c4`
+pub const BISQUE: [u8; 4] = [255, 228, 196, 255];
+
+/// CSS color `black`, `#000000`
+pub const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+/// CSS color `blanchedalmond`, `#ffebcd`
+pub const BLANCHEDALMOND: [u8; 4] = [255, 235, 205, 255];
+
+/// CSS color `blue`, `#0000ff`
+pub const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+/// CSS color `blueviolet`, `#8a2be2`
+pub const BLUEVIOLET: [u8; 4] = [138, 43, 226, 255];
+
+/// CSS color `brown`, `#a52a2a`
+pub const BROWN: [u8; 4] = [165, 42, 42, 255];
+
+/// CSS color `burlywood`, `#deb887`
+pub const BURLYWOOD: [u8; 4] = [222, 184, 135, 255];
+
+/// CSS color `cadetblue`, `#5f9ea0`
+pub const CADETBLUE: [u8; 4] = [95, 158, 160, 255];
+
+/// CSS color `chartreuse`, `#7fff00`
+pub const CHARTREUSE: [u8; 4] = [127, 255, 0, 255];
+
+/// CSS color `chocolate`, `#d2691e`
+pub const CHOCOLATE: [u8; 4] = [210, 105, 30, 255];
+
+/// CSS color `coral`, `#ff7f50`
+pub const CORAL: [u8; 4] = [255, 127, 80, 255];
+
+/// CSS color `cornflowerblue`, `#6495ed`
+pub const CORNFLOWERBLUE: [u8; 4] = [100, 149, 237, 255];
+
+/// CSS color `cornsilk`, `#fff8dc`
+pub const CORNSILK: [u8; 4] = [255, 248, 220, 255];
+
+/// CSS color `crimson`, `#dc143c`
+pub const CRIMSON: [u8; 4] = [220, 20, 60, 255];
+
+/// CSS color `cyan`, `#00ffff`
+pub const CYAN: [u8; 4] = [0, 255, 255, 255];
+
+/// CSS color `darkblue`, `#00008b`
+pub const DARKBLUE: [u8; 4] = [0, 0, 139, 255];
+
+/// CSS color `darkcyan`, `#008b8b`
+pub const DARKCYAN: [u8; 4] = [0, 139, 139, 255];
+
+/// CSS color `darkgoldenrod`, `#b8860b`
+pub const DARKGOLDENROD: [u8; 4] = [184, 134, 11, 255];
+
+/// CSS color `darkgray`, `#a9a9a9`
+pub const DARKGRAY: [u8; 4] = [169, 169, 169, 255];
+
+/// CSS color `darkgreen`, `#006400`
+pub const DARKGREEN: [u8; 4] = [0, 100, 0, 255];
+
+/// CSS color `darkgrey`, `#a9a9a9`
+pub const DARKGREY: [u8; 4] = [169, 169, 169, 255];
+
+/// CSS color `darkkhaki`, `#bdb76b`
+pub const DARKKHAKI: [u8; 4] = [189, 183, 107, 255];
+
+/// CSS color `darkmagenta`, `#8b008b`
+pub const DARKMAGENTA: [u8; 4] = [139, 0, 139, 255];
+
+/// CSS color `darkolivegreen`, `#556b2f`
+pub const DARKOLIVEGREEN: [u8; 4] = [85, 107, 47, 255];
+
+/// CSS color `darkorange`, `#ff8c00`
+pub const DARKORANGE: [u8; 4] = [255, 140, 0, 255];
+
+/// CSS color `darkorchid`, `#9932cc`
+pub const DARKORCHID: [u8; 4] = [153, 50, 204, 255];
+
+/// CSS color `darkred`, `#8b0000`
+pub const DARKRED: [u8; 4] = [139, 0, 0, 255];
+
+/// CSS color `darksalmon`, `#e9967a`
+pub const DARKSALMON: [u8; 4] = [233, 150, 122, 255];
+
+/// CSS color `darkseagreen`, `#8fbc8f`
+pub const DARKSEAGREEN: [u8; 4] = [143, 188, 143, 255];
+
+/// CSS color `darkslateblue`, `#483d8b`
+pub const DARKSLATEBLUE: [u8; 4] = [72, 61, 139, 255];
+
+/// CSS color `darkslategray`, `#2f4f4f`
+pub const DARKSLATEGRAY: [u8; 4] = [47, 79, 79, 255];
+
+/// CSS color `darkslategrey`, `#2f4f4f`
+pub const DARKSLATEGREY: [u8; 4] = [47, 79, 79, 255];
+
+/// CSS color `darkturquoise`, `#00ced1`
+pub const DARKTURQUOISE: [u8; 4] = [0, 206, 209, 255];
+
+/// CSS color `darkviolet`, `#9400d3`
+pub const DARKVIOLET: [u8; 4] = [148, 0, 211, 255];
+
+/// CSS color `deeppink`, `#ff1493`
+pub const DEEPPINK: [u8; 4] = [255, 20, 147, 255];
+
+/// CSS color `deepskyblue`, `#00bfff`
+pub const DEEPSKYBLUE: [u8; 4] = [0, 191, 255, 255];
+
+/// CSS color `dimgray`, `#696969`
+pub const DIMGRAY: [u8; 4] = [105, 105, 105, 255];
+
+/// CSS color `dimgrey`, `#696969`
+pub const DIMGREY: [u8; 4] = [105, 105, 105, 255];
+
+/// CSS color `dodgerblue`, `#1e90ff`
+pub const DODGERBLUE: [u8; 4] = [30, 144, 255, 255];
+
+/// CSS color `firebrick`, `#b22222`
+pub const FIREBRICK: [u8; 4] = [178, 34, 34, 255];
+
+/// CSS color `floralwhite`, `#fffaf0`
+pub const FLORALWHITE: [u8; 4] = [255, 250, 240, 255];
+
+/// CSS color `forestgreen`, `#228b22`
+pub const FORESTGREEN: [u8; 4] = [34, 139, 34, 255];
+
+/// CSS color `fuchsia`, `#ff00ff`
+pub const FUCHSIA: [u8; 4] = [255, 0, 255, 255];
+
+/// CSS color `gainsboro`, `#dcdcdc`
+pub const GAINSBORO: [u8; 4] = [220, 220, 220, 255];
+
+/// CSS color `ghostwhite`, `#f8f8ff`
+pub const GHOSTWHITE: [u8; 4] = [248, 248, 255, 255];
+
+/// CSS color `gold`, `#ffd700`
+pub const GOLD: [u8; 4] = [255, 215, 0, 255];
+
+/// CSS color `goldenrod`, `#daa520`
+pub const GOLDENROD: [u8; 4] = [218, 165, 32, 255];
+
+/// CSS color `gray`, `#808080`
+pub const GRAY: [u8; 4] = [128, 128, 128, 255];
+
+/// CSS color `green`, `#008000`
+pub const GREEN: [u8; 4] = [0, 128, 0, 255];
+
+/// CSS color `greenyellow`, `#adff2f`
+pub const GREENYELLOW: [u8; 4] = [173, 255, 47, 255];
+
+/// CSS color `grey`, `#808080`
+pub const GREY: [u8; 4] = [128, 128, 128, 255];
+
+/// CSS color `honeydew`, `#f0fff0`
+pub const HONEYDEW: [u8; 4] = [240, 255, 240, 255];
+
+/// CSS color `hotpink`, `#ff69b4`
+pub const HOTPINK: [u8; 4] = [255, 105, 180, 255];
+
+/// CSS color `indianred`, `#cd5c5c`
+pub const INDIANRED: [u8; 4] = [205, 92, 92, 255];
+
+/// CSS color `indigo`, `#4b0082`
+pub const INDIGO: [u8; 4] = [75, 0, 130, 255];
+
+/// CSS color `ivory`, `#fffff0`
+pub const IVORY: [u8; 4] = [255, 255, 240, 255];
+
+/// CSS color `khaki`, `#f0e68c`
+pub const KHAKI: [u8; 4] = [240, 230, 140, 255];
+
+/// CSS color `lavender`, `#e6e6fa`
+pub const LAVENDER: [u8; 4] = [230, 230, 250, 255];
+
+/// CSS color `lavenderblush`, `#fff0f5`
+pub const LAVENDERBLUSH: [u8; 4] = [255, 240, 245, 255];
+
+/// CSS color `lawngreen`, `#7cfc00`
+pub const LAWNGREEN: [u8; 4] = [124, 252, 0, 255];
+
+/// CSS color `lemonchiffon`, `#fffacd`
+pub const LEMONCHIFFON: [u8; 4] = [255, 250, 205, 255];
+
+/// CSS color `lightblue`, `#add8e6`
+pub const LIGHTBLUE: [u8; 4] = [173, 216, 230, 255];
+
+/// CSS color `lightcoral`, `#f08080`
+pub const LIGHTCORAL: [u8; 4] = [240, 128, 128, 255];
+
+/// CSS color `lightcyan`, `#e0ffff`
+pub const LIGHTCYAN: [u8; 4] = [224, 255, 255, 255];
+
+/// CSS color `lightgoldenrodyellow`, `#fafad2`
+pub const LIGHTGOLDENRODYELLOW: [u8; 4] = [250, 250, 210, 255];
+
+/// CSS color `lightgray`, `#d3d3d3`
+pub const LIGHTGRAY: [u8; 4] = [211, 211, 211, 255];
+
+/// CSS color `lightgreen`, `#90ee90`
+pub const LIGHTGREEN: [u8; 4] = [144, 238, 144, 255];
+
+/// CSS color `lightgrey`, `#d3d3d3`
+pub const LIGHTGREY: [u8; 4] = [211, 211, 211, 255];
+
+/// CSS color `lightpink`, `#ffb6c1`
+pub const LIGHTPINK: [u8; 4] = [255, 182, 193, 255];
+
+/// CSS color `lightsalmon`, `#ffa07a`
+pub const LIGHTSALMON: [u8; 4] = [255, 160, 122, 255];
+
+/// CSS color `lightseagreen`, `#20b2aa`
+pub const LIGHTSEAGREEN: [u8; 4] = [32, 178, 170, 255];
+
+/// CSS color `lightskyblue`, `#87cefa`
+pub const LIGHTSKYBLUE: [u8; 4] = [135, 206, 250, 255];
+
+/// CSS color `lightslategray`, `#778899`
+pub const LIGHTSLATEGRAY: [u8; 4] = [119, 136, 153, 255];
+
+/// CSS color `lightslategrey`, `#778899`
+pub const LIGHTSLATEGREY: [u8; 4] = [119, 136, 153, 255];
+
+/// CSS color `lightsteelblue`, `#b0c4de`
+pub const LIGHTSTEELBLUE: [u8; 4] = [176, 196, 222, 255];
+
+/// CSS color `lightyellow`, `#ffffe0`
+pub const LIGHTYELLOW: [u8; 4] = [255, 255, 224, 255];
+
+/// CSS color `lime`, `#00ff00`
+pub const LIME: [u8; 4] = [0, 255, 0, 255];
+
+/// CSS color `limegreen`, `#32cd32`
+pub const LIMEGREEN: [u8; 4] = [50, 205, 50, 255];
+
+/// CSS color `linen`, `#faf0e6`
+pub const LINEN: [u8; 4] = [250, 240, 230, 255];
+
+/// CSS color `magenta`, `#ff00ff`
+pub const MAGENTA: [u8; 4] = [255, 0, 255, 255];
+
+/// CSS color `maroon`, `#800000`
+pub const MAROON: [u8; 4] = [128, 0, 0, 255];
+
+/// CSS color `mediumaquamarine`, `#66cdaa`
+pub const MEDIUMAQUAMARINE: [u8; 4] = [102, 205, 170, 255];
+
+/// CSS color `mediumblue`, `#0000cd`
+pub const MEDIUMBLUE: [u8; 4] = [0, 0, 205, 255];
+
+/// CSS color `mediumorchid`, `#ba55d3`
+pub const MEDIUMORCHID: [u8; 4] = [186, 85, 211, 255];
+
+/// CSS color `mediumpurple`, `#9370db`
+pub const MEDIUMPURPLE: [u8; 4] = [147, 112, 219, 255];
+
+/// CSS color `mediumseagreen`, `#3cb371`
+pub const MEDIUMSEAGREEN: [u8; 4] = [60, 179, 113, 255];
+
+/// CSS color `mediumslateblue`, `#7b68ee`
+pub const MEDIUMSLATEBLUE: [u8; 4] = [123, 104, 238, 255];
+
+/// CSS color `mediumspringgreen`, `#00fa9a`
+pub const MEDIUMSPRINGGREEN: [u8; 4] = [0, 250, 154, 255];
+
+/// CSS color `mediumturquoise`, `#48d1cc`
+pub const MEDIUMTURQUOISE: [u8; 4] = [72, 209, 204, 255];
+
+/// CSS color `mediumvioletred`, `#c71585`
+pub const MEDIUMVIOLETRED: [u8; 4] = [199, 21, 133, 255];
+
+/// CSS color `midnightblue`, `#191970`
+pub const MIDNIGHTBLUE: [u8; 4] = [25, 25, 112, 255];
+
+/// CSS color `mintcream`, `#f5fffa`
+pub const MINTCREAM: [u8; 4] = [245, 255, 250, 255];
+
+/// CSS color `mistyrose`, `#ffe4e1`
+pub const MISTYROSE: [u8; 4] = [255, 228, 225, 255];
+
+/// CSS color `moccasin`, `#ffe4b5`
+pub const MOCCASIN: [u8; 4] = [255, 228, 181, 255];
+
+/// CSS color `navajowhite`, `#ffdead`
+pub const NAVAJOWHITE: [u8; 4] = [255, 222, 173, 255];
+
+/// CSS color `navy`, `#000080`
+pub const NAVY: [u8; 4] = [0, 0, 128, 255];
+
+/// CSS color `oldlace`, `#fdf5e6`
+pub const OLDLACE: [u8; 4] = [253, 245, 230, 255];
+
+/// CSS color `olive`, `#808000`
+pub const OLIVE: [u8; 4] = [128, 128, 0, 255];
+
+/// CSS color `olivedrab`, `#6b8e23`
+pub const OLIVEDRAB: [u8; 4] = [107, 142, 35, 255];
+
+/// CSS color `orange`, `#ffa500`
+pub const ORANGE: [u8; 4] = [255, 165, 0, 255];
+
+/// CSS color `orangered`, `#ff4500`
+pub const ORANGERED: [u8; 4] = [255, 69, 0, 255];
+
+/// CSS color `orchid`, `#da70d6`
+pub const ORCHID: [u8; 4] = [218, 112, 214, 255];
+
+/// CSS color `palegoldenrod`, `#eee8aa`
+pub const PALEGOLDENROD: [u8; 4] = [238, 232, 170, 255];
+
+/// CSS color `palegreen`, `#98fb98`
+pub const PALEGREEN: [u8; 4] = [152, 251, 152, 255];
+
+/// CSS color `paleturquoise`, `#afeeee`
+pub const PALETURQUOISE: [u8; 4] = [175, 238, 238, 255];
+
+/// CSS color `palevioletred`, `#db7093`
+pub const PALEVIOLETRED: [u8; 4] = [219, 112, 147, 255];
+
+/// CSS color `papayawhip`, `#ffefd5`
+pub const PAPAYAWHIP: [u8; 4] = [255, 239, 213, 255];
+
+/// CSS color `peachpuff`, `#ffdab9`
+pub const PEACHPUFF: [u8; 4] = [255, 218, 185, 255];
+
+/// CSS color `peru`, `#cd853f`
+pub const PERU: [u8; 4] = [205, 133, 63, 255];
+
+/// CSS color `pink`, `#ffc0cb`
+pub const PINK: [u8; 4] = [255, 192, 203, 255];
+
+/// CSS color `plum`, `#dda0dd`
+pub const PLUM: [u8; 4] = [221, 160, 221, 255];
+
+/// CSS color `powderblue`, `#b0e0e6`
+pub const POWDERBLUE: [u8; 4] = [176, 224, 230, 255];
+
+/// CSS color `purple`, `#800080`
+pub const PURPLE: [u8; 4] = [128, 0, 128, 255];
+
+/// CSS color `rebeccapurple`, `#663399`
+pub const REBECCAPURPLE: [u8; 4] = [102, 51, 153, 255];
+
+/// CSS color `red`, `#ff0000`
+pub const RED: [u8; 4] = [255, 0, 0, 255];
+
+/// CSS color `rosybrown`, `#bc8f8f`
+pub const ROSYBROWN: [u8; 4] = [188, 143, 143, 255];
+
+/// CSS color `royalblue`, `#4169e1`
+pub const ROYALBLUE: [u8; 4] = [65, 105, 225, 255];
+
+/// CSS color `saddlebrown`, `#8b4513`
+pub const SADDLEBROWN: [u8; 4] = [139, 69, 19, 255];
+
+/// CSS color `salmon`, `#fa8072`
+pub const SALMON: [u8; 4] = [250, 128, 114, 255];
+
+/// CSS color `sandybrown`, `#f4a460`
+pub const SANDYBROWN: [u8; 4] = [244, 164, 96, 255];
+
+/// CSS color `seagreen`, `#2e8b57`
+pub const SEAGREEN: [u8; 4] = [46, 139, 87, 255];
+
+/// CSS color `seashell`, `#fff5ee`
+pub const SEASHELL: [u8; 4] = [255, 245, 238, 255];
+
+/// CSS color `sienna`, `#a0522d`
+pub const SIENNA: [u8; 4] = [160, 82, 45, 255];
+
+/// CSS color `silver`, `#c0c0c0`
+pub const SILVER: [u8; 4] = [192, 192, 192, 255];
+
+/// CSS color `skyblue`, `#87ceeb`
+pub const SKYBLUE: [u8; 4] = [135, 206, 235, 255];
+
+/// CSS color `slateblue`, `#6a5acd`
+pub const SLATEBLUE: [u8; 4] = [106, 90, 205, 255];
+
+/// CSS color `slategray`, `#708090`
+pub const SLATEGRAY: [u8; 4] = [112, 128, 144, 255];
+
+/// CSS color `slategrey`, `#708090`
+pub const SLATEGREY: [u8; 4] = [112, 128, 144, 255];
+
+/// CSS color `snow`, `#fffafa`
+pub const SNOW: [u8; 4] = [255, 250, 250, 255];
+
+/// CSS color `springgreen`, `#00ff7f`
+pub const SPRINGGREEN: [u8; 4] = [0, 255, 127, 255];
+
+/// CSS color `steelblue`, `#4682b4`
+pub const STEELBLUE: [u8; 4] = [70, 130, 180, 255];
+
+/// CSS color `tan`, `#d2b48c`
+pub const TAN: [u8; 4] = [210, 180, 140, 255];
+
+/// CSS color `teal`, `#008080`
+pub const TEAL: [u8; 4] = [0, 128, 128, 255];
+
+/// CSS color `thistle`, `#d8bfd8`
+pub const THISTLE: [u8; 4] = [216, 191, 216, 255];
+
+/// CSS color `tomato`, `#ff6347`
+pub const TOMATO: [u8; 4] = [255, 99, 71, 255];
+
+/// CSS color `turquoise`, `#40e0d0`
+pub const TURQUOISE: [u8; 4] = [64, 224, 208, 255];
+
+/// CSS color `violet`, `#ee82ee`
+pub const VIOLET: [u8; 4] = [238, 130, 238, 255];
+
+/// CSS color `wheat`, `#f5deb3`
+pub const WHEAT: [u8; 4] = [245, 222, 179, 255];
+
+/// CSS color `white`, `#ffffff`
+pub const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// CSS color `whitesmoke`, `#f5f5f5`
+pub const WHITESMOKE: [u8; 4] = [245, 245, 245, 255];
+
+/// CSS color `yellow`, `#ffff00`
+pub const YELLOW: [u8; 4] = [255, 255, 0, 255];
+
+/// CSS color `yellowgreen`, `#9acd32`
+pub const YELLOWGREEN: [u8; 4] = [154, 205, 50, 255];
+
+/// Transparent black, CSS `transparent`
+pub const TRANSPARENT: [u8; 4] = [0, 0, 0, 0];
+
+/// CSS color names and values, sorted by name
+pub(super) const NAMED_COLORS: [(&str, [u8; 4]); 149] = [
+    ("aliceblue", ALICEBLUE),
+    ("antiquewhite", ANTIQUEWHITE),
+    ("aqua", AQUA),
+    ("aquamarine", AQUAMARINE),
+    ("azure", AZURE),
+    ("beige", BEIGE),
+    ("bisque", BISQUE),
+    ("black", BLACK),
+    ("blanchedalmond", BLANCHEDALMOND),
+    ("blue", BLUE),
+    ("blueviolet", BLUEVIOLET),
+    ("brown", BROWN),
+    ("burlywood", BURLYWOOD),
+    ("cadetblue", CADETBLUE),
+    ("chartreuse", CHARTREUSE),
+    ("chocolate", CHOCOLATE),
+    ("coral", CORAL),
+    ("cornflowerblue", CORNFLOWERBLUE),
+    ("cornsilk", CORNSILK),
+    ("crimson", CRIMSON),
+    ("cyan", CYAN),
+    ("darkblue", DARKBLUE),
+    ("darkcyan", DARKCYAN),
+    ("darkgoldenrod", DARKGOLDENROD),
+    ("darkgray", DARKGRAY),
+    ("darkgreen", DARKGREEN),
+    ("darkgrey", DARKGREY),
+    ("darkkhaki", DARKKHAKI),
+    ("darkmagenta", DARKMAGENTA),
+    ("darkolivegreen", DARKOLIVEGREEN),
+    ("darkorange", DARKORANGE),
+    ("darkorchid", DARKORCHID),
+    ("darkred", DARKRED),
+    ("darksalmon", DARKSALMON),
+    ("darkseagreen", DARKSEAGREEN),
+    ("darkslateblue", DARKSLATEBLUE),
+    ("darkslategray", DARKSLATEGRAY),
+    ("darkslategrey", DARKSLATEGREY),
+    ("darkturquoise", DARKTURQUOISE),
+    ("darkviolet", DARKVIOLET),
+    ("deeppink", DEEPPINK),
+    ("deepskyblue", DEEPSKYBLUE),
+    ("dimgray", DIMGRAY),
+    ("dimgrey", DIMGREY),
+    ("dodgerblue", DODGERBLUE),
+    ("firebrick", FIREBRICK),
+    ("floralwhite", FLORALWHITE),
+    ("forestgreen", FORESTGREEN),
+    ("fuchsia", FUCHSIA),
+    ("gainsboro", GAINSBORO),
+    ("ghostwhite", GHOSTWHITE),
+    ("gold", GOLD),
+    ("goldenrod", GOLDENROD),
+    ("gray", GRAY),
+    ("green", GREEN),
+    ("greenyellow", GREENYELLOW),
+    ("grey", GREY),
+    ("honeydew", HONEYDEW),
+    ("hotpink", HOTPINK),
+    ("indianred", INDIANRED),
+    ("indigo", INDIGO),
+    ("ivory", IVORY),
+    ("khaki", KHAKI),
+    ("lavender", LAVENDER),
+    ("lavenderblush", LAVENDERBLUSH),
+    ("lawngreen", LAWNGREEN),
+    ("lemonchiffon", LEMONCHIFFON),
+    ("lightblue", LIGHTBLUE),
+    ("lightcoral", LIGHTCORAL),
+    ("lightcyan", LIGHTCYAN),
+    ("lightgoldenrodyellow", LIGHTGOLDENRODYELLOW),
+    ("lightgray", LIGHTGRAY),
+    ("lightgreen", LIGHTGREEN),
+    ("lightgrey", LIGHTGREY),
+    ("lightpink", LIGHTPINK),
+    ("lightsalmon", LIGHTSALMON),
+    ("lightseagreen", LIGHTSEAGREEN),
+    ("lightskyblue", LIGHTSKYBLUE),
+    ("lightslategray", LIGHTSLATEGRAY),
+    ("lightslategrey", LIGHTSLATEGREY),
+    ("lightsteelblue", LIGHTSTEELBLUE),
+    ("lightyellow", LIGHTYELLOW),
+    ("lime", LIME),
+    ("limegreen", LIMEGREEN),
+    ("linen", LINEN),
+    ("magenta", MAGENTA),
+    ("maroon", MAROON),
+    ("mediumaquamarine", MEDIUMAQUAMARINE),
+    ("mediumblue", MEDIUMBLUE),
+    ("mediumorchid", MEDIUMORCHID),
+    ("mediumpurple", MEDIUMPURPLE),
+    ("mediumseagreen", MEDIUMSEAGREEN),
+    ("mediumslateblue", MEDIUMSLATEBLUE),
+    ("mediumspringgreen", MEDIUMSPRINGGREEN),
+    ("mediumturquoise", MEDIUMTURQUOISE),
+    ("mediumvioletred", MEDIUMVIOLETRED),
+    ("midnightblue", MIDNIGHTBLUE),
+    ("mintcream", MINTCREAM),
+    ("mistyrose", MISTYROSE),
+    ("moccasin", MOCCASIN),
+    ("navajowhite", NAVAJOWHITE),
+    ("navy", NAVY),
+    ("oldlace", OLDLACE),
+    ("olive", OLIVE),
+    ("olivedrab", OLIVEDRAB),
+    ("orange", ORANGE),
+    ("orangered", ORANGERED),
+    ("orchid", ORCHID),
+    ("palegoldenrod", PALEGOLDENROD),
+    ("palegreen", PALEGREEN),
+    ("paleturquoise", PALETURQUOISE),
+    ("palevioletred", PALEVIOLETRED),
+    ("papayawhip", PAPAYAWHIP),
+    ("peachpuff", PEACHPUFF),
+    ("peru", PERU),
+    ("pink", PINK),
+    ("plum", PLUM),
+    ("powderblue", POWDERBLUE),
+    ("purple", PURPLE),
+    ("rebeccapurple", REBECCAPURPLE),
+    ("red", RED),
+    ("rosybrown", ROSYBROWN),
+    ("royalblue", ROYALBLUE),
+    ("saddlebrown", SADDLEBROWN),
+    ("salmon", SALMON),
+    ("sandybrown", SANDYBROWN),
+    ("seagreen", SEAGREEN),
+    ("seashell", SEASHELL),
+    ("sienna", SIENNA),
+    ("silver", SILVER),
+    ("skyblue", SKYBLUE),
+    ("slateblue", SLATEBLUE),
+    ("slategray", SLATEGRAY),
+    ("slategrey", SLATEGREY),
+    ("snow", SNOW),
+    ("springgreen", SPRINGGREEN),
+    ("steelblue", STEELBLUE),
+    ("tan", TAN),
+    ("teal", TEAL),
+    ("thistle", THISTLE),
+    ("tomato", TOMATO),
+    ("transparent", TRANSPARENT),
+    ("turquoise", TURQUOISE),
+    ("violet", VIOLET),
+    ("wheat", WHEAT),
+    ("white", WHITE),
+    ("whitesmoke", WHITESMOKE),
+    ("yellow", YELLOW),
+    ("yellowgreen", YELLOWGREEN),
+];
@@ -0,0 +1,383 @@
+//! Oklab and Okhsl perceptual color spaces, after Björn Ottosson's reference
+//! implementation, with perceptual interpolation and gradients.
+
+use super::{linear_to_srgb, srgb_to_linear};
+use std::f32::consts::PI;
+
+/// A color in the Oklab perceptual color space
+///
+/// `l` is the perceived lightness in [0, 1]; `a` and `b` are the green-red and
+/// blue-yellow axes, roughly in [-0.4, 0.4]. Equal steps in Oklab look like equal
+/// steps in color, so interpolating here keeps gradients from shifting hue or
+/// dipping in brightness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    /// Perceived lightness
+    pub l: f32,
+    /// Green (negative) to red (positive) axis
+    pub a: f32,
+    /// Blue (negative) to yellow (positive) axis
+    pub b: f32,
+}
+
+impl Oklab {
+    /// Converts an sRGB color to Oklab, ignoring its alpha
+    pub fn from_rgba(color: [u8; 4]) -> Self {
+        let [r, g, b] = [color[0], color[1], color[2]].map(|c| srgb_to_linear(c as f32 / 255.0));
+        linear_srgb_to_oklab(r, g, b)
+    }
+
+    /// Converts to an sRGB color with the given alpha, clipping colors outside the sRGB gamut
+    pub fn to_rgba(self, alpha: u8) -> [u8; 4] {
+        let [r, g, b] = self.to_srgb().map(|c| (c * 255.0).round() as u8);
+        [r, g, b, alpha]
+    }
+
+    /// Converts to sRGB encoded channels in [0, 1] without quantizing, clipping to the gamut
+    pub(crate) fn to_srgb(self) -> [f32; 3] {
+        oklab_to_linear_srgb(self).map(|c| linear_to_srgb(c.clamp(0.0, 1.0)))
+    }
+
+    /// Returns the color a fraction `t` of the way from `self` to `other`
+    pub fn lerp(self, other: Oklab, t: f32) -> Oklab {
+        Oklab {
+            l: self.l + (other.l - self.l) * t,
+            a: self.a + (other.a - self.a) * t,
+            b: self.b + (other.b - self.b) * t,
+        }
+    }
+
+    /// Returns the chroma, the distance from the gray axis
+    pub fn chroma(&self) -> f32 {
+        self.a.hypot(self.b)
+    }
+
+    /// Returns the hue in turns, in [0, 1)
+    pub fn hue(&self) -> f32 {
+        (self.b.atan2(self.a) / (2.0 * PI)).rem_euclid(1.0)
+    }
+}
+
+/// A color in Okhsl, a hue, saturation and lightness model built on Oklab
+///
+/// Like HSL, every combination of `h`, `s` and `l` in [0, 1] is a valid sRGB
+/// color, but lightness and hue follow perception: colors with the same `l` look
+/// equally bright whatever their hue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Okhsl {
+    /// Hue in turns, in [0, 1)
+    pub h: f32,
+    /// Saturation relative to the most saturated sRGB color of this hue and lightness
+    pub s: f32,
+    /// Perceived lightness
+    pub l: f32,
+}
+
+impl Okhsl {
+    /// Creates an Okhsl color from hue in turns, saturation and lightness
+    pub fn new(h: f32, s: f32, l: f32) -> Self {
+        Self { h, s, l }
+    }
+
+    /// Converts an sRGB color to Okhsl, ignoring its alpha
+    ///
+    /// ```
+    /// use artimate::color::Okhsl;
+    ///
+    /// let color = [30, 144, 255, 255];
+    /// assert_eq!(Okhsl::from_rgba(color).to_rgba(255), color);
+    /// ```
+    pub fn from_rgba(color: [u8; 4]) -> Self {
+        let lab = Oklab::from_rgba(color);
+        let c = lab.chroma();
+        let l = toe(lab.l);
+        if c < 1e-6 || lab.l <= 0.0 || lab.l >= 1.0 {
+            return Self { h: 0.0, s: 0.0, l };
+        }
+        let (a_, b_) = (lab.a / c, lab.b / c);
+        let h = 0.5 + 0.5 * (-lab.b).atan2(-lab.a) / PI;
+        let cs = chroma_stops(lab.l, a_, b_);
+        let s = if c < cs.mid {
+            let k_1 = MID * cs.zero;
+            let k_2 = 1.0 - k_1 / cs.mid;
+            MID * c / (k_1 + k_2 * c)
+        } else {
+            let k_0 = cs.mid;
+            let k_1 = (1.0 - MID) * cs.mid * cs.mid / (MID * MID * cs.zero);
+            let k_2 = 1.0 - k_1 / (cs.max - cs.mid);
+            let t = (c - k_0) / (k_1 + k_2 * (c - k_0));
+            MID + (1.0 - MID) * t
+        };
+        Self { h, s, l }
+    }
+
+    /// Converts to an sRGB color with the given alpha
+    pub fn to_rgba(self, alpha: u8) -> [u8; 4] {
+        let l = self.l.clamp(0.0, 1.0);
+        let s = self.s.clamp(0.0, 1.0);
+        if l >= 1.0 {
+            return [255, 255, 255, alpha];
+        }
+        if l <= 0.0 {
+            return [0, 0, 0, alpha];
+        }
+        let (b_, a_) = (2.0 * PI * self.h).sin_cos();
+        let lightness = toe_inv(l);
+        let cs = chroma_stops(lightness, a_, b_);
+        let c = if s < MID {
+            let t = s / MID;
+            let k_1 = MID * cs.zero;
+            let k_2 = 1.0 - k_1 / cs.mid;
+            t * k_1 / (1.0 - k_2 * t)
+        } else {
+            let t = (s - MID) / (1.0 - MID);
+            let k_0 = cs.mid;
+            let k_1 = (1.0 - MID) * cs.mid * cs.mid / (MID * MID * cs.zero);
+            let k_2 = 1.0 - k_1 / (cs.max - cs.mid);
+            k_0 + t * k_1 / (1.0 - k_2 * t)
+        };
+        Oklab {
+            l: lightness,
+            a: c * a_,
+            b: c * b_,
+        }
+        .to_rgba(alpha)
+    }
+}
+
+/// Interpolates between two sRGB colors in Oklab, and linearly in alpha
+///
+/// ```
+/// use artimate::color::{lerp, BLUE, WHITE};
+///
+/// assert_eq!(lerp(BLUE, WHITE, 0.0), BLUE);
+/// assert_eq!(lerp(BLUE, WHITE, 1.0), WHITE);
+/// ```
+pub fn lerp(from: [u8; 4], to: [u8; 4], t: f32) -> [u8; 4] {
+    let alpha = from[3] as f32 + (to[3] as f32 - from[3] as f32) * t;
+    Oklab::from_rgba(from)
+        .lerp(Oklab::from_rgba(to), t)
+        .to_rgba(alpha.round().clamp(0.0, 255.0) as u8)
+}
+
+/// Returns `n` colors evenly spaced along a gradient through `stops`, interpolated in Oklab
+///
+/// The stops are spread evenly over the gradient and the first and last colors
+/// are the first and last stops.
+///
+/// ```
+/// use artimate::color::{gradient, NAVY, ORANGE, WHITE};
+///
+/// let colors = gradient(&[NAVY, ORANGE, WHITE], 5);
+/// assert_eq!(colors.len(), 5);
+/// assert_eq!((colors[0], colors[2], colors[4]), (NAVY, ORANGE, WHITE));
+/// ```
+pub fn gradient(stops: &[[u8; 4]], n: usize) -> Vec<[u8; 4]> {
+    match stops {
+        [] => Vec::new(),
+        [color] => vec![*color; n],
+        _ => (0..n)
+            .map(|i| {
+                let t = i as f32 / (n - 1).max(1) as f32 * (stops.len() - 1) as f32;
+                let segment = (t.floor() as usize).min(stops.len() - 2);
+                lerp(stops[segment], stops[segment + 1], t - segment as f32)
+            })
+            .collect(),
+    }
+}
+
+/// Saturation at which Okhsl switches from the low to the high chroma curve
+const MID: f32 = 0.8;
+
+fn linear_srgb_to_oklab(r: f32, g: f32, b: f32) -> Oklab {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    Oklab {
+        l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    }
+}
+
+fn oklab_to_linear_srgb(lab: Oklab) -> [f32; 3] {
+    let l = (lab.l + 0.396_337_78 * lab.a + 0.215_803_76 * lab.b).powi(3);
+    let m = (lab.l - 0.105_561_346 * lab.a - 0.063_854_17 * lab.b).powi(3);
+    let s = (lab.l - 0.089_484_18 * lab.a - 1.291_485_5 * lab.b).powi(3);
+    LMS_TO_RGB.map(|w| w[0] * l + w[1] * m + w[2] * s)
+}
+
+/// Rows of the linear sRGB from LMS matrix, one per channel
+const LMS_TO_RGB: [[f32; 3]; 3] = [
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_38],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+];
+
+/// Returns how the cube roots of L, M and S change with chroma along the hue `(a, b)`
+fn lms_slopes(a: f32, b: f32) -> [f32; 3] {
+    [
+        0.396_337_78 * a + 0.215_803_76 * b,
+        -0.105_561_346 * a - 0.063_854_17 * b,
+        -0.089_484_18 * a - 1.291_485_5 * b,
+    ]
+}
+
+/// Returns the saturation `C / L` at which the hue `(a, b)` leaves the sRGB gamut
+fn max_saturation(a: f32, b: f32) -> f32 {
+    let (k, channel) = if -1.881_703_3 * a - 0.809_364_9 * b > 1.0 {
+        (
+            [
+                1.190_862_8,
+                1.765_767_3,
+                0.596_626_4,
+                0.755_152,
+                0.567_712_4,
+            ],
+            0,
+        )
+    } else if 1.814_441 * a - 1.194_452_8 * b > 1.0 {
+        (
+            [
+                0.739_565_15,
+                -0.459_544_04,
+                0.082_854_27,
+                0.125_410_7,
+                0.145_032_04,
+            ],
+            1,
+        )
+    } else {
+        (
+            [
+                1.357_336_5,
+                -0.009_157_99,
+                -1.151_302_1,
+                -0.505_596_06,
+                0.006_921_67,
+            ],
+            2,
+        )
+    };
+    let w = LMS_TO_RGB[channel];
+    let saturation = k[0] + k[1] * a + k[2] * b + k[3] * a * a + k[4] * a * b;
+    let slopes = lms_slopes(a, b);
+    // One Halley step refines the polynomial approximation
+    let (mut f, mut f1, mut f2) = (0.0, 0.0, 0.0);
+    for (w, k) in w.iter().zip(slopes) {
+        let root = 1.0 + saturation * k;
+        f += w * root * root * root;
+        f1 += w * 3.0 * k * root * root;
+        f2 += w * 6.0 * k * k * root;
+    }
+    saturation - f * f1 / (f1 * f1 - 0.5 * f * f2)
+}
+
+/// Returns the lightness and chroma of the most saturated color of the hue `(a, b)`
+fn find_cusp(a: f32, b: f32) -> (f32, f32) {
+    let saturation = max_saturation(a, b);
+    let rgb = oklab_to_linear_srgb(Oklab {
+        l: 1.0,
+        a: saturation * a,
+        b: saturation * b,
+    });
+    let l = (1.0 / rgb[0].max(rgb[1]).max(rgb[2])).cbrt();
+    (l, l * saturation)
+}
+
+/// Returns how far along the line from `(l0, 0)` to `(l1, c1)` the hue `(a, b)` leaves the gamut
+fn gamut_intersection(a: f32, b: f32, l1: f32, c1: f32, l0: f32, cusp: (f32, f32)) -> f32 {
+    let (cusp_l, cusp_c) = cusp;
+    if (l1 - l0) * cusp_c - (cusp_l - l0) * c1 <= 0.0 {
+        // Lower half of the gamut triangle, which is exact
+        return cusp_c * l0 / (c1 * cusp_l + cusp_c * (l0 - l1));
+    }
+    let t = cusp_c * (l0 - 1.0) / (c1 * (cusp_l - 1.0) + cusp_c * (l0 - l1));
+    let slopes = lms_slopes(a, b);
+    let l = l0 * (1.0 - t) + t * l1;
+    let c = t * c1;
+    let mut lms = [0.0; 3];
+    let mut lms_dt = [0.0; 3];
+    let mut lms_dt2 = [0.0; 3];
+    for i in 0..3 {
+        let root = l + c * slopes[i];
+        let root_dt = (l1 - l0) + c1 * slopes[i];
+        lms[i] = root * root * root;
+        lms_dt[i] = 3.0 * root_dt * root * root;
+        lms_dt2[i] = 6.0 * root_dt * root_dt * root;
+    }
+    // One Halley step towards the nearest channel reaching 1
+    let step = LMS_TO_RGB
+        .iter()
+        .map(|w| {
+            let dot = |v: &[f32; 3]| w[0] * v[0] + w[1] * v[1] + w[2] * v[2];
+            let (f, f1, f2) = (dot(&lms) - 1.0, dot(&lms_dt), dot(&lms_dt2));
+            let u = f1 / (f1 * f1 - 0.5 * f * f2);
+            if u >= 0.0 {
+                -f * u
+            } else {
+                f32::MAX
+            }
+        })
+        .fold(f32::MAX, f32::min);
+    t + step
+}
+
+/// Okhsl's lightness estimate from Oklab lightness
+fn toe(x: f32) -> f32 {
+    let (k_1, k_2) = (0.206, 0.03);
+    let k_3 = (1.0 + k_1) / (1.0 + k_2);
+    let y = k_3 * x - k_1;
+    0.5 * (y + (y * y + 4.0 * k_2 * k_3 * x).sqrt())
+}
+
+/// Inverse of `toe`
+fn toe_inv(x: f32) -> f32 {
+    let (k_1, k_2) = (0.206, 0.03);
+    let k_3 = (1.0 + k_1) / (1.0 + k_2);
+    (x * x + k_1 * x) / (k_3 * (x + k_2))
+}
+
+/// Chroma values Okhsl saturation is mapped through for a lightness and hue
+struct ChromaStops {
+    zero: f32,
+    mid: f32,
+    max: f32,
+}
+
+/// Returns the chroma stops for lightness `l` of the hue `(a, b)`
+fn chroma_stops(l: f32, a: f32, b: f32) -> ChromaStops {
+    let cusp = find_cusp(a, b);
+    let max = gamut_intersection(a, b, l, 1.0, l, cusp);
+    let (cusp_l, cusp_c) = cusp;
+    let (st_max_s, st_max_t) = (cusp_c / cusp_l, cusp_c / (1.0 - cusp_l));
+    let k = max / (l * st_max_s).min((1.0 - l) * st_max_t);
+
+    // Smooth approximation of the gamut boundary for the mid saturation
+    let s = 0.115_169_93
+        + 1.0
+            / (7.447_789_7
+                + 4.159_012_4 * b
+                + a * (-2.195_573_5
+                    + 1.751_984 * b
+                    + a * (-2.137_049_4 - 10.023_01 * b
+                        + a * (-4.248_945_7 + 5.387_708 * b + 4.698_91 * a))));
+    let t = 0.112_396_42
+        + 1.0
+            / (1.613_203_2 - 0.681_243_8 * b
+                + a * (0.403_706_12
+                    + 0.901_481_2 * b
+                    + a * (-0.270_879_43
+                        + 0.612_239_9 * b
+                        + a * (0.002_992_15 - 0.453_995_68 * b - 0.146_618_72 * a))));
+    let (c_a, c_b) = (l * s, (1.0 - l) * t);
+    let mid = 0.9
+        * k
+        * (1.0 / (1.0 / c_a.powi(4) + 1.0 / c_b.powi(4)))
+            .sqrt()
+            .sqrt();
+
+    let (c_a, c_b) = (l * 0.4, (1.0 - l) * 0.8);
+    let zero = (1.0 / (1.0 / (c_a * c_a) + 1.0 / (c_b * c_b))).sqrt();
+    ChromaStops { zero, mid, max }
+}
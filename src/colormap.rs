@@ -0,0 +1,173 @@
+//! # Colormaps
+//!
+//! Perceptually uniform colormaps for mapping scalar values to colors, shared by
+//! the heat map helper and anything else that turns numbers into pixels.
+//!
+//! Viridis, plasma, inferno and magma are the matplotlib maps and turbo is Google's
+//! improved rainbow map, each evaluated from a polynomial fit. Cubehelix is Dave
+//! Green's monotonic-lightness helix, computed from its defining formula.
+//!
+//! ```
+//! use artimate::colormap::Colormap;
+//!
+//! assert_eq!(Colormap::Viridis.sample(0.0), [71, 1, 85, 255]);
+//!
+//! // Recolor a grayscale frame by luminance
+//! let mut frame = vec![128; 4 * 16];
+//! Colormap::Inferno.apply_luminance(&mut frame);
+//! ```
+
+use std::f64::consts::PI;
+
+/// A colormap mapping values in [0, 1] to colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Colormap {
+    /// Perceptually uniform blue to green to yellow
+    #[default]
+    Viridis,
+    /// Perceptually uniform blue to magenta to yellow
+    Plasma,
+    /// Perceptually uniform black to red to pale yellow
+    Inferno,
+    /// Perceptually uniform black to purple to pale yellow
+    Magma,
+    /// Rainbow-like blue to green to red with smooth lightness
+    Turbo,
+    /// Black to white through a helix of hues with monotonically increasing lightness
+    Cubehelix,
+}
+
+impl Colormap {
+    /// Every built-in colormap
+    pub const ALL: [Colormap; 6] = [
+        Colormap::Viridis,
+        Colormap::Plasma,
+        Colormap::Inferno,
+        Colormap::Magma,
+        Colormap::Turbo,
+        Colormap::Cubehelix,
+    ];
+
+    /// Returns the RGBA color at `t`, clamped to [0, 1]
+    ///
+    /// NaN samples the start of the map.
+    pub fn sample(&self, t: f32) -> [u8; 4] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) } as f64;
+        let rgb = match self {
+            Colormap::Viridis => polynomial(&VIRIDIS, t),
+            Colormap::Plasma => polynomial(&PLASMA, t),
+            Colormap::Inferno => polynomial(&INFERNO, t),
+            Colormap::Magma => polynomial(&MAGMA, t),
+            Colormap::Turbo => polynomial(&TURBO, t),
+            Colormap::Cubehelix => cubehelix(t),
+        };
+        let [r, g, b] = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        [r, g, b, 255]
+    }
+
+    /// Returns the colormap sampled at 256 evenly spaced points
+    ///
+    /// Entry `i` is `sample(i / 255)`; index with `(t * 255.0).round()` when
+    /// coloring many values.
+    pub fn lut(&self) -> [[u8; 4]; 256] {
+        std::array::from_fn(|i| self.sample(i as f32 / 255.0))
+    }
+
+    /// Writes the colors of `values` in [0, 1] into an RGBA buffer
+    ///
+    /// `out` must hold 4 bytes per value; extra values or bytes are ignored.
+    pub fn apply(&self, values: &[f32], out: &mut [u8]) {
+        let lut = self.lut();
+        for (v, px) in values.iter().zip(out.chunks_exact_mut(4)) {
+            let t = if v.is_nan() { 0.0 } else { v.clamp(0.0, 1.0) };
+            px.copy_from_slice(&lut[(t * 255.0).round() as usize]);
+        }
+    }
+
+    /// Recolors an RGBA frame in place by the luminance of each pixel, keeping alpha
+    pub fn apply_luminance(&self, frame: &mut [u8]) {
+        let lut = self.lut();
+        for px in frame.chunks_exact_mut(4) {
+            let y = 0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32;
+            let color = lut[(y.round() as usize).min(255)];
+            px[..3].copy_from_slice(&color[..3]);
+        }
+    }
+}
+
+/// Coefficients of degree 6 polynomial fits, lowest degree first, per channel
+type Fit = [[f64; 3]; 7];
+
+const VIRIDIS: Fit = [
+    [0.277727327223, 0.00540734454497, 0.334099805335],
+    [0.105093043109, 1.4046135299, 1.38459016259],
+    [-0.330861828726, 0.214847559468, 0.0950951630282],
+    [-4.63423049898, -5.79910097335, -19.3324409563],
+    [6.22826993635, 14.1799333668, 56.6905526007],
+    [4.77638499767, -13.7451453777, -65.3530326334],
+    [-5.43545585593, 4.64585261218, 26.3124352496],
+];
+
+const PLASMA: Fit = [
+    [0.058732343924, 0.0233367089257, 0.543340182675],
+    [2.1765146342, 0.238383417126, 0.753960459978],
+    [-2.68946047646, -7.45585113574, 3.11079993972],
+    [6.13034834589, 42.3461881477, -28.5188546533],
+    [-11.1074361906, -82.6663110943, 60.1398476742],
+    [10.0230655765, 71.413617701, -54.0721865556],
+    [-3.65871384278, -22.9315346546, 18.1919077854],
+];
+
+const INFERNO: Fit = [
+    [0.000218940369119, 0.001651004631, -0.0194808984371],
+    [0.106513419486, 0.563956436788, 3.93271238889],
+    [11.6024930825, -3.97285396567, -15.9423941063],
+    [-41.7039961314, 17.4363988821, 44.3541451987],
+    [77.1629356994, -33.4023589421, -81.8073092574],
+    [-71.319428245, 32.626064264, 73.209519858],
+    [25.1311262248, -12.2426689524, -23.0703250029],
+];
+
+const MAGMA: Fit = [
+    [-0.00213648505394, -0.000749655052795, -0.00538612785532],
+    [0.251660540737, 0.677523243684, 2.49402659931],
+    [8.35371727922, -3.57771951496, 0.314467903013],
+    [-27.6687330858, 14.264730781, -13.6492131881],
+    [52.1761398123, -27.9436060717, 12.9441694424],
+    [-50.7685253647, 29.0465828213, 4.23415299385],
+    [18.6557050659, -11.48977352, -5.60196150873],
+];
+
+const TURBO: Fit = [
+    [0.13572138, 0.09140261, 0.1066733],
+    [4.6153926, 2.19418839, 12.64194608],
+    [-42.66032258, 4.84296658, -60.58204836],
+    [132.13108234, -14.18503333, 110.36276771],
+    [-152.94239396, 4.27729857, -89.90310912],
+    [59.28637943, 2.82956604, 27.34824973],
+    [0.0, 0.0, 0.0],
+];
+
+/// Evaluates a per-channel polynomial fit at `t` with Horner's method
+fn polynomial(fit: &Fit, t: f64) -> [f64; 3] {
+    let mut rgb = [0.0; 3];
+    for coefficients in fit.iter().rev() {
+        for (c, k) in rgb.iter_mut().zip(coefficients) {
+            *c = *c * t + k;
+        }
+    }
+    rgb
+}
+
+/// Cubehelix with the standard parameters: start 0.5, -1.5 rotations, hue 1, gamma 1
+fn cubehelix(t: f64) -> [f64; 3] {
+    let (start, rotations, hue) = (0.5, -1.5, 1.0);
+    let angle = 2.0 * PI * (start / 3.0 + 1.0 + rotations * t);
+    let amplitude = hue * t * (1.0 - t) / 2.0;
+    let (sin, cos) = angle.sin_cos();
+    [
+        t + amplitude * (-0.14861 * cos + 1.78277 * sin),
+        t + amplitude * (-0.29227 * cos - 0.90649 * sin),
+        t + amplitude * (1.97294 * cos),
+    ]
+}
@@ -0,0 +1,309 @@
+//! # Render Farm
+//!
+//! Splits the frames of a long deterministic animation across several headless
+//! worker processes, on one machine or many, and merges their output into a single
+//! timing manifest.
+//!
+//! Every worker runs the same sketch binary. A process is a worker when the
+//! `ARTIMATE_WORKER` environment variable is set to `index/count`; it then renders
+//! its contiguous share of the frames in `ARTIMATE_FRAMES` (`start..end`) to the
+//! directory in `ARTIMATE_FARM_DIR` and exits. Each worker replays the update
+//! function from frame 0 up to its first frame, so the model is exactly what a
+//! single long render would have seen.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, AppMode, Config};
+//! use artimate::farm::{self, Job};
+//!
+//! #[derive(Clone)]
+//! struct Model {
+//!     angle: f32,
+//! }
+//!
+//! fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
+//!     model.angle = app.time;
+//!     model
+//! }
+//!
+//! fn draw(app: &App<AppMode, Model>, _model: &Model) -> Vec<u8> {
+//!     app.new_frame()
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let config = Config::with_dims(1920, 1080).deterministic(30.0);
+//!     let mut app = App::app(Model { angle: 0.0 }, config, update, draw);
+//!     if let Some(job) = Job::from_env()? {
+//!         app.render_job(&job)?;
+//!         return Ok(());
+//!     }
+//!     // Coordinator: run 4 local workers, then merge their manifests
+//!     let manifest = farm::coordinate(0..3600, 4, "render")?;
+//!     println!("merged manifest: {}", manifest.display());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! To farm out across machines, start the same binary on each machine with
+//! `ARTIMATE_WORKER=<index>/<count>`, `ARTIMATE_FRAMES` and `ARTIMATE_FARM_DIR` set,
+//! copy the output directories together and call [`merge`] with the full frame range.
+
+use crate::app::{save_frame, App};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+/// Environment variable holding a worker's `index/count`
+pub const WORKER_ENV: &str = "ARTIMATE_WORKER";
+/// Environment variable holding the full frame range as `start..end`
+pub const FRAMES_ENV: &str = "ARTIMATE_FRAMES";
+/// Environment variable holding the output directory
+pub const DIR_ENV: &str = "ARTIMATE_FARM_DIR";
+
+/// One worker's share of a farmed render
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    /// Index of this worker, from 0 to `count - 1`
+    pub index: u32,
+    /// Total number of workers
+    pub count: u32,
+    /// Frames of the whole render, shared by all workers
+    pub frames: Range<u32>,
+    /// Directory the frames and the worker manifest are written to
+    pub dir: PathBuf,
+}
+
+impl Job {
+    /// Creates the job of worker `index` out of `count`
+    pub fn new(frames: Range<u32>, index: u32, count: u32, dir: impl AsRef<Path>) -> Self {
+        Self {
+            index,
+            count,
+            frames,
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Reads the job from the environment, returning None if this process is not a worker
+    ///
+    /// # Returns
+    /// * `Ok(Some(Job))` - If `ARTIMATE_WORKER` is set and all variables are valid
+    /// * `Ok(None)` - If `ARTIMATE_WORKER` is not set
+    /// * `Err(io::Error)` - If a variable is missing or malformed
+    pub fn from_env() -> io::Result<Option<Self>> {
+        let Ok(worker) = std::env::var(WORKER_ENV) else {
+            return Ok(None);
+        };
+        let invalid = |name: &str, value: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid {}: {:?}", name, value),
+            )
+        };
+        let (index, count) = worker
+            .split_once('/')
+            .and_then(|(i, n)| Some((i.trim().parse().ok()?, n.trim().parse().ok()?)))
+            .filter(|&(i, n): &(u32, u32)| i < n)
+            .ok_or_else(|| invalid(WORKER_ENV, &worker))?;
+        let frames = std::env::var(FRAMES_ENV).map_err(|_| invalid(FRAMES_ENV, ""))?;
+        let range = frames
+            .split_once("..")
+            .and_then(|(a, b)| Some(a.trim().parse().ok()?..b.trim().parse().ok()?))
+            .filter(|range: &Range<u32>| range.start <= range.end)
+            .ok_or_else(|| invalid(FRAMES_ENV, &frames))?;
+        let dir = std::env::var_os(DIR_ENV).ok_or_else(|| invalid(DIR_ENV, ""))?;
+        Ok(Some(Self::new(range, index, count, PathBuf::from(dir))))
+    }
+
+    /// Returns the frames this worker renders, none if `index` is not below `count`
+    pub fn worker_frames(&self) -> Range<u32> {
+        split(self.frames.clone(), self.count)
+            .get(self.index as usize)
+            .cloned()
+            .unwrap_or(self.frames.end..self.frames.end)
+    }
+}
+
+/// Splits `frames` into `count` contiguous ranges whose lengths differ by at most one
+///
+/// ```
+/// use artimate::farm::split;
+///
+/// assert_eq!(split(0..10, 3), vec![0..4, 4..7, 7..10]);
+/// ```
+pub fn split(frames: Range<u32>, count: u32) -> Vec<Range<u32>> {
+    let count = count.max(1);
+    let len = frames.end.saturating_sub(frames.start);
+    let (base, extra) = (len / count, len % count);
+    let mut start = frames.start;
+    (0..count)
+        .map(|i| {
+            let end = start + base + u32::from(i < extra);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+impl<Mode, M> App<Mode, M>
+where
+    M: Clone,
+{
+    /// Renders this worker's share of a farmed render without a window
+    ///
+    /// Frames are written to `job.dir` in `Config::export_format`, named by
+    /// `Config::frame_filename`, followed by a `worker_<index>.manifest` listing
+    /// them, which `merge` combines. Time is deterministic at
+    /// `config.deterministic`, `config.fps` or 60 fps.
+    ///
+    /// # Returns
+    /// * `Ok(PathBuf)` - The path of the worker manifest
+    /// * `Err(io::Error)` - If the directory, a frame or the manifest could not be written
+    pub fn render_job(&mut self, job: &Job) -> io::Result<PathBuf> {
+        fs::create_dir_all(&job.dir)?;
+        let frames = job.worker_frames();
+        let fps = self
+            .config
+            .deterministic
+            .or(self.config.fps)
+            .unwrap_or(60.0);
+        self.advance_headless(0..frames.start);
+
+        let mut manifest = format!("fps {}\n", fps);
+        for frame in frames {
+            let (data, width, height) = self.render_headless_frame(frame);
+            let name = self.config.frame_filename(frame);
+            save_frame(
+                data,
+                job.dir.join(&name).to_string_lossy().to_string(),
+                width,
+                height,
+                &self.config.save_options(),
+            )?;
+            manifest.push_str(&format!("{} {}\n", frame, name));
+            self.advance_headless(frame..frame + 1);
+        }
+        let path = job.dir.join(format!("worker_{}.manifest", job.index));
+        fs::write(&path, manifest)?;
+        Ok(path)
+    }
+}
+
+/// Runs `workers` local worker processes of the current executable and merges their output
+///
+/// Each worker is started with the farm environment variables set and renders its
+/// share of `frames` into `dir`. Returns the path of the merged manifest.
+pub fn coordinate(frames: Range<u32>, workers: u32, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let exe = std::env::current_exe()?;
+    let children = (0..workers.max(1))
+        .map(|index| {
+            Command::new(&exe)
+                .args(std::env::args_os().skip(1))
+                .env(WORKER_ENV, format!("{}/{}", index, workers.max(1)))
+                .env(FRAMES_ENV, format!("{}..{}", frames.start, frames.end))
+                .env(DIR_ENV, dir)
+                .spawn()
+        })
+        .collect::<io::Result<Vec<Child>>>()?;
+    let mut children = children.into_iter().enumerate();
+    while let Some((index, mut child)) = children.next() {
+        let failure = match child.wait() {
+            Ok(status) if status.success() => continue,
+            Ok(status) => io::Error::other(format!("worker {} failed: {}", index, status)),
+            Err(err) => err,
+        };
+        // Don't leave the other workers rendering in the background
+        for (_, mut child) in children {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        return Err(failure);
+    }
+    merge(dir, frames)
+}
+
+/// Combines the worker manifests in `dir` into a single `frames.ffconcat` manifest
+///
+/// The merged manifest can be encoded with `ffmpeg -f concat -i frames.ffconcat out.mp4`.
+///
+/// # Returns
+/// * `Ok(PathBuf)` - The path of the merged manifest
+/// * `Err(io::Error)` - If no manifests were found, workers disagree on the frame rate,
+///   or any frame of `frames` is missing
+pub fn merge(dir: impl AsRef<Path>, frames: Range<u32>) -> io::Result<PathBuf> {
+    let requested = frames;
+    let dir = dir.as_ref();
+    let mut fps = None;
+    let mut frames = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "manifest") {
+            continue;
+        }
+        let contents = fs::read_to_string(&path)?;
+        let mut lines = contents.lines();
+        let worker_fps = lines
+            .next()
+            .and_then(|line| line.strip_prefix("fps "))
+            .and_then(|v| v.parse::<f32>().ok())
+            .ok_or_else(|| malformed(&path))?;
+        if fps.is_some_and(|fps| fps != worker_fps) {
+            return Err(io::Error::other(
+                "workers rendered at different frame rates",
+            ));
+        }
+        fps = Some(worker_fps);
+        for line in lines {
+            let (frame, name) = line.split_once(' ').ok_or_else(|| malformed(&path))?;
+            let frame: u32 = frame.parse().map_err(|_| malformed(&path))?;
+            frames.push((frame, name.to_string()));
+        }
+    }
+    let fps =
+        fps.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no worker manifests to merge"))?;
+    frames.sort();
+    frames.dedup_by_key(|(frame, _)| *frame);
+    frames.retain(|(frame, _)| requested.contains(frame));
+    // Every requested frame is present when the frames fill the range in order
+    let mut expected = requested.start;
+    for &(frame, _) in &frames {
+        if frame != expected {
+            break;
+        }
+        expected += 1;
+    }
+    if expected < requested.end {
+        let next = frames
+            .iter()
+            .map(|(frame, _)| *frame)
+            .find(|&frame| frame > expected);
+        return Err(io::Error::other(format!(
+            "frames {} to {} are missing",
+            expected,
+            next.unwrap_or(requested.end) - 1
+        )));
+    }
+
+    let duration_us = (1_000_000.0 / fps).round() as u64;
+    let mut manifest = String::from("ffconcat version 1.0\n");
+    for (frame, name) in &frames {
+        manifest.push_str(&format!(
+            "# frame {}\nfile '{}'\nduration {}us\n",
+            frame, name, duration_us
+        ));
+    }
+    let path = dir.join("frames.ffconcat");
+    fs::write(&path, manifest)?;
+    Ok(path)
+}
+
+fn malformed(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("malformed worker manifest {}", path.display()),
+    )
+}
@@ -0,0 +1,85 @@
+//! # Geometry
+//!
+//! Simple geometric types for laying out drawings on the canvas.
+
+/// An axis-aligned rectangle in pixel coordinates
+///
+/// `x` and `y` are the top-left corner, with y increasing downward like the
+/// pixel buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    /// Left edge in pixels
+    pub x: f32,
+    /// Top edge in pixels
+    pub y: f32,
+    /// Width in pixels
+    pub width: f32,
+    /// Height in pixels
+    pub height: f32,
+}
+
+impl Rect {
+    /// Creates a rectangle from its top-left corner and size
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a rectangle of the given size with its top-left corner at the origin
+    pub fn from_size(width: f32, height: f32) -> Self {
+        Self::new(0.0, 0.0, width, height)
+    }
+
+    /// Returns the x-coordinate of the right edge
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// Returns the y-coordinate of the bottom edge
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Returns the center point of the rectangle
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Returns the rectangle shrunk by `amount` pixels on every side
+    ///
+    /// The size never becomes negative; an inset larger than half the size
+    /// collapses the rectangle onto its center.
+    pub fn inset(&self, amount: f32) -> Self {
+        let dx = amount.min(self.width / 2.0);
+        let dy = amount.min(self.height / 2.0);
+        Self::new(
+            self.x + dx,
+            self.y + dy,
+            self.width - 2.0 * dx,
+            self.height - 2.0 * dy,
+        )
+    }
+
+    /// Returns true if the point `(x, y)` lies inside the rectangle
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Maps normalized coordinates in [0, 1] to a pixel position inside the rectangle
+    ///
+    /// `(0, 0)` is the top-left corner and `(1, 1)` the bottom-right corner.
+    pub fn map(&self, u: f32, v: f32) -> (f32, f32) {
+        (self.x + u * self.width, self.y + v * self.height)
+    }
+
+    /// Maps a pixel position to normalized coordinates relative to the rectangle
+    ///
+    /// The inverse of `map`; points outside the rectangle map outside [0, 1].
+    pub fn normalize(&self, x: f32, y: f32) -> (f32, f32) {
+        ((x - self.x) / self.width, (y - self.y) / self.height)
+    }
+}
@@ -0,0 +1,157 @@
+//! # Gradients
+//!
+//! Linear and radial gradient fills interpolated in Oklab. Large soft gradients
+//! have fewer 8-bit levels than pixels, which shows as visible bands; the fills
+//! add a per-pixel dither below one level before quantizing, so the steps
+//! disappear. Dithering is on by default and can be turned off with
+//! [`Gradient::dither`].
+//!
+//! ```
+//! use artimate::gradient::Gradient;
+//!
+//! let (width, height) = (200, 100);
+//! let mut frame = vec![0; (width * height * 4) as usize];
+//! let sky = Gradient::new(&[[12, 20, 60, 255], [250, 140, 90, 255]]);
+//! sky.fill_linear(&mut frame, width, (0.0, 0.0), (0.0, 100.0));
+//! let glow = Gradient::new(&[[255, 240, 200, 255], [255, 240, 200, 0]]);
+//! glow.fill_radial(&mut frame, width, (100.0, 80.0), 60.0);
+//! ```
+
+use crate::color::Oklab;
+
+/// Number of precomputed samples along a gradient
+const LUT_SIZE: usize = 1024;
+
+/// A color gradient through evenly spaced or positioned stops
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// Colors at positions in [0, 1], sorted by position
+    stops: Vec<(f32, [u8; 4])>,
+    /// Whether fills dither before quantizing
+    dither: bool,
+    /// Straight alpha sRGB samples in [0, 255], `LUT_SIZE` long
+    lut: Vec<[f32; 4]>,
+}
+
+impl Gradient {
+    /// Creates a gradient through `colors`, spread evenly from 0 to 1
+    pub fn new(colors: &[[u8; 4]]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        let stops = colors
+            .iter()
+            .enumerate()
+            .map(|(i, color)| (i as f32 / last, *color))
+            .collect();
+        Self::with_stops(stops)
+    }
+
+    /// Creates a gradient from colors at positions in [0, 1]
+    ///
+    /// Stops are sorted by position. Before the first stop and after the last the
+    /// gradient keeps their colors.
+    pub fn with_stops(mut stops: Vec<(f32, [u8; 4])>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let lut = (0..LUT_SIZE)
+            .map(|i| sample(&stops, i as f32 / (LUT_SIZE - 1) as f32))
+            .collect();
+        Self {
+            stops,
+            dither: true,
+            lut,
+        }
+    }
+
+    /// Sets whether fills dither to prevent banding and returns updated gradient
+    pub fn dither(self, dither: bool) -> Self {
+        Self { dither, ..self }
+    }
+
+    /// Returns the color at `t`, clamped to [0, 1]
+    pub fn color_at(&self, t: f32) -> [u8; 4] {
+        self.lookup(t).map(|c| c.round() as u8)
+    }
+
+    /// Fills an RGBA frame with a gradient running from `from` (t = 0) to `to` (t = 1)
+    ///
+    /// Pixels are blended over the frame with the gradient's alpha.
+    pub fn fill_linear(&self, frame: &mut [u8], width: u32, from: (f32, f32), to: (f32, f32)) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length_sq = (dx * dx + dy * dy).max(f32::EPSILON);
+        self.fill(frame, width, |x, y| {
+            ((x - from.0) * dx + (y - from.1) * dy) / length_sq
+        });
+    }
+
+    /// Fills an RGBA frame with a gradient from `center` (t = 0) out to `radius` (t = 1)
+    ///
+    /// Pixels are blended over the frame with the gradient's alpha.
+    pub fn fill_radial(&self, frame: &mut [u8], width: u32, center: (f32, f32), radius: f32) {
+        let radius = radius.max(f32::EPSILON);
+        self.fill(frame, width, |x, y| {
+            (x - center.0).hypot(y - center.1) / radius
+        });
+    }
+
+    /// Blends the gradient over every pixel, at the position given by `t_at` for the pixel center
+    fn fill(&self, frame: &mut [u8], width: u32, t_at: impl Fn(f32, f32) -> f32) {
+        if width == 0 || self.stops.is_empty() {
+            return;
+        }
+        for (i, px) in frame.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let color = self.lookup(t_at(x as f32 + 0.5, y as f32 + 0.5));
+            let offset = if self.dither { dither(x, y) } else { 0.5 };
+            let [r, g, b, a] = color.map(|c| (c + offset).floor().clamp(0.0, 255.0));
+            let alpha = a / 255.0;
+            for (dst, src) in px[..3].iter_mut().zip([r, g, b]) {
+                *dst = (src * alpha + *dst as f32 * (1.0 - alpha)).round() as u8;
+            }
+            px[3] = (a + px[3] as f32 * (1.0 - alpha)).round() as u8;
+        }
+    }
+
+    /// Returns the interpolated color at `t` from the lookup table, unquantized
+    fn lookup(&self, t: f32) -> [f32; 4] {
+        if self.lut.is_empty() {
+            return [0.0; 4];
+        }
+        let pos = t.clamp(0.0, 1.0) * (LUT_SIZE - 1) as f32;
+        let i = (pos as usize).min(LUT_SIZE - 2);
+        let f = pos - i as f32;
+        let (a, b) = (self.lut[i], self.lut[i + 1]);
+        std::array::from_fn(|c| a[c] + (b[c] - a[c]) * f)
+    }
+}
+
+/// Returns a per-pixel offset in [0, 1) with blue-noise-like spacing
+///
+/// Interleaved gradient noise: neighbouring pixels get well separated offsets, so
+/// adding it before truncating to 8 bits replaces banding with fine grain that is
+/// not visible at normal viewing distance.
+pub fn dither(x: u32, y: u32) -> f32 {
+    let v = 52.982_918 * (0.067_110_56 * x as f32 + 0.005_837_15 * y as f32).fract();
+    v.fract()
+}
+
+/// Interpolates the stops in Oklab at `t`, returning straight alpha sRGB in [0, 255]
+fn sample(stops: &[(f32, [u8; 4])], t: f32) -> [f32; 4] {
+    let Some(&(_, first)) = stops.first() else {
+        return [0.0; 4];
+    };
+    let next = stops.iter().position(|(pos, _)| *pos > t);
+    let (from, to, f) = match next {
+        None => (stops[stops.len() - 1].1, stops[stops.len() - 1].1, 0.0),
+        Some(0) => (first, first, 0.0),
+        Some(i) => {
+            let (t0, c0) = stops[i - 1];
+            let (t1, c1) = stops[i];
+            (c0, c1, (t - t0) / (t1 - t0).max(f32::EPSILON))
+        }
+    };
+    let [r, g, b] = Oklab::from_rgba(from)
+        .lerp(Oklab::from_rgba(to), f)
+        .to_srgb()
+        .map(|c| c * 255.0);
+    let a = from[3] as f32 + (to[3] as f32 - from[3] as f32) * f;
+    [r, g, b, a]
+}
@@ -0,0 +1,147 @@
+//! # Composition Guides
+//!
+//! Overlay guides drawn over the window preview to help compose a piece. Guides
+//! are never part of saved or exported frames.
+//!
+//! ```
+//! use artimate::app::Config;
+//! use artimate::guides::Guide;
+//!
+//! let config = Config::with_dims(1920, 1080).guides(&[Guide::RuleOfThirds, Guide::SafeAreas]);
+//! ```
+
+/// A composition guide drawn over the preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Guide {
+    /// Lines dividing the frame into thirds horizontally and vertically
+    RuleOfThirds,
+    /// A golden ratio spiral built from successive golden rectangle subdivisions
+    GoldenSpiral,
+    /// Action safe (90%) and title safe (80%) rectangles used for video
+    SafeAreas,
+    /// A cross marking the center of the frame
+    CenterCross,
+}
+
+/// Draws `guides` in `color` over an RGBA frame of the given size
+pub(crate) fn draw_guides(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    guides: &[Guide],
+    color: [u8; 4],
+) {
+    let mut canvas = Canvas {
+        frame,
+        width,
+        height,
+        color,
+    };
+    let (w, h) = (width as f32, height as f32);
+    for guide in guides {
+        match guide {
+            Guide::RuleOfThirds => {
+                for t in [1.0 / 3.0, 2.0 / 3.0] {
+                    canvas.line(w * t, 0.0, w * t, h);
+                    canvas.line(0.0, h * t, w, h * t);
+                }
+            }
+            Guide::GoldenSpiral => canvas.golden_spiral(),
+            Guide::SafeAreas => {
+                for inset in [0.05, 0.1] {
+                    canvas.rect(w * inset, h * inset, w * (1.0 - inset), h * (1.0 - inset));
+                }
+            }
+            Guide::CenterCross => {
+                let arm = w.min(h) * 0.05;
+                canvas.line(w / 2.0 - arm, h / 2.0, w / 2.0 + arm, h / 2.0);
+                canvas.line(w / 2.0, h / 2.0 - arm, w / 2.0, h / 2.0 + arm);
+            }
+        }
+    }
+}
+
+struct Canvas<'a> {
+    frame: &'a mut [u8],
+    width: u32,
+    height: u32,
+    color: [u8; 4],
+}
+
+impl Canvas<'_> {
+    fn plot(&mut self, x: f32, y: f32) {
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return;
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let alpha = self.color[3] as u32;
+        for (dst, src) in self.frame[i..i + 3].iter_mut().zip(self.color) {
+            *dst = ((src as u32 * alpha + *dst as u32 * (255 - alpha)) / 255) as u8;
+        }
+    }
+
+    fn line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32) {
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as u32;
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            self.plot(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+        }
+    }
+
+    fn rect(&mut self, x0: f32, y0: f32, x1: f32, y1: f32) {
+        self.line(x0, y0, x1, y0);
+        self.line(x1, y0, x1, y1);
+        self.line(x1, y1, x0, y1);
+        self.line(x0, y1, x0, y0);
+    }
+
+    /// Draws a quarter circle of radius `r` around `(cx, cy)` starting at angle `start`
+    fn arc(&mut self, cx: f32, cy: f32, r: f32, start: f32) {
+        let steps = (r * 2.0).ceil().max(4.0) as u32;
+        for i in 0..=steps {
+            let a = start + std::f32::consts::FRAC_PI_2 * i as f32 / steps as f32;
+            self.plot(cx + r * a.cos(), cy + r * a.sin());
+        }
+    }
+
+    /// Repeatedly cuts a square off the remaining rectangle, rotating clockwise,
+    /// and draws the square's dividing line and a quarter arc inside it
+    fn golden_spiral(&mut self) {
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut w, mut h) = (self.width as f32, self.height as f32);
+        for i in 0..10 {
+            let s = w.min(h);
+            if s < 2.0 {
+                break;
+            }
+            match i % 4 {
+                0 => {
+                    // Square on the left
+                    self.line(x + s, y, x + s, y + h);
+                    self.arc(x + s, y + s, s, std::f32::consts::PI);
+                    x += s;
+                    w -= s;
+                }
+                1 => {
+                    // Square on the top
+                    self.line(x, y + s, x + w, y + s);
+                    self.arc(x, y + s, s, -std::f32::consts::FRAC_PI_2);
+                    y += s;
+                    h -= s;
+                }
+                2 => {
+                    // Square on the right
+                    self.line(x + w - s, y, x + w - s, y + h);
+                    self.arc(x + w - s, y, s, 0.0);
+                    w -= s;
+                }
+                _ => {
+                    // Square on the bottom
+                    self.line(x, y + h - s, x + w, y + h - s);
+                    self.arc(x + w, y + h - s, s, std::f32::consts::FRAC_PI_2);
+                    h -= s;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! # High Dynamic Range Buffers
+//!
+//! A floating point RGBA image for sketches that accumulate light or need more
+//! tonal precision than 8 bits per channel. Values are linear light with straight
+//! alpha; anything above 1.0 is kept as is in OpenEXR files and clipped elsewhere.
+//!
+//! ```no_run
+//! use artimate::hdr::HdrImage;
+//!
+//! let mut image = HdrImage::new(640, 480);
+//! image.set_pixel(10, 10, [4.0, 2.0, 1.0, 1.0]);
+//! image.save_exr("glow.exr").unwrap();
+//! image.save_png16("glow.png").unwrap();
+//! ```
+
+use crate::buffer::Image;
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether a NaN channel value has already been reported by a debug build
+static NAN_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// An owned image with linear `f32` RGBA channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct HdrImage {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA channel data, `width * height * 4` values
+    pub data: Vec<f32>,
+}
+
+impl HdrImage {
+    /// Creates a fully transparent black image
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0.0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Converts an sRGB encoded RGBA8 image into linear floating point values
+    pub fn from_image(image: &Image) -> Self {
+        Self {
+            width: image.width,
+            height: image.height,
+            data: image
+                .data
+                .chunks_exact(4)
+                .flat_map(|p| {
+                    [
+                        srgb_to_linear(p[0] as f32 / 255.0),
+                        srgb_to_linear(p[1] as f32 / 255.0),
+                        srgb_to_linear(p[2] as f32 / 255.0),
+                        p[3] as f32 / 255.0,
+                    ]
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the RGBA value of the pixel at `(x, y)`
+    pub fn pixel(&self, x: u32, y: u32) -> [f32; 4] {
+        let i = ((y * self.width + x) * 4) as usize;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    /// Sets the RGBA value of the pixel at `(x, y)`
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [f32; 4]) {
+        let i = ((y * self.width + x) * 4) as usize;
+        self.data[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Returns an sRGB encoded RGBA8 buffer, suitable as the result of a draw function
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.encode(|v| (v * 255.0).round() as u8)
+    }
+
+    /// Saves the image as a 16-bit sRGB encoded RGBA PNG file
+    pub fn save_png16(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let data: Vec<u8> = self
+            .encode(|v| (v * 65535.0).round() as u16)
+            .into_iter()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Sixteen);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&data).map_err(io::Error::other)
+    }
+
+    /// Saves the image as an uncompressed 32-bit float OpenEXR file
+    ///
+    /// Channel values are written unclipped in linear light, as compositing
+    /// applications expect.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the file was written
+    /// * `Err(io::Error)` - If the image has no pixels, in which case nothing is
+    ///   written, or the file could not be written
+    ///
+    /// ```
+    /// use artimate::hdr::HdrImage;
+    ///
+    /// let err = HdrImage::new(0, 4).save_exr("empty.exr").unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    /// ```
+    pub fn save_exr(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot save an empty image as OpenEXR",
+            ));
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        let (w, h) = (self.width as i32, self.height as i32);
+
+        let mut header = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+        let mut channels = Vec::new();
+        for name in ["A", "B", "G", "R"] {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&2i32.to_le_bytes()); // FLOAT
+            channels.extend_from_slice(&[0; 4]); // pLinear and reserved
+            channels.extend_from_slice(&1i32.to_le_bytes());
+            channels.extend_from_slice(&1i32.to_le_bytes());
+        }
+        channels.push(0);
+        let window: Vec<u8> = [0, 0, w - 1, h - 1]
+            .iter()
+            .flat_map(|v: &i32| v.to_le_bytes())
+            .collect();
+        exr_attribute(&mut header, "channels", "chlist", &channels);
+        exr_attribute(&mut header, "compression", "compression", &[0]);
+        exr_attribute(&mut header, "dataWindow", "box2i", &window);
+        exr_attribute(&mut header, "displayWindow", "box2i", &window);
+        exr_attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+        exr_attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        exr_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        exr_attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1f32.to_le_bytes(),
+        );
+        header.push(0);
+        out.write_all(&header)?;
+
+        // One scanline per block: y, byte count, then each channel's row in name order
+        let line_bytes = self.width as usize * 4 * 4;
+        let block_size = (8 + line_bytes) as u64;
+        let first_block = (header.len() + self.height as usize * 8) as u64;
+        for y in 0..self.height as u64 {
+            out.write_all(&(first_block + y * block_size).to_le_bytes())?;
+        }
+        for (y, row) in self.data.chunks_exact(self.width as usize * 4).enumerate() {
+            out.write_all(&(y as i32).to_le_bytes())?;
+            out.write_all(&(line_bytes as i32).to_le_bytes())?;
+            for channel in [3, 2, 1, 0] {
+                for px in row.chunks_exact(4) {
+                    out.write_all(&px[channel].to_le_bytes())?;
+                }
+            }
+        }
+        out.flush()
+    }
+
+    /// Clips to [0, 1], sRGB encodes the color channels and quantizes with `quantize`
+    fn encode<T>(&self, quantize: impl Fn(f32) -> T) -> Vec<T> {
+        if cfg!(debug_assertions) && !NAN_REPORTED.load(Ordering::Relaxed) {
+            if let Some(i) = self.data.iter().position(|v| v.is_nan()) {
+                let (x, y) = (i as u32 / 4 % self.width, i as u32 / 4 / self.width);
+                eprintln!(
+                    "HdrImage contains NaN (first at pixel ({}, {})), which encodes as 0; \
+                     check for divisions by zero or sqrt of negative values",
+                    x, y
+                );
+                NAN_REPORTED.store(true, Ordering::Relaxed);
+            }
+        }
+        self.data
+            .chunks_exact(4)
+            .flat_map(|p| {
+                [
+                    quantize(linear_to_srgb(p[0].clamp(0.0, 1.0))),
+                    quantize(linear_to_srgb(p[1].clamp(0.0, 1.0))),
+                    quantize(linear_to_srgb(p[2].clamp(0.0, 1.0))),
+                    quantize(p[3].clamp(0.0, 1.0)),
+                ]
+            })
+            .collect()
+    }
+}
+
+fn exr_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
@@ -0,0 +1,74 @@
+//! # Headless Rendering
+//!
+//! Render a single frame of a sketch to an RGBA buffer or straight to a PNG file
+//! without creating a window or event loop, for tests, servers, scripts and
+//! thumbnail generation. Nothing here needs a display.
+
+use crate::app::{save_frame, App, AppMode, Config, DrawFn};
+use std::io;
+use std::path::Path;
+
+/// Renders frame `frame` of a sketch and returns the RGBA pixels with their width and height
+///
+/// The frame is rendered exactly as `render_single` would save it, without
+/// writing a file.
+///
+/// # Examples
+/// ```
+/// use artimate::app::{App, AppMode, Config};
+///
+/// fn draw(app: &App<AppMode, u8>, shade: &u8) -> Vec<u8> {
+///     vec![*shade; (app.config.width * app.config.height * 4) as usize]
+/// }
+///
+/// let (pixels, width, height) = artimate::render_rgba(Config::with_dims(32, 16), 200, draw, 0);
+/// assert_eq!((width, height), (32, 16));
+/// assert_eq!(&pixels[..4], &[200, 200, 200, 200]);
+/// ```
+pub fn render_rgba<M: Clone>(
+    config: Config,
+    model: M,
+    draw: DrawFn<AppMode, M>,
+    frame: u32,
+) -> (Vec<u8>, u32, u32) {
+    let mut app = App::app(model, config, |_, model| model, draw);
+    app.update = None;
+    app.render_headless(frame)
+}
+
+/// Renders frame `frame` of a sketch to a PNG file at `path`
+///
+/// `app.frame_count` and `app.time` are set as they would be on frame `frame` of a
+/// deterministic run (at `config.deterministic`, `config.fps` or 60 fps), and the
+/// model is passed to `draw` unchanged. If `config.export_size` is set the frame is
+/// rendered at that size. The file is always a PNG, whatever `config.export_format`
+/// is, and is written with the configured color profile.
+///
+/// # Examples
+/// ```rust,no_run
+/// use artimate::app::{App, AppMode, Config};
+///
+/// fn draw(app: &App<AppMode, u8>, shade: &u8) -> Vec<u8> {
+///     let t = (app.time * 100.0) as u8;
+///     vec![shade.wrapping_add(t); (app.config.width * app.config.height * 4) as usize]
+/// }
+///
+/// artimate::render_single(Config::with_dims(320, 240), 40, draw, 90, "frame_90.png").unwrap();
+/// ```
+pub fn render_single<M: Clone>(
+    config: Config,
+    model: M,
+    draw: DrawFn<AppMode, M>,
+    frame: u32,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let save_options = config.screenshot_options();
+    let (data, width, height) = render_rgba(config, model, draw, frame);
+    save_frame(
+        data,
+        path.as_ref().to_string_lossy().to_string(),
+        width,
+        height,
+        &save_options,
+    )
+}
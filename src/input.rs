@@ -0,0 +1,284 @@
+//! # Input Remapping
+//!
+//! Loads a `bindings.toml` file that maps action names to keys or mouse buttons,
+//! so controls can be remapped for other keyboard layouts or custom button boxes
+//! without recompiling. Each line assigns an input to a name:
+//!
+//! ```toml
+//! # bindings.toml
+//! [bindings]
+//! increase_density = "ArrowUp"
+//! decrease_density = "ArrowDown"
+//! reseed = "r"
+//! save = "Mouse Right"
+//! ```
+//!
+//! Keys are written as a single character, or a named key such as `Space`,
+//! `Enter`, `ArrowLeft` or `F5`. Mouse buttons are written as `Mouse Left`,
+//! `Mouse Right`, `Mouse Middle`, `Mouse Back`, `Mouse Forward` or `Mouse <n>`.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, Config};
+//! use winit::keyboard::{Key, NamedKey};
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     app.new_frame()
+//! }
+//!
+//! let mut app = App::sketch(Config::with_dims(800, 800), draw);
+//! app.load_bindings("bindings.toml").unwrap();
+//! let input = app.input("increase_density", Key::Named(NamedKey::ArrowUp));
+//! app.on_input_press(input, |app| app.set_dims(1200, 1200));
+//! ```
+
+use crate::overlay::{key_name, mouse_button_name};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// A physical input a handler can be bound to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Input {
+    /// A key, by its logical value
+    Key(Key),
+    /// A mouse button
+    Mouse(MouseButton),
+}
+
+impl From<Key> for Input {
+    fn from(key: Key) -> Self {
+        Input::Key(key)
+    }
+}
+
+impl From<MouseButton> for Input {
+    fn from(button: MouseButton) -> Self {
+        Input::Mouse(button)
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Key(key) => f.write_str(&key_name(key)),
+            Input::Mouse(button) => f.write_str(&mouse_button_name(button)),
+        }
+    }
+}
+
+/// Step size of an action, chosen by the modifiers held when it is triggered
+///
+/// Holding Shift selects a coarse step and holding Alt (Option on macOS) a fine
+/// step, so one pair of actions covers every adjustment size.
+///
+/// ```
+/// use artimate::input::Step;
+///
+/// assert_eq!(Step::Coarse.pick(0.1, 1.0, 10.0), 10.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Step {
+    /// Alt is held
+    Fine,
+    /// No step modifier is held
+    #[default]
+    Normal,
+    /// Shift is held
+    Coarse,
+}
+
+impl Step {
+    /// Returns the step selected by the held modifiers, Shift taking precedence over Alt
+    pub fn from_modifiers(modifiers: ModifiersState) -> Self {
+        if modifiers.shift_key() {
+            Step::Coarse
+        } else if modifiers.alt_key() {
+            Step::Fine
+        } else {
+            Step::Normal
+        }
+    }
+
+    /// Returns the value matching this step
+    pub fn pick<T>(self, fine: T, normal: T, coarse: T) -> T {
+        match self {
+            Step::Fine => fine,
+            Step::Normal => normal,
+            Step::Coarse => coarse,
+        }
+    }
+}
+
+/// Named keys that can be written in a bindings file
+const NAMED_KEYS: [NamedKey; 35] = [
+    NamedKey::Space,
+    NamedKey::Enter,
+    NamedKey::Tab,
+    NamedKey::Backspace,
+    NamedKey::Escape,
+    NamedKey::Delete,
+    NamedKey::Insert,
+    NamedKey::Home,
+    NamedKey::End,
+    NamedKey::PageUp,
+    NamedKey::PageDown,
+    NamedKey::ArrowUp,
+    NamedKey::ArrowDown,
+    NamedKey::ArrowLeft,
+    NamedKey::ArrowRight,
+    NamedKey::Shift,
+    NamedKey::Control,
+    NamedKey::Alt,
+    NamedKey::Super,
+    NamedKey::CapsLock,
+    NamedKey::PrintScreen,
+    NamedKey::Pause,
+    NamedKey::F1,
+    NamedKey::F2,
+    NamedKey::F3,
+    NamedKey::F4,
+    NamedKey::F5,
+    NamedKey::F6,
+    NamedKey::F7,
+    NamedKey::F8,
+    NamedKey::F9,
+    NamedKey::F10,
+    NamedKey::F11,
+    NamedKey::F12,
+    NamedKey::ContextMenu,
+];
+
+/// Parses the name of a key or mouse button as written in a bindings file
+///
+/// Names are case-insensitive. Single characters are parsed as the lowercase
+/// character key, matching the logical key reported without Shift.
+///
+/// ```
+/// use artimate::input::{parse_input, Input};
+/// use winit::event::MouseButton;
+/// use winit::keyboard::{Key, NamedKey};
+///
+/// assert_eq!(parse_input("R"), Some(Input::Key(Key::Character("r".into()))));
+/// assert_eq!(parse_input("arrowup"), Some(Input::Key(Key::Named(NamedKey::ArrowUp))));
+/// assert_eq!(parse_input("Mouse Left"), Some(Input::Mouse(MouseButton::Left)));
+/// assert_eq!(parse_input("Hyper"), None);
+///
+/// // Displayed inputs parse back to themselves
+/// for button in [MouseButton::Back, MouseButton::Other(3)] {
+///     let input = Input::Mouse(button);
+///     assert_eq!(parse_input(&input.to_string()), Some(input));
+/// }
+/// ```
+pub fn parse_input(name: &str) -> Option<Input> {
+    let name = name.trim();
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Input::Key(Key::Character(
+            c.to_lowercase().to_string().into(),
+        )));
+    }
+    if let Some(button) = name
+        .get(..6)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("mouse "))
+        .map(|_| name[6..].trim())
+    {
+        return match button.to_ascii_lowercase().as_str() {
+            "left" => Some(MouseButton::Left),
+            "right" => Some(MouseButton::Right),
+            "middle" => Some(MouseButton::Middle),
+            "back" => Some(MouseButton::Back),
+            "forward" => Some(MouseButton::Forward),
+            other => other.parse().ok().map(MouseButton::Other),
+        }
+        .map(Input::Mouse);
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|named| format!("{:?}", named).eq_ignore_ascii_case(name))
+        .map(|named| Input::Key(Key::Named(*named)))
+}
+
+/// Parses the contents of a bindings file into `(action name, input)` pairs
+///
+/// Blank lines and `#` comments are ignored, as is a `[bindings]` header; any other
+/// table header is an error. Names and inputs may be basic (`"..."`) or literal
+/// (`'...'`) strings.
+///
+/// ```
+/// use artimate::input::{parse_bindings, Input};
+/// use winit::event::MouseButton;
+///
+/// let bindings = parse_bindings("[bindings]\nsave = 'Mouse 3' # extra button").unwrap();
+/// assert_eq!(bindings, [("save".to_string(), Input::Mouse(MouseButton::Other(3)))]);
+/// assert!(parse_bindings("[keys]\nsave = \"s\"").is_err());
+/// ```
+///
+/// # Returns
+/// * `Ok(Vec<(String, Input)>)` - The bindings in file order
+/// * `Err(io::Error)` - If a line is not `name = "input"`, the input is unknown or
+///   the line is a table header other than `[bindings]`
+pub fn parse_bindings(text: &str) -> io::Result<Vec<(String, Input)>> {
+    let mut bindings = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        let invalid = |message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bindings line {}: {}", n + 1, message),
+            )
+        };
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            if header.trim() == "bindings" {
+                continue;
+            }
+            return Err(invalid(&format!("unexpected table [{}]", header.trim())));
+        }
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid("expected `name = \"input\"`"))?;
+        let name = unquote(name.trim());
+        let value = unquote(value.trim());
+        if name.is_empty() {
+            return Err(invalid("missing action name"));
+        }
+        let input =
+            parse_input(value).ok_or_else(|| invalid(&format!("unknown input {:?}", value)))?;
+        bindings.push((name.to_string(), input));
+    }
+    Ok(bindings)
+}
+
+/// Removes a `#` comment from a line, ignoring `#` inside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            ('#', None) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Removes the quotes around a basic or literal TOML string
+fn unquote(text: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| text.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(text)
+}
+
+/// Reads and parses a bindings file
+pub fn load_bindings(path: impl AsRef<Path>) -> io::Result<Vec<(String, Input)>> {
+    parse_bindings(&std::fs::read_to_string(path)?)
+}
@@ -0,0 +1,229 @@
+//! # Layers
+//!
+//! A stack of RGBA buffers the size of the frame, each with its own opacity,
+//! blend mode and visibility, composited bottom to top into the frame before it
+//! is shown. Layers keep their pixels between frames, so a static background is
+//! drawn once when it is created and only the animated layers above it are
+//! redrawn, instead of redrawing everything each frame.
+//!
+//! Keep the layers in the model, draw into the animated ones in the update
+//! function, and composite them in the draw function:
+//!
+//! ```
+//! use artimate::app::{App, AppMode, Config};
+//! use artimate::buffer::fill_rect;
+//! use artimate::layers::{BlendMode, Layers};
+//!
+//! #[derive(Clone)]
+//! struct Model {
+//!     layers: Layers,
+//! }
+//!
+//! fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
+//!     let x = app.frame_count % 180;
+//!     let sprite = model.layers.get_mut("sprite").unwrap();
+//!     sprite.clear([0, 0, 0, 0]);
+//!     fill_rect(&mut sprite.data, 200, (x, 80, x + 20, 120), [255, 200, 0, 255]);
+//!     model
+//! }
+//!
+//! fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+//!     let mut frame = app.new_frame();
+//!     model.layers.composite(&mut frame);
+//!     frame
+//! }
+//!
+//! let mut layers = Layers::new(200, 200);
+//! // Drawn once
+//! layers.add("background").clear([20, 30, 60, 255]);
+//! layers.add("sprite").blend = BlendMode::Add;
+//! let app = App::app(Model { layers }, Config::with_dims(200, 200), update, draw);
+//! ```
+
+use crate::buffer::{blend_rect, clear};
+
+/// How a layer's colors combine with the layers below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// The layer covers what is below by its alpha
+    #[default]
+    Normal,
+    /// Colors are added, brightening what is below, for glows and light
+    Add,
+    /// Colors are multiplied, darkening what is below, for shadows and tints
+    Multiply,
+    /// Inverted colors are multiplied, brightening what is below without clipping
+    Screen,
+}
+
+impl BlendMode {
+    /// Returns the blended color channel of `src` over `dst`, before alpha
+    fn mix(self, src: u32, dst: u32) -> u32 {
+        match self {
+            BlendMode::Normal => src,
+            BlendMode::Add => (src + dst).min(255),
+            BlendMode::Multiply => src * dst / 255,
+            BlendMode::Screen => 255 - (255 - src) * (255 - dst) / 255,
+        }
+    }
+}
+
+/// A named RGBA buffer in a [`Layers`] stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    /// Name the layer is looked up by
+    pub name: String,
+    /// RGBA pixel data with straight alpha, the size of the stack
+    pub data: Vec<u8>,
+    /// Opacity in [0, 1], multiplied with the alpha of every pixel
+    pub opacity: f32,
+    /// How the layer combines with the layers below it
+    pub blend: BlendMode,
+    /// Whether the layer is composited
+    pub visible: bool,
+}
+
+impl Layer {
+    /// Fills the layer with `color`, e.g. `[0, 0, 0, 0]` to make it transparent
+    pub fn clear(&mut self, color: [u8; 4]) {
+        clear(&mut self.data, color);
+    }
+
+    /// Blends the layer over `frame`, which must be the same size
+    fn composite(&self, frame: &mut [u8], width: u32) {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if !self.visible || opacity == 0.0 {
+            return;
+        }
+        if self.blend == BlendMode::Normal && opacity == 1.0 {
+            let height = (self.data.len() / (width as usize * 4).max(1)) as u32;
+            blend_rect(
+                frame,
+                width,
+                &self.data,
+                width,
+                (0, 0, width, height),
+                (0, 0),
+            );
+            return;
+        }
+        let opacity = (opacity * 255.0).round() as u32;
+        for (d, s) in frame.chunks_exact_mut(4).zip(self.data.chunks_exact(4)) {
+            let a = s[3] as u32 * opacity / 255;
+            if a == 0 {
+                continue;
+            }
+            for c in 0..3 {
+                let mixed = self.blend.mix(s[c] as u32, d[c] as u32);
+                d[c] = ((mixed * a + d[c] as u32 * (255 - a) + 127) / 255) as u8;
+            }
+            d[3] = ((255 * a + d[3] as u32 * (255 - a) + 127) / 255) as u8;
+        }
+    }
+}
+
+/// A stack of layers composited bottom to top
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layers {
+    width: u32,
+    height: u32,
+    layers: Vec<Layer>,
+}
+
+impl Layers {
+    /// Creates an empty stack for frames of the given size
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Returns the width and height of the layers
+    pub fn wh(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Adds a transparent layer on top of the stack and returns it
+    ///
+    /// The layer starts fully opaque, visible and blending normally.
+    pub fn add(&mut self, name: &str) -> &mut Layer {
+        self.layers.push(Layer {
+            name: name.to_string(),
+            data: vec![0; (self.width * self.height * 4) as usize],
+            opacity: 1.0,
+            blend: BlendMode::Normal,
+            visible: true,
+        });
+        self.layers.last_mut().unwrap()
+    }
+
+    /// Returns the layer called `name`, if there is one
+    pub fn get(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Returns the layer called `name` for drawing or changing its settings
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    /// Removes the layer called `name` and returns it
+    pub fn remove(&mut self, name: &str) -> Option<Layer> {
+        let i = self.layers.iter().position(|layer| layer.name == name)?;
+        Some(self.layers.remove(i))
+    }
+
+    /// Returns the layers from bottom to top
+    pub fn iter(&self) -> impl Iterator<Item = &Layer> {
+        self.layers.iter()
+    }
+
+    /// Returns the number of layers
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns true if there are no layers
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Resizes every layer, clearing its pixels
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        for layer in &mut self.layers {
+            layer.data = vec![0; (width * height * 4) as usize];
+        }
+    }
+
+    /// Blends the visible layers over `frame`, bottom to top
+    ///
+    /// The frame holds what is below the bottom layer, usually a cleared frame
+    /// from `App::new_frame`. Nothing is drawn if the frame is not the size of the
+    /// layers, as after a resize.
+    ///
+    /// ```
+    /// use artimate::layers::{BlendMode, Layers};
+    ///
+    /// let mut layers = Layers::new(1, 1);
+    /// layers.add("base").clear([100, 100, 100, 255]);
+    /// let glow = layers.add("glow");
+    /// glow.clear([100, 50, 0, 255]);
+    /// glow.blend = BlendMode::Add;
+    /// glow.opacity = 0.5;
+    /// let mut frame = vec![0, 0, 0, 255];
+    /// layers.composite(&mut frame);
+    /// assert_eq!(frame, [150, 125, 100, 255]);
+    /// ```
+    pub fn composite(&self, frame: &mut [u8]) {
+        if frame.len() != (self.width * self.height * 4) as usize {
+            return;
+        }
+        for layer in &self.layers {
+            layer.composite(frame, self.width);
+        }
+    }
+}
@@ -163,8 +163,49 @@
 //! - GPU-accelerated rendering via the `pixels` crate
 //! - Minimal overhead pixel buffer management
 //! - Supports high frame rates for smooth animations
+//! - Per-frame arena for temporary geometry via `App::alloc_slice`
 //!
 //! When the application exits, performance statistics are printed including
 //! average FPS, total frame count, and elapsed time.
 
 pub mod app;
+pub mod arena;
+pub mod batch;
+pub mod brush;
+pub mod buffer;
+pub mod capture;
+pub mod color;
+pub mod colormap;
+pub mod farm;
+pub mod geom;
+pub mod gradient;
+pub mod guides;
+pub mod hdr;
+pub mod headless;
+pub mod input;
+pub mod layers;
+pub mod noise;
+pub mod overlay;
+pub mod pacing;
+pub mod package;
+pub mod palette;
+pub mod power;
+pub mod preset;
+pub mod random;
+pub mod raster;
+pub mod scaffold;
+pub mod scaling;
+pub mod session;
+pub mod starters;
+pub mod testing;
+pub mod text;
+pub mod tiles;
+#[cfg(feature = "s3")]
+pub mod upload;
+pub mod viz;
+#[cfg(feature = "wassily")]
+pub mod wassily;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+pub use headless::{render_rgba, render_single};
@@ -0,0 +1,514 @@
+//! # Noise
+//!
+//! First-party coherent noise for textures, flow fields and terrain without
+//! pulling in the `noise` crate: simplex noise in 2, 3 and 4 dimensions, worley
+//! (cellular) noise, curl noise derived from any noise, and batch sampling over
+//! grids for per-pixel use, with rows sampled in parallel when the `parallel`
+//! feature is enabled.
+//!
+//! ```
+//! use artimate::noise::{curl2, grid2, Noise, Simplex, Worley};
+//!
+//! let simplex = Simplex::new(7);
+//! let height = simplex.get2(0.3, 1.7);
+//! assert!((-1.0..=1.0).contains(&height));
+//!
+//! let cells = Worley::new(7).get3(0.5, 0.5, 2.0);
+//! let [vx, vy] = curl2(&simplex, 0.3, 1.7);
+//!
+//! // One value per pixel of a 320 x 240 frame, 0.01 noise units per pixel
+//! let field = grid2(&simplex, 320, 240, (0.0, 0.0), 0.01);
+//! assert_eq!(field.values.len(), 320 * 240);
+//! # let _ = (cells, vx, vy);
+//! ```
+//!
+//! Combinators assemble richer textures from these building blocks, and are
+//! themselves noise, so they nest:
+//!
+//! ```
+//! use artimate::noise::{grid2, Noise, Simplex};
+//!
+//! let clouds = Simplex::new(1).fbm(6, 2.0, 0.5);
+//! let mountains = Simplex::new(2).ridged(5, 2.1, 0.5);
+//! let marble = Simplex::new(3)
+//!     .fbm(4, 2.0, 0.5)
+//!     .warp(Simplex::new(4).fbm(3, 2.0, 0.5), 1.5);
+//!
+//! let field = grid2(&marble, 320, 240, (0.0, 0.0), 0.01);
+//! # let _ = (clouds.get2(0.5, 0.5), mountains.get2(0.5, 0.5), field);
+//! ```
+
+use crate::random::Rng;
+use crate::viz::Field;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A coherent noise function of 2 and 3 dimensional space
+pub trait Noise {
+    /// Returns the noise value at `(x, y)`
+    fn get2(&self, x: f32, y: f32) -> f32;
+
+    /// Returns the noise value at `(x, y, z)`
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32;
+
+    /// Layers `octaves` copies of this noise as fractal Brownian motion
+    ///
+    /// Each octave multiplies the frequency by `lacunarity` and the amplitude by
+    /// `gain`; the sum is normalized so the range of the noise is kept.
+    fn fbm(self, octaves: u32, lacunarity: f32, gain: f32) -> Fbm<Self>
+    where
+        Self: Sized,
+    {
+        Fbm {
+            noise: self,
+            octaves,
+            lacunarity,
+            gain,
+        }
+    }
+
+    /// Layers octaves like `fbm`, folding each into sharp ridges, with values in [0, 1]
+    ///
+    /// Suited to mountain ranges, veins and lightning. Expects noise in [-1, 1].
+    fn ridged(self, octaves: u32, lacunarity: f32, gain: f32) -> Ridged<Self>
+    where
+        Self: Sized,
+    {
+        Ridged(self.fbm(octaves, lacunarity, gain))
+    }
+
+    /// Displaces the coordinates of this noise by `offset` noise scaled by `strength`
+    ///
+    /// Domain warping: each axis is displaced by a decorrelated sample of `offset`,
+    /// turning smooth noise into swirling, marbled patterns. Warping a warp nests.
+    fn warp<W: Noise>(self, offset: W, strength: f32) -> Warp<Self, W>
+    where
+        Self: Sized,
+    {
+        Warp {
+            noise: self,
+            offset,
+            strength,
+        }
+    }
+}
+
+impl<N: Noise + ?Sized> Noise for &N {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        (**self).get2(x, y)
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        (**self).get3(x, y, z)
+    }
+}
+
+/// Simplex noise in 2, 3 and 4 dimensions, with values in [-1, 1]
+///
+/// Ken Perlin's simplex noise following Stefan Gustavson's reference
+/// implementation, with the gradient permutation shuffled by the seed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplex {
+    perm: Vec<u8>,
+}
+
+const GRAD3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// Returns the 4D gradient with index `i` in [0, 32): a vertex of the tesseract with one coordinate zeroed
+fn grad4(i: usize) -> [f32; 4] {
+    let sign = |bit: usize| if i & bit == 0 { 1.0 } else { -1.0 };
+    let (a, b, c) = (sign(4), sign(2), sign(1));
+    match i / 8 {
+        0 => [0.0, a, b, c],
+        1 => [a, 0.0, b, c],
+        2 => [a, b, 0.0, c],
+        _ => [a, b, c, 0.0],
+    }
+}
+
+impl Simplex {
+    /// Creates simplex noise whose gradients are shuffled by `seed`
+    pub fn new(seed: u64) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        Rng::new(seed).shuffle(&mut table);
+        let perm = table.iter().chain(table.iter()).copied().collect();
+        Self { perm }
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        self.perm[i & 511] as usize
+    }
+
+    /// Returns the 4D noise value at `(x, y, z, w)`, useful for looping 3D animations
+    pub fn get4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let f4 = (5.0f32.sqrt() - 1.0) / 4.0;
+        let g4 = (5.0 - 5.0f32.sqrt()) / 20.0;
+        let s = (x + y + z + w) * f4;
+        let cell = [x, y, z, w].map(|v| (v + s).floor());
+        let t = cell.iter().sum::<f32>() * g4;
+        let p0 = [
+            x - cell[0] + t,
+            y - cell[1] + t,
+            z - cell[2] + t,
+            w - cell[3] + t,
+        ];
+
+        // Rank each coordinate to find which simplex the point is in
+        let mut rank = [0; 4];
+        for a in 0..4 {
+            for b in a + 1..4 {
+                if p0[a] > p0[b] {
+                    rank[a] += 1;
+                } else {
+                    rank[b] += 1;
+                }
+            }
+        }
+        let [i, j, k, l] = cell.map(|c| (c as i32 & 255) as usize);
+        let mut total = 0.0;
+        for corner in 0..5 {
+            let offset: [usize; 4] = rank.map(|r| usize::from(r + corner >= 4));
+            let p = [0, 1, 2, 3].map(|d| p0[d] - offset[d] as f32 + corner as f32 * g4);
+            let t = 0.6 - p.iter().map(|v| v * v).sum::<f32>();
+            if t > 0.0 {
+                let h = self.hash(
+                    i + offset[0]
+                        + self.hash(
+                            j + offset[1] + self.hash(k + offset[2] + self.hash(l + offset[3])),
+                        ),
+                );
+                let g = grad4(h % 32);
+                let dot: f32 = g.iter().zip(p).map(|(g, p)| g * p).sum();
+                total += t * t * t * t * dot;
+            }
+        }
+        27.0 * total
+    }
+}
+
+impl Noise for Simplex {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        let f2 = 0.5 * (3.0f32.sqrt() - 1.0);
+        let g2 = (3.0 - 3.0f32.sqrt()) / 6.0;
+        let s = (x + y) * f2;
+        let (ci, cj) = ((x + s).floor(), (y + s).floor());
+        let t = (ci + cj) * g2;
+        let (x0, y0) = (x - ci + t, y - cj + t);
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let corners = [
+            (x0, y0, 0, 0),
+            (x0 - i1 as f32 + g2, y0 - j1 as f32 + g2, i1, j1),
+            (x0 - 1.0 + 2.0 * g2, y0 - 1.0 + 2.0 * g2, 1, 1),
+        ];
+        let (i, j) = ((ci as i32 & 255) as usize, (cj as i32 & 255) as usize);
+        let mut total = 0.0;
+        for (px, py, di, dj) in corners {
+            let t = 0.5 - px * px - py * py;
+            if t > 0.0 {
+                let g = GRAD3[self.hash(i + di + self.hash(j + dj)) % 12];
+                total += t * t * t * t * (g[0] * px + g[1] * py);
+            }
+        }
+        70.0 * total
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (f3, g3) = (1.0 / 3.0, 1.0 / 6.0);
+        let s = (x + y + z) * f3;
+        let cell = [x, y, z].map(|v| (v + s).floor());
+        let t = cell.iter().sum::<f32>() * g3;
+        let p0 = [x - cell[0] + t, y - cell[1] + t, z - cell[2] + t];
+        let (o1, o2) = match (p0[0] >= p0[1], p0[1] >= p0[2], p0[0] >= p0[2]) {
+            (true, true, _) => ([1, 0, 0], [1, 1, 0]),
+            (true, false, true) => ([1, 0, 0], [1, 0, 1]),
+            (true, false, false) => ([0, 0, 1], [1, 0, 1]),
+            (false, false, _) => ([0, 0, 1], [0, 1, 1]),
+            (false, true, false) => ([0, 1, 0], [0, 1, 1]),
+            (false, true, true) => ([0, 1, 0], [1, 1, 0]),
+        };
+        let offsets = [[0, 0, 0], o1, o2, [1, 1, 1]];
+        let [i, j, k] = cell.map(|c| (c as i32 & 255) as usize);
+        let mut total = 0.0;
+        for (corner, offset) in offsets.iter().enumerate() {
+            let p = [0, 1, 2].map(|d| p0[d] - offset[d] as f32 + corner as f32 * g3);
+            let t = 0.6 - p.iter().map(|v| v * v).sum::<f32>();
+            if t > 0.0 {
+                let h =
+                    self.hash(i + offset[0] + self.hash(j + offset[1] + self.hash(k + offset[2])));
+                let g = GRAD3[h % 12];
+                total += t * t * t * t * (g[0] * p[0] + g[1] * p[1] + g[2] * p[2]);
+            }
+        }
+        32.0 * total
+    }
+}
+
+/// Worley (cellular) noise: the distance to the nearest of randomly scattered points
+///
+/// One feature point is placed in every unit cell, so values are roughly in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Worley {
+    seed: u64,
+}
+
+impl Worley {
+    /// Creates worley noise whose feature points are placed by `seed`
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns a generator seeded for the cell at integer coordinates `cell`
+    fn cell_rng(&self, cell: [i32; 3]) -> Rng {
+        let h = cell
+            .iter()
+            .fold(self.seed, |h, &c| Rng::new(h ^ c as u32 as u64).next_u64());
+        Rng::new(h)
+    }
+}
+
+impl Noise for Worley {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        let (cx, cy) = (x.floor() as i32, y.floor() as i32);
+        let mut nearest = f32::MAX;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let mut rng = self.cell_rng([cx + dx, cy + dy, 0]);
+                let fx = (cx + dx) as f32 + rng.next_f32() - x;
+                let fy = (cy + dy) as f32 + rng.next_f32() - y;
+                nearest = nearest.min(fx * fx + fy * fy);
+            }
+        }
+        nearest.sqrt()
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let c = [x, y, z].map(|v| v.floor() as i32);
+        let mut nearest = f32::MAX;
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let cell = [c[0] + dx, c[1] + dy, c[2] + dz];
+                    let mut rng = self.cell_rng(cell);
+                    let distance: f32 = [x, y, z]
+                        .iter()
+                        .zip(cell)
+                        .map(|(p, c)| (c as f32 + rng.next_f32() - p).powi(2))
+                        .sum();
+                    nearest = nearest.min(distance);
+                }
+            }
+        }
+        nearest.sqrt()
+    }
+}
+
+/// Fractal Brownian motion over another noise, created by `Noise::fbm`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fbm<N> {
+    noise: N,
+    octaves: u32,
+    lacunarity: f32,
+    gain: f32,
+}
+
+/// Offset added to the coordinates of each octave so their features do not line up
+const OCTAVE_OFFSET: f32 = 17.13;
+
+impl<N: Noise> Fbm<N> {
+    /// Sums `f(octave value)` over the octaves, normalized by the total amplitude
+    fn sum(&self, sample: impl Fn(f32, f32) -> f32, f: impl Fn(f32) -> f32) -> f32 {
+        let (mut total, mut norm) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        for octave in 0..self.octaves {
+            total += amplitude * f(sample(frequency, octave as f32 * OCTAVE_OFFSET));
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        if norm > 0.0 {
+            total / norm
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        self.sum(
+            |freq, offset| self.noise.get2(x * freq + offset, y * freq - offset),
+            |v| v,
+        )
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sum(
+            |freq, offset| {
+                self.noise
+                    .get3(x * freq + offset, y * freq - offset, z * freq + offset)
+            },
+            |v| v,
+        )
+    }
+}
+
+/// Ridged multifractal noise over another noise, created by `Noise::ridged`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ridged<N>(Fbm<N>);
+
+/// Folds a noise value in [-1, 1] into a ridge peaking at 1 where it crosses zero
+fn ridge(v: f32) -> f32 {
+    let r = 1.0 - v.abs();
+    r * r
+}
+
+impl<N: Noise> Noise for Ridged<N> {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        let fbm = &self.0;
+        fbm.sum(
+            |freq, offset| fbm.noise.get2(x * freq + offset, y * freq - offset),
+            ridge,
+        )
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let fbm = &self.0;
+        fbm.sum(
+            |freq, offset| {
+                fbm.noise
+                    .get3(x * freq + offset, y * freq - offset, z * freq + offset)
+            },
+            ridge,
+        )
+    }
+}
+
+/// Domain-warped noise, created by `Noise::warp`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warp<N, W> {
+    noise: N,
+    offset: W,
+    strength: f32,
+}
+
+/// Offsets that decorrelate the per-axis samples of the warp noise
+const WARP_AXES: [(f32, f32, f32); 3] = [(0.0, 0.0, 0.0), (5.2, 1.3, 2.8), (1.7, 9.2, 4.6)];
+
+impl<N: Noise, W: Noise> Noise for Warp<N, W> {
+    fn get2(&self, x: f32, y: f32) -> f32 {
+        let [dx, dy] = [WARP_AXES[0], WARP_AXES[1]]
+            .map(|(ox, oy, _)| self.strength * self.offset.get2(x + ox, y + oy));
+        self.noise.get2(x + dx, y + dy)
+    }
+
+    fn get3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let [dx, dy, dz] =
+            WARP_AXES.map(|(ox, oy, oz)| self.strength * self.offset.get3(x + ox, y + oy, z + oz));
+        self.noise.get3(x + dx, y + dy, z + dz)
+    }
+}
+
+/// Step used for the finite differences of curl noise
+const CURL_EPSILON: f32 = 1e-3;
+
+/// Returns the divergence-free 2D curl of `noise` at `(x, y)`
+///
+/// The noise is treated as a stream function, so particles following the vectors
+/// swirl without bunching up or thinning out.
+pub fn curl2(noise: &impl Noise, x: f32, y: f32) -> [f32; 2] {
+    let e = CURL_EPSILON;
+    let dx = (noise.get2(x + e, y) - noise.get2(x - e, y)) / (2.0 * e);
+    let dy = (noise.get2(x, y + e) - noise.get2(x, y - e)) / (2.0 * e);
+    [dy, -dx]
+}
+
+/// Returns the divergence-free 3D curl of `noise` at `(x, y, z)`
+///
+/// The vector potential uses three offset copies of the noise as its components.
+pub fn curl3(noise: &impl Noise, x: f32, y: f32, z: f32) -> [f32; 3] {
+    let e = CURL_EPSILON;
+    let potential = |c: usize, x: f32, y: f32, z: f32| {
+        let offset = c as f32 * 31.416;
+        noise.get3(x + offset, y - offset, z + offset)
+    };
+    let d = |c: usize, axis: usize| {
+        let mut plus = [x, y, z];
+        let mut minus = [x, y, z];
+        plus[axis] += e;
+        minus[axis] -= e;
+        (potential(c, plus[0], plus[1], plus[2]) - potential(c, minus[0], minus[1], minus[2]))
+            / (2.0 * e)
+    };
+    [d(2, 1) - d(1, 2), d(0, 2) - d(2, 0), d(1, 0) - d(0, 1)]
+}
+
+/// Samples 2D noise at every cell of a `width` x `height` grid
+///
+/// Cell `(x, y)` holds `noise.get2(origin.0 + x * scale, origin.1 + y * scale)`.
+/// With the `parallel` feature, rows are sampled in parallel on the rayon thread pool.
+pub fn grid2(
+    noise: &(impl Noise + Sync),
+    width: u32,
+    height: u32,
+    origin: (f32, f32),
+    scale: f32,
+) -> Field {
+    sample_rows(width, height, |x, y| {
+        noise.get2(origin.0 + x as f32 * scale, origin.1 + y as f32 * scale)
+    })
+}
+
+/// Samples a slice of 3D noise at depth `z` over a `width` x `height` grid
+///
+/// Like `grid2`, but evaluating `noise.get3` at depth `z`, e.g. time for animation.
+pub fn grid3(
+    noise: &(impl Noise + Sync),
+    width: u32,
+    height: u32,
+    origin: (f32, f32),
+    scale: f32,
+    z: f32,
+) -> Field {
+    sample_rows(width, height, |x, y| {
+        noise.get3(origin.0 + x as f32 * scale, origin.1 + y as f32 * scale, z)
+    })
+}
+
+/// Fills a field by evaluating `f(x, y)` row by row, in parallel with the `parallel` feature
+fn sample_rows(width: u32, height: u32, f: impl Fn(u32, u32) -> f32 + Sync) -> Field {
+    let mut field = Field::new(width, height);
+    if width == 0 || height == 0 {
+        return field;
+    }
+    let fill = |(y, row): (usize, &mut [f32])| {
+        for (x, value) in row.iter_mut().enumerate() {
+            *value = f(x as u32, y as u32);
+        }
+    };
+    #[cfg(feature = "parallel")]
+    field
+        .values
+        .par_chunks_mut(width as usize)
+        .enumerate()
+        .for_each(fill);
+    #[cfg(not(feature = "parallel"))]
+    field
+        .values
+        .chunks_mut(width as usize)
+        .enumerate()
+        .for_each(fill);
+    field
+}
@@ -0,0 +1,453 @@
+//! # Overlays
+//!
+//! In-window overlays drawn over the preview, such as the hotkey help toggled
+//! with `?`. Overlays are never part of saved or exported frames.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, Config};
+//! use artimate::overlay::OverlayTheme;
+//! use winit::keyboard::Key;
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     app.new_frame()
+//! }
+//!
+//! let config = Config::with_dims(800, 800).overlay_theme(OverlayTheme::HighContrast);
+//! let mut app = App::sketch(config, draw);
+//! app.on_key_press(Key::Character("r".into()), |app| app.set_dims(400, 400));
+//! app.describe_key(Key::Character("r".into()), "Render at 400 x 400");
+//! app.register_action("toggle_pause", Key::Character("p".into()));
+//! for binding in app.bindings() {
+//!     println!("{}", binding);
+//! }
+//! ```
+
+use crate::buffer::Image;
+use crate::text::{draw_text, fill_rect, text_height, text_width};
+use std::fmt;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, ModifiersState};
+
+/// Colors and sizes used to draw overlays and guides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlayTheme {
+    /// Translucent panels and guides that keep the artwork visible
+    #[default]
+    Default,
+    /// Opaque panels, saturated colors and larger text for legibility
+    HighContrast,
+}
+
+impl OverlayTheme {
+    /// RGBA color of composition guide lines
+    pub fn guide_color(&self) -> [u8; 4] {
+        match self {
+            OverlayTheme::Default => [0, 255, 255, 160],
+            OverlayTheme::HighContrast => [255, 255, 0, 255],
+        }
+    }
+
+    /// RGBA color of the panel behind overlay text
+    pub fn panel_color(&self) -> [u8; 4] {
+        match self {
+            OverlayTheme::Default => [0, 0, 0, 180],
+            OverlayTheme::HighContrast => [0, 0, 0, 255],
+        }
+    }
+
+    /// RGBA color of overlay text
+    pub fn text_color(&self) -> [u8; 4] {
+        match self {
+            OverlayTheme::Default => [230, 230, 230, 255],
+            OverlayTheme::HighContrast => [255, 255, 0, 255],
+        }
+    }
+
+    /// Size of a font pixel in screen pixels
+    pub fn text_scale(&self) -> u32 {
+        match self {
+            OverlayTheme::Default => 1,
+            OverlayTheme::HighContrast => 2,
+        }
+    }
+
+    /// RGBA color of the recording indicator
+    pub fn record_color(&self) -> [u8; 4] {
+        match self {
+            OverlayTheme::Default => [230, 40, 40, 255],
+            OverlayTheme::HighContrast => [255, 0, 0, 255],
+        }
+    }
+}
+
+/// The kind of input event a binding responds to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// Fires once when the key is pressed
+    Press,
+    /// Fires while the key is held down
+    Held,
+    /// Fires once when the key or mouse button is released
+    Release,
+    /// Fires when the mouse button is pressed
+    Click,
+    /// Fires when the mouse button is clicked twice in quick succession
+    DoubleClick,
+    /// Fires while the cursor moves with the mouse button held
+    Drag,
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Trigger::Press => "press",
+            Trigger::Held => "hold",
+            Trigger::Release => "release",
+            Trigger::Click => "click",
+            Trigger::DoubleClick => "double-click",
+            Trigger::Drag => "drag",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A registered hotkey or mouse binding, as listed by `App::bindings`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// Human readable name of the key, chord or mouse button, e.g. `Cmd+S` or `ArrowUp`
+    pub input: String,
+    /// The event the binding responds to
+    pub trigger: Trigger,
+    /// Name of the action the input is bound to, for bindings made with `App::register_action`
+    pub action: Option<String>,
+    /// Optional description set with `App::describe_key`
+    pub description: Option<String>,
+}
+
+impl Binding {
+    /// Returns the description, or the action name with spaces for underscores
+    pub fn label(&self) -> Option<String> {
+        match (&self.description, &self.action) {
+            (Some(description), _) => Some(description.clone()),
+            (None, Some(action)) => Some(action.replace('_', " ")),
+            (None, None) => None,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<10} {:<7}", self.input, self.trigger.to_string())?;
+        match self.label() {
+            Some(label) => write!(f, " {}", label),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Formats bindings as a plain text cheat sheet headed by `title`, one binding per line
+///
+/// The lines match the help overlay, so printing the sheet at startup shows the
+/// same controls in the terminal.
+pub fn cheat_sheet(title: &str, bindings: &[Binding]) -> String {
+    let mut sheet = format!("{}\n{}\n", title, "-".repeat(title.chars().count()));
+    for binding in bindings {
+        sheet.push_str(binding.to_string().trim_end());
+        sheet.push('\n');
+    }
+    sheet
+}
+
+/// Formats bindings as a Markdown table, for READMEs and example documentation
+///
+/// ```
+/// use artimate::overlay::{bindings_markdown, Binding, Trigger};
+///
+/// let bindings = [Binding {
+///     input: "Space".to_string(),
+///     trigger: Trigger::Press,
+///     action: Some("toggle_pause".to_string()),
+///     description: None,
+/// }];
+/// assert_eq!(
+///     bindings_markdown(&bindings),
+///     "| Input | Trigger | Description |\n|---|---|---|\n| `Space` | press | toggle pause |\n"
+/// );
+/// ```
+pub fn bindings_markdown(bindings: &[Binding]) -> String {
+    let mut table = String::from("| Input | Trigger | Description |\n|---|---|---|\n");
+    for binding in bindings {
+        let label = binding.label().unwrap_or_default();
+        table.push_str(&format!(
+            "| `{}` | {} | {} |\n",
+            binding.input.replace('|', "\\|"),
+            binding.trigger,
+            label.replace('|', "\\|")
+        ));
+    }
+    table
+}
+
+/// Returns a human readable name for a key
+pub fn key_name(key: &Key) -> String {
+    match key {
+        Key::Character(c) if c.as_str() == " " => "Space".to_string(),
+        Key::Character(c) => c.to_uppercase(),
+        Key::Named(named) => format!("{:?}", named),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Returns a human readable name for a key pressed with modifiers, e.g. `Shift+ArrowUp`
+///
+/// ```
+/// use artimate::overlay::chord_name;
+/// use winit::keyboard::{Key, ModifiersState, NamedKey};
+///
+/// let key = Key::Named(NamedKey::ArrowUp);
+/// let modifiers = ModifiersState::SHIFT | ModifiersState::CONTROL;
+/// assert_eq!(chord_name(&key, modifiers), "Ctrl+Shift+ArrowUp");
+/// ```
+pub fn chord_name(key: &Key, modifiers: ModifiersState) -> String {
+    let mut name = String::new();
+    let prefixes = [
+        (ModifiersState::CONTROL, "Ctrl+"),
+        (ModifiersState::ALT, "Alt+"),
+        (ModifiersState::SHIFT, "Shift+"),
+        (ModifiersState::SUPER, "Cmd+"),
+    ];
+    for (modifier, prefix) in prefixes {
+        if modifiers.contains(modifier) {
+            name.push_str(prefix);
+        }
+    }
+    name + &key_name(key)
+}
+
+/// Returns a human readable name for a mouse button, as read by `input::parse_input`
+///
+/// ```
+/// use artimate::overlay::mouse_button_name;
+/// use winit::event::MouseButton;
+///
+/// assert_eq!(mouse_button_name(&MouseButton::Left), "Mouse Left");
+/// assert_eq!(mouse_button_name(&MouseButton::Other(3)), "Mouse 3");
+/// ```
+pub fn mouse_button_name(button: &MouseButton) -> String {
+    match button {
+        MouseButton::Other(n) => format!("Mouse {}", n),
+        button => format!("Mouse {:?}", button),
+    }
+}
+
+/// Height in pixels of the history scrub bar along the bottom of the window
+pub(crate) const SCRUB_BAR_HEIGHT: u32 = 24;
+
+/// Draws the history scrub bar along the bottom of an RGBA frame
+///
+/// `position` is the index of the shown frame among `len` retained frames and
+/// `frame` its frame number.
+pub(crate) fn draw_scrub_bar(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    (position, len, frame_number): (usize, usize, u32),
+    theme: OverlayTheme,
+) {
+    let top = height.saturating_sub(SCRUB_BAR_HEIGHT);
+    fill_rect(
+        frame,
+        width,
+        height,
+        (0, top, width, height),
+        theme.panel_color(),
+    );
+    let filled = if len > 1 {
+        (position as u64 * width as u64 / (len - 1) as u64) as u32
+    } else {
+        width
+    };
+    let color = theme.guide_color();
+    fill_rect(frame, width, height, (0, top, filled, top + 3), color);
+    let marker = filled.saturating_sub(2).min(width.saturating_sub(4));
+    fill_rect(
+        frame,
+        width,
+        height,
+        (marker, top, marker + 4, height),
+        color,
+    );
+
+    let scale = theme.text_scale().min(2);
+    let label = format!(
+        "paused  frame {}  ({}/{})  left/right or drag to scrub",
+        frame_number,
+        position + 1,
+        len
+    );
+    let y = top + 3 + (SCRUB_BAR_HEIGHT - 3).saturating_sub(text_height(scale)) / 2;
+    draw_text(
+        frame,
+        width,
+        height,
+        6,
+        y as i32,
+        &label,
+        theme.text_color(),
+        scale,
+    );
+}
+
+/// Draws debug views as labeled insets stacked down the right edge of an RGBA frame
+///
+/// Each inset is scaled to fit a quarter of the shorter frame edge, keeping its
+/// aspect ratio. Insets that do not fit below the previous ones are skipped.
+pub(crate) fn draw_insets(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    insets: &[(String, Image)],
+    theme: OverlayTheme,
+) {
+    let scale = theme.text_scale();
+    let margin = 8;
+    let max_edge = (width.min(height) / 4).max(1);
+    let label_height = text_height(scale) + 4;
+    let mut top = margin;
+    for (name, image) in insets {
+        if image.width == 0 || image.height == 0 {
+            continue;
+        }
+        let fit = max_edge as f32 / image.width.max(image.height) as f32;
+        let w = ((image.width as f32 * fit).round() as u32).max(1);
+        let h = ((image.height as f32 * fit).round() as u32).max(1);
+        if top + label_height + h + margin > height || w + 2 * margin > width {
+            break;
+        }
+        let left = width - w - margin;
+        fill_rect(
+            frame,
+            width,
+            height,
+            (left - 2, top - 2, left + w + 2, top + label_height + h + 2),
+            theme.panel_color(),
+        );
+        draw_text(
+            frame,
+            width,
+            height,
+            left as i32,
+            top as i32,
+            name,
+            theme.text_color(),
+            scale,
+        );
+        let y0 = top + label_height;
+        for y in 0..h {
+            let sy = y * image.height / h;
+            for x in 0..w {
+                let sx = x * image.width / w;
+                let i = (((y0 + y) * width + left + x) * 4) as usize;
+                let [r, g, b, a] = image.pixel(sx, sy);
+                let alpha = a as u32;
+                for (dst, src) in frame[i..i + 3].iter_mut().zip([r, g, b]) {
+                    *dst = ((src as u32 * alpha + *dst as u32 * (255 - alpha)) / 255) as u8;
+                }
+            }
+        }
+        top = y0 + h + margin;
+    }
+}
+
+/// Draws the recording indicator, a red dot followed by `label`, in the top-right corner of an RGBA frame
+pub(crate) fn draw_recording(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    label: &str,
+    theme: OverlayTheme,
+) {
+    let scale = theme.text_scale();
+    let padding = 6 * scale;
+    let dot = text_height(scale);
+    let panel_w = padding * 3 + dot + text_width(label, scale);
+    let panel_h = padding * 2 + dot;
+    let left = width.saturating_sub(panel_w + padding);
+    let top = padding;
+    fill_rect(
+        frame,
+        width,
+        height,
+        (left, top, left + panel_w, top + panel_h),
+        theme.panel_color(),
+    );
+    let (x, y) = (left + padding, top + padding);
+    fill_rect(
+        frame,
+        width,
+        height,
+        (x, y, x + dot, y + dot),
+        theme.record_color(),
+    );
+    draw_text(
+        frame,
+        width,
+        height,
+        (x + dot + padding) as i32,
+        y as i32,
+        label,
+        theme.text_color(),
+        scale,
+    );
+}
+
+/// Draws the hotkey help panel listing `bindings` in the top-left corner of an RGBA frame
+pub(crate) fn draw_help(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    bindings: &[Binding],
+    theme: OverlayTheme,
+) {
+    let scale = theme.text_scale();
+    let line_height = text_height(scale) + 4 * scale;
+    let padding = 6 * scale;
+    let mut lines = vec!["Hotkeys (? to close)".to_string()];
+    lines.extend(
+        bindings
+            .iter()
+            .map(|binding| binding.to_string().trim_end().to_string()),
+    );
+
+    let panel_width = lines
+        .iter()
+        .map(|line| text_width(line, scale))
+        .max()
+        .unwrap_or(0)
+        + 2 * padding;
+    let panel_height = lines.len() as u32 * line_height + 2 * padding - 4 * scale;
+    fill_rect(
+        frame,
+        width,
+        height,
+        (
+            padding,
+            padding,
+            padding + panel_width,
+            padding + panel_height,
+        ),
+        theme.panel_color(),
+    );
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            frame,
+            width,
+            height,
+            (2 * padding) as i32,
+            (2 * padding + i as u32 * line_height) as i32,
+            line,
+            theme.text_color(),
+            scale,
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! # Frame Pacing
+//!
+//! Statistics of the time between consecutive frames, which is what a viewer
+//! perceives as smoothness. The average frame rate hides stutters; percentiles,
+//! the number of frames over budget and the indices of the worst frames show when
+//! they happened, so they can be matched with what the sketch was doing.
+//!
+//! The app prints a report with its other statistics at exit, and
+//! `App::pacing_report` returns one at any time. It keeps the intervals in a
+//! [`PacingStats`], which uses the same memory however long the app runs.
+//!
+//! ```
+//! use artimate::pacing::PacingReport;
+//! use std::time::Duration;
+//!
+//! let intervals: Vec<(u32, Duration)> = (0..100)
+//!     .map(|frame| (frame, Duration::from_millis(if frame == 42 { 50 } else { 16 })))
+//!     .collect();
+//! let report = PacingReport::new(&intervals, Duration::from_secs_f32(1.0 / 60.0));
+//! assert_eq!(report.over_budget, 1);
+//! assert_eq!(report.worst[0], (42, Duration::from_millis(50)));
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::time::Duration;
+
+/// Number of slowest frames listed in a report
+pub const WORST_FRAMES: usize = 10;
+
+/// Width of a histogram bucket of [`PacingStats`] in microseconds
+const BUCKET_MICROS: u64 = 10;
+
+/// Number of histogram buckets, covering frame times up to one second
+const BUCKETS: usize = 100_000;
+
+/// Frame intervals of a run summarized in bounded memory
+///
+/// Frame times are counted in a histogram of 10 microsecond buckets, with longer
+/// frames than a second in the last one, and the slowest frames are kept in a
+/// heap, so reports match `PacingReport::new` to within a bucket.
+///
+/// ```
+/// use artimate::pacing::PacingStats;
+/// use std::time::Duration;
+///
+/// let mut stats = PacingStats::new();
+/// for frame in 0..100 {
+///     stats.push(frame, Duration::from_millis(if frame == 42 { 50 } else { 16 }));
+/// }
+/// let report = stats.report(Duration::from_secs_f32(1.0 / 60.0));
+/// assert_eq!(report.p50, Duration::from_millis(16));
+/// assert_eq!(report.over_budget, 1);
+/// assert_eq!(report.worst[0], (42, Duration::from_millis(50)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PacingStats {
+    /// Number of intervals in each bucket, allocated on the first push
+    histogram: Vec<u32>,
+    /// Number of intervals pushed
+    frames: usize,
+    /// The slowest intervals, fastest on top; ties keep the earlier frame
+    worst: BinaryHeap<Reverse<(Duration, Reverse<u32>)>>,
+}
+
+impl PacingStats {
+    /// Creates empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the interval before `frame`
+    pub fn push(&mut self, frame: u32, interval: Duration) {
+        if self.histogram.is_empty() {
+            self.histogram = vec![0; BUCKETS];
+        }
+        let bucket = (interval.as_micros() / BUCKET_MICROS as u128) as usize;
+        self.histogram[bucket.min(BUCKETS - 1)] += 1;
+        self.frames += 1;
+        self.worst.push(Reverse((interval, Reverse(frame))));
+        if self.worst.len() > WORST_FRAMES {
+            self.worst.pop();
+        }
+    }
+
+    /// Forgets every interval
+    pub fn clear(&mut self) {
+        self.histogram.clear();
+        self.frames = 0;
+        self.worst.clear();
+    }
+
+    /// Returns the number of intervals pushed
+    pub fn len(&self) -> usize {
+        self.frames
+    }
+
+    /// Returns true if no intervals were pushed
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// Summarizes the intervals against a frame budget
+    pub fn report(&self, budget: Duration) -> PacingReport {
+        let mut worst: Vec<(u32, Duration)> = self
+            .worst
+            .iter()
+            .map(|Reverse((time, Reverse(frame)))| (*frame, *time))
+            .collect();
+        worst.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let max = worst.first().map_or(Duration::ZERO, |worst| worst.1);
+        let bucket_time = |i: usize| match i {
+            // Frames past the last bucket are reported at the longest time seen
+            _ if i == BUCKETS - 1 => max,
+            i => Duration::from_micros(i as u64 * BUCKET_MICROS),
+        };
+        let percentile = |p: f32| {
+            if self.frames == 0 {
+                return Duration::ZERO;
+            }
+            // Nearest rank, counted from the fastest frame
+            let rank = ((p * self.frames as f32).ceil() as usize).clamp(1, self.frames);
+            let mut seen = 0;
+            for (i, &count) in self.histogram.iter().enumerate() {
+                seen += count as usize;
+                if seen >= rank {
+                    return bucket_time(i);
+                }
+            }
+            max
+        };
+        let over_budget = self
+            .histogram
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| bucket_time(i) > budget)
+            .map(|(_, &count)| count as usize)
+            .sum();
+        PacingReport {
+            frames: self.frames,
+            budget,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max,
+            over_budget,
+            worst,
+        }
+    }
+}
+
+/// Frame time percentiles, frames over budget and the slowest frames of a run
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PacingReport {
+    /// Number of frame intervals measured
+    pub frames: usize,
+    /// Time a frame may take at the target frame rate
+    pub budget: Duration,
+    /// Median frame time
+    pub p50: Duration,
+    /// 90th percentile frame time
+    pub p90: Duration,
+    /// 99th percentile frame time
+    pub p99: Duration,
+    /// Longest frame time
+    pub max: Duration,
+    /// Number of frames that took longer than the budget
+    pub over_budget: usize,
+    /// Indices and times of the slowest frames, slowest first
+    pub worst: Vec<(u32, Duration)>,
+}
+
+impl PacingReport {
+    /// Summarizes frame intervals, each the frame index and the time since the previous frame
+    pub fn new(intervals: &[(u32, Duration)], budget: Duration) -> Self {
+        let mut sorted: Vec<(u32, Duration)> = intervals.to_vec();
+        // Slowest first; ties keep the earlier frame first
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let percentile = |p: f32| {
+            if sorted.is_empty() {
+                return Duration::ZERO;
+            }
+            // Nearest rank, counted from the fastest frame
+            let rank = (p * sorted.len() as f32).ceil() as usize;
+            sorted[sorted.len() - rank.clamp(1, sorted.len())].1
+        };
+        Self {
+            frames: intervals.len(),
+            budget,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: sorted.first().map_or(Duration::ZERO, |worst| worst.1),
+            over_budget: intervals.iter().filter(|(_, time)| *time > budget).count(),
+            worst: sorted.iter().take(WORST_FRAMES).copied().collect(),
+        }
+    }
+
+    /// Returns true if no frame intervals were measured
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+}
+
+impl fmt::Display for PacingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Frame times: p50 {:.1} ms, p90 {:.1} ms, p99 {:.1} ms, max {:.1} ms",
+            millis(self.p50),
+            millis(self.p90),
+            millis(self.p99),
+            millis(self.max)
+        )?;
+        writeln!(
+            f,
+            "Over budget ({:.1} ms): {} of {} frames ({:.1}%)",
+            millis(self.budget),
+            self.over_budget,
+            self.frames,
+            100.0 * self.over_budget as f32 / self.frames.max(1) as f32
+        )?;
+        if !self.worst.is_empty() {
+            writeln!(f, "Slowest frames:")?;
+        }
+        for (frame, time) in &self.worst {
+            writeln!(f, "  frame {}: {:.1} ms", frame, millis(*time))?;
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
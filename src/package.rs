@@ -0,0 +1,169 @@
+//! # Session Packaging
+//!
+//! Writes a set of files into a single ZIP or tar archive, used to bundle the
+//! frames, metadata and source of an export session for hand-off or upload.
+//! Files are stored uncompressed; PNG frames are already compressed.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Archive format of a packaged session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A ZIP archive with stored (uncompressed) entries
+    Zip,
+    /// A POSIX ustar archive
+    Tar,
+}
+
+impl Format {
+    /// Returns the file extension of the format, without a leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Zip => "zip",
+            Format::Tar => "tar",
+        }
+    }
+}
+
+/// Writes the files in `entries` to an archive at `path`
+///
+/// Each entry is the name the file gets inside the archive, using `/` as
+/// separator, and the path it is read from.
+///
+/// # Examples
+/// ```rust,no_run
+/// use artimate::package::{write_archive, Format};
+///
+/// write_archive(
+///     "renders.zip",
+///     Format::Zip,
+///     &[("frames/frame_0000.png".to_string(), "out/frame_0000.png".into())],
+/// )
+/// .unwrap();
+/// ```
+pub fn write_archive(
+    path: impl AsRef<Path>,
+    format: Format,
+    entries: &[(String, PathBuf)],
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        Format::Zip => write_zip(&mut out, entries)?,
+        Format::Tar => write_tar(&mut out, entries)?,
+    }
+    out.flush()
+}
+
+fn write_zip(out: &mut impl Write, entries: &[(String, PathBuf)]) -> io::Result<()> {
+    if entries.len() > u16::MAX as usize {
+        return Err(io::Error::other("too many files for a ZIP archive"));
+    }
+    let mut central = Vec::new();
+    let mut offset = 0u64;
+    for (name, source) in entries {
+        let data = fs::read(source)?;
+        let (size, start) = match (u32::try_from(data.len()), u32::try_from(offset)) {
+            (Ok(size), Ok(start)) => (size, start),
+            _ => return Err(io::Error::other("ZIP archive larger than 4 GiB")),
+        };
+        let crc = crc32(&data);
+        // Version needed, flags, method (stored), DOS time and date (1980-01-01)
+        let common: [u16; 5] = [20, 0, 0, 0, 0x21];
+
+        let mut local = 0x0403_4b50u32.to_le_bytes().to_vec();
+        for v in common {
+            local.extend_from_slice(&v.to_le_bytes());
+        }
+        local.extend_from_slice(&crc.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes());
+        local.extend_from_slice(name.as_bytes());
+        out.write_all(&local)?;
+        out.write_all(&data)?;
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        for v in common {
+            central.extend_from_slice(&v.to_le_bytes());
+        }
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+        central.extend_from_slice(&start.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+
+        offset += (local.len() + data.len()) as u64;
+    }
+    let central_start =
+        u32::try_from(offset).map_err(|_| io::Error::other("ZIP archive larger than 4 GiB"))?;
+    out.write_all(&central)?;
+
+    let mut end = 0x0605_4b50u32.to_le_bytes().to_vec();
+    end.extend_from_slice(&[0; 4]); // disk numbers
+    end.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    end.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    end.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    end.extend_from_slice(&central_start.to_le_bytes());
+    end.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out.write_all(&end)
+}
+
+fn write_tar(out: &mut impl Write, entries: &[(String, PathBuf)]) -> io::Result<()> {
+    for (name, source) in entries {
+        let data = fs::read(source)?;
+        let mut header = [0u8; 512];
+        let (prefix, name) = split_tar_name(name)?;
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..108].copy_from_slice(b"0000644\0");
+        header[108..116].copy_from_slice(b"0000000\0");
+        header[116..124].copy_from_slice(b"0000000\0");
+        header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+        header[136..148].copy_from_slice(b"00000000000\0");
+        header[148..156].copy_from_slice(b"        ");
+        header[156] = b'0';
+        header[257..265].copy_from_slice(b"ustar\x0000");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+        header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+        out.write_all(&header)?;
+        out.write_all(&data)?;
+        let padding = (512 - data.len() % 512) % 512;
+        out.write_all(&vec![0; padding])?;
+    }
+    out.write_all(&[0; 1024])
+}
+
+/// Splits a name into the ustar prefix and name fields, at most 155 and 100 bytes
+fn split_tar_name(name: &str) -> io::Result<(&str, &str)> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+    name.char_indices()
+        .filter(|&(i, c)| c == '/' && i <= 155 && name.len() - i - 1 <= 100)
+        .map(|(i, _)| (&name[..i], &name[i + 1..]))
+        .next()
+        .ok_or_else(|| io::Error::other(format!("file name too long for tar: {}", name)))
+}
+
+/// CRC-32 (IEEE 802.3) checksum as used by ZIP
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,194 @@
+//! # Palettes
+//!
+//! Procedural color palettes built in the perceptual Okhsl space, so the colors
+//! of a scheme share their brightness and saturation whatever their hue.
+//! [`harmonious`] turns a seed into a balanced five color palette for seed-driven
+//! exploration, and [`Palette`] keeps favorite colors locked while the rest are
+//! shuffled or rerolled.
+//!
+//! ```
+//! use artimate::palette::{self, Palette};
+//! use artimate::random::Rng;
+//!
+//! let base = [30, 144, 255, 255];
+//! assert_eq!(palette::triadic(base).len(), 3);
+//! assert_eq!(palette::analogous(base, 4).len(), 4);
+//!
+//! let mut palette = Palette::new(palette::harmonious(7));
+//! palette.lock(0);
+//! palette.reroll(8);
+//! assert_eq!(palette.colors()[0], palette::harmonious(7)[0]);
+//! let color = palette.pick(&mut Rng::new(1));
+//! # let _ = color;
+//! ```
+
+use crate::color::Okhsl;
+use crate::random::Rng;
+
+/// Hue step between neighbouring analogous colors, in turns
+const ANALOGOUS_STEP: f32 = 1.0 / 12.0;
+
+/// Number of colors in a palette from `harmonious`
+const HARMONIOUS_SIZE: usize = 5;
+
+/// Returns `base` rotated by each of `offsets`, in turns of Okhsl hue
+fn rotations(base: [u8; 4], offsets: &[f32]) -> Vec<[u8; 4]> {
+    let hsl = Okhsl::from_rgba(base);
+    offsets
+        .iter()
+        .map(|offset| Okhsl::new((hsl.h + offset).rem_euclid(1.0), hsl.s, hsl.l).to_rgba(base[3]))
+        .collect()
+}
+
+/// Returns `n` colors of neighbouring hues centred on `base`
+///
+/// Hues are a twelfth of a turn apart and `base` is the first color.
+pub fn analogous(base: [u8; 4], n: usize) -> Vec<[u8; 4]> {
+    let offsets: Vec<f32> = (0..n)
+        .map(|i| {
+            let step = i.div_ceil(2) as f32 * ANALOGOUS_STEP;
+            if i % 2 == 1 {
+                step
+            } else {
+                -step
+            }
+        })
+        .collect();
+    rotations(base, &offsets)
+}
+
+/// Returns `base` and the color of the opposite hue
+pub fn complementary(base: [u8; 4]) -> Vec<[u8; 4]> {
+    rotations(base, &[0.0, 0.5])
+}
+
+/// Returns `base` and the two colors a third of a turn away
+pub fn triadic(base: [u8; 4]) -> Vec<[u8; 4]> {
+    rotations(base, &[0.0, 1.0 / 3.0, 2.0 / 3.0])
+}
+
+/// Returns `base` and the two neighbours of its complement
+pub fn split_complementary(base: [u8; 4]) -> Vec<[u8; 4]> {
+    rotations(base, &[0.0, 5.0 / 12.0, 7.0 / 12.0])
+}
+
+/// Returns a balanced five color palette determined by `seed`
+///
+/// A random base hue is combined with a randomly chosen scheme (analogous,
+/// complementary, split complementary or triadic), and the colors are spread
+/// from dark to light so the palette always has contrast. Equal seeds give equal
+/// palettes.
+pub fn harmonious(seed: u64) -> Vec<[u8; 4]> {
+    let mut rng = Rng::new(seed).fork("harmonious");
+    let hue = rng.next_f32();
+    let offsets: &[f32] = match rng.below(4) {
+        0 => &[0.0, ANALOGOUS_STEP, -ANALOGOUS_STEP, 2.0 * ANALOGOUS_STEP],
+        1 => &[0.0, 0.5],
+        2 => &[0.0, 5.0 / 12.0, 7.0 / 12.0],
+        _ => &[0.0, 1.0 / 3.0, 2.0 / 3.0],
+    };
+    let mut lightness: Vec<f32> = (0..HARMONIOUS_SIZE)
+        .map(|i| 0.2 + 0.7 * i as f32 / (HARMONIOUS_SIZE - 1) as f32)
+        .collect();
+    rng.shuffle(&mut lightness);
+    lightness
+        .into_iter()
+        .enumerate()
+        .map(|(i, l)| {
+            let h = hue + offsets[i % offsets.len()] + rng.gaussian(0.0, 0.01);
+            let s = rng.range(0.45, 0.9);
+            Okhsl::new(h.rem_euclid(1.0), s, l).to_rgba(255)
+        })
+        .collect()
+}
+
+/// A palette whose colors can be locked while the others are shuffled or rerolled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<[u8; 4]>,
+    locked: Vec<bool>,
+}
+
+impl Palette {
+    /// Creates a palette with no locked colors
+    pub fn new(colors: Vec<[u8; 4]>) -> Self {
+        let locked = vec![false; colors.len()];
+        Self { colors, locked }
+    }
+
+    /// Returns the colors in order
+    pub fn colors(&self) -> &[[u8; 4]] {
+        &self.colors
+    }
+
+    /// Returns the number of colors
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Returns true if the palette has no colors
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Keeps the color at `index` in place when shuffling or rerolling
+    pub fn lock(&mut self, index: usize) {
+        if let Some(locked) = self.locked.get_mut(index) {
+            *locked = true;
+        }
+    }
+
+    /// Releases the color at `index`
+    pub fn unlock(&mut self, index: usize) {
+        if let Some(locked) = self.locked.get_mut(index) {
+            *locked = false;
+        }
+    }
+
+    /// Locks or releases the color at `index`
+    pub fn toggle_lock(&mut self, index: usize) {
+        if let Some(locked) = self.locked.get_mut(index) {
+            *locked = !*locked;
+        }
+    }
+
+    /// Returns true if the color at `index` is locked
+    pub fn is_locked(&self, index: usize) -> bool {
+        self.locked.get(index).copied().unwrap_or(false)
+    }
+
+    /// Shuffles the unlocked colors among the unlocked positions
+    pub fn shuffle(&mut self, rng: &mut Rng) {
+        let unlocked: Vec<usize> = (0..self.colors.len())
+            .filter(|&i| !self.locked[i])
+            .collect();
+        let mut colors: Vec<[u8; 4]> = unlocked.iter().map(|&i| self.colors[i]).collect();
+        rng.shuffle(&mut colors);
+        for (i, color) in unlocked.into_iter().zip(colors) {
+            self.colors[i] = color;
+        }
+    }
+
+    /// Replaces the unlocked colors with those at the same positions of `harmonious(seed)`
+    ///
+    /// Palettes longer than five colors draw their extra colors from the
+    /// following seeds.
+    pub fn reroll(&mut self, seed: u64) {
+        let mut fresh = Vec::with_capacity(self.colors.len());
+        let mut next = seed;
+        while fresh.len() < self.colors.len() {
+            fresh.extend(harmonious(next));
+            next = next.wrapping_add(1);
+        }
+        for (i, color) in fresh.into_iter().enumerate().take(self.colors.len()) {
+            if !self.locked[i] {
+                self.colors[i] = color;
+            }
+        }
+    }
+
+    /// Returns a uniformly chosen color, or transparent black if the palette is empty
+    pub fn pick(&self, rng: &mut Rng) -> [u8; 4] {
+        rng.choose(&self.colors).copied().unwrap_or([0, 0, 0, 0])
+    }
+}
@@ -0,0 +1,73 @@
+//! # Power Management
+//!
+//! Lower frame rates for sketches running on battery, so laptop demos last.
+
+use std::path::Path;
+use std::process::Command;
+
+/// How the frame rate responds to the power source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PowerMode {
+    /// Render at the configured frame rate, as fast as possible if none is set
+    #[default]
+    Normal,
+    /// Always render at half the target frame rate, sleeping between frames
+    Low,
+    /// Use `Low` when the machine runs on battery and `Normal` otherwise
+    Auto,
+}
+
+impl PowerMode {
+    /// Returns true if frames should be rendered in low power mode
+    ///
+    /// `Auto` falls back to normal power when the power source cannot be detected.
+    pub fn is_low(&self) -> bool {
+        match self {
+            PowerMode::Normal => false,
+            PowerMode::Low => true,
+            PowerMode::Auto => on_battery().unwrap_or(false),
+        }
+    }
+}
+
+/// Returns whether the machine is running on battery, or None if it cannot be determined
+///
+/// Supported on Linux, using `/sys/class/power_supply`, and macOS, using `pmset`.
+pub fn on_battery() -> Option<bool> {
+    if cfg!(target_os = "macos") {
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        if text.contains("'Battery Power'") {
+            Some(true)
+        } else if text.contains("'AC Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    } else if cfg!(target_os = "linux") {
+        let mut has_battery = false;
+        for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+            let path = entry.path();
+            // Batteries and chargers of peripherals such as wireless mice don't power the machine
+            if read_trimmed(&path.join("scope")).as_deref() == Some("Device") {
+                continue;
+            }
+            match read_trimmed(&path.join("type")).as_deref() {
+                Some("Battery") => has_battery = true,
+                Some(_) if read_trimmed(&path.join("online")).as_deref() == Some("1") => {
+                    return Some(false)
+                }
+                _ => {}
+            }
+        }
+        has_battery.then_some(true)
+    } else {
+        None
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
@@ -0,0 +1,87 @@
+//! # Size Presets
+//!
+//! Common canvas sizes for social media and print, usable both as the window size
+//! (`Config::preset`) and as targets for batch export (`App::export_presets`).
+
+use std::fmt;
+
+/// Paper sizes for print presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paper {
+    /// ISO A3, 297 x 420 mm
+    A3,
+    /// ISO A4, 210 x 297 mm
+    A4,
+    /// ISO A5, 148 x 210 mm
+    A5,
+    /// US Letter, 8.5 x 11 in
+    Letter,
+}
+
+impl Paper {
+    /// Returns the portrait width and height in millimeters
+    pub fn mm(self) -> (f32, f32) {
+        match self {
+            Paper::A3 => (297.0, 420.0),
+            Paper::A4 => (210.0, 297.0),
+            Paper::A5 => (148.0, 210.0),
+            Paper::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+/// A named canvas size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// 1080 x 1080, square social media post
+    Square1080,
+    /// 1080 x 1350, portrait social media post
+    Portrait1080x1350,
+    /// 1080 x 1920, vertical story or reel
+    Story1080x1920,
+    /// 1920 x 1080, full HD landscape video
+    Landscape1920x1080,
+    /// 3840 x 2160, 4K UHD landscape video
+    Uhd3840x2160,
+    /// Portrait paper size at 300 dots per inch
+    Print300dpi(Paper),
+}
+
+impl Preset {
+    /// Returns the width and height in pixels
+    pub fn dims(self) -> (u32, u32) {
+        match self {
+            Preset::Square1080 => (1080, 1080),
+            Preset::Portrait1080x1350 => (1080, 1350),
+            Preset::Story1080x1920 => (1080, 1920),
+            Preset::Landscape1920x1080 => (1920, 1080),
+            Preset::Uhd3840x2160 => (3840, 2160),
+            Preset::Print300dpi(paper) => {
+                let (w, h) = paper.mm();
+                let px = |mm: f32| (mm / 25.4 * 300.0).round() as u32;
+                (px(w), px(h))
+            }
+        }
+    }
+
+    /// Returns a short name suitable for file names, e.g. `square1080` or `a4_300dpi`
+    pub fn name(self) -> String {
+        match self {
+            Preset::Square1080 => "square1080".to_string(),
+            Preset::Portrait1080x1350 => "portrait1080x1350".to_string(),
+            Preset::Story1080x1920 => "story1080x1920".to_string(),
+            Preset::Landscape1920x1080 => "landscape1920x1080".to_string(),
+            Preset::Uhd3840x2160 => "uhd3840x2160".to_string(),
+            Preset::Print300dpi(paper) => {
+                format!("{}_300dpi", format!("{:?}", paper).to_lowercase())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (w, h) = self.dims();
+        write!(f, "{} ({}x{})", self.name(), w, h)
+    }
+}
@@ -0,0 +1,598 @@
+//! # Raster Fill Styles
+//!
+//! Plotter and engraving style fills: stippling with dots whose density follows a
+//! function, and hatching with evenly spaced lines that follow a direction field.
+//! Fills produce geometry, which can be drawn into a pixel buffer or written to
+//! SVG for a pen plotter.
+//!
+//! Photographs can be re-rendered as drawings too: `darkness` and `threshold`
+//! turn an image into a field, `weighted_stipple` places evenly spread dots,
+//! `tsp_path` joins them into one continuous line, and `contours` traces outlines.
+//!
+//! ```
+//! use artimate::geom::Rect;
+//! use artimate::raster::{draw_dots, draw_lines, hatch, stipple, to_svg};
+//! use artimate::random::Rng;
+//!
+//! let (width, height) = (320, 240);
+//! let mut frame = vec![255; (width * height * 4) as usize];
+//! let region = Rect::from_size(320.0, 240.0);
+//!
+//! // Darker towards the right
+//! let dots = stipple(region, |x, _| x / 320.0 * 0.05, &mut Rng::new(1));
+//! draw_dots(&mut frame, width, &dots, 1.0, [0, 0, 0, 255]);
+//!
+//! // Lines swirling around the center
+//! let lines = hatch(region, 8.0, |x, y| [-(y - 120.0), x - 160.0]);
+//! draw_lines(&mut frame, width, &lines, 1.0, [0, 0, 0, 255]);
+//!
+//! let svg = to_svg(width, height, &dots, 1.0, &lines, 1.0);
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use crate::brush::{stroke, Profile, StrokeStyle};
+use crate::buffer::Image;
+use crate::geom::Rect;
+use crate::random::Rng;
+use crate::viz::Field;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Scatters dots over `region` with a density given per square pixel
+///
+/// Every pixel of the region receives a dot at a random position within it with
+/// probability `density(x, y)`, so `0.05` gives one dot per 20 square pixels on
+/// average. Densities are clamped to [0, 1]; return 0 outside the shape to fill.
+pub fn stipple(region: Rect, density: impl Fn(f32, f32) -> f32, rng: &mut Rng) -> Vec<(f32, f32)> {
+    let mut dots = Vec::new();
+    let columns = region.width.max(0.0).ceil() as u32;
+    let rows = region.height.max(0.0).ceil() as u32;
+    for row in 0..rows {
+        for col in 0..columns {
+            let x = region.x + col as f32 + rng.next_f32();
+            let y = region.y + row as f32 + rng.next_f32();
+            if region.contains(x, y) && rng.chance(density(x, y).clamp(0.0, 1.0)) {
+                dots.push((x, y));
+            }
+        }
+    }
+    dots
+}
+
+/// Fills `region` with evenly spaced lines that follow a direction field
+///
+/// Lines are seeded on a grid and traced in both directions along `field`, one
+/// pixel at a time, until they leave the region, reach the field's zero or come
+/// closer than `spacing` to another line. A constant field such as
+/// `|_, _| [1.0, 1.0]` gives classic straight hatching; hatch twice with
+/// perpendicular fields to cross-hatch.
+///
+/// # Returns
+/// Polylines with points about a pixel apart, ready for `draw_lines` or `to_svg`
+pub fn hatch(
+    region: Rect,
+    spacing: f32,
+    field: impl Fn(f32, f32) -> [f32; 2],
+) -> Vec<Vec<(f32, f32)>> {
+    let spacing = spacing.max(1.0);
+    let mut occupied = Occupancy::new(region, spacing);
+    let mut lines: Vec<Vec<(f32, f32)>> = Vec::new();
+    let direction = |x: f32, y: f32, sign: f32| {
+        let [vx, vy] = field(x, y);
+        let len = (vx * vx + vy * vy).sqrt();
+        (len > f32::EPSILON && len.is_finite()).then(|| (sign * vx / len, sign * vy / len))
+    };
+    let max_steps = (region.width + region.height) as usize * 4;
+    let columns = (region.width / spacing).ceil() as u32;
+    let rows = (region.height / spacing).ceil() as u32;
+    for row in 0..rows {
+        for col in 0..columns {
+            let seed = (
+                region.x + (col as f32 + 0.5) * spacing,
+                region.y + (row as f32 + 0.5) * spacing,
+            );
+            if !region.contains(seed.0, seed.1) || occupied.near(seed, lines.len(), 0.0) {
+                continue;
+            }
+            let id = lines.len();
+            let mut halves = [Vec::new(), Vec::new()];
+            for (half, sign) in halves.iter_mut().zip([1.0, -1.0]) {
+                let (mut x, mut y) = seed;
+                for step in 1..=max_steps {
+                    let arc = sign * step as f32;
+                    let Some((dx, dy)) = direction(x, y, sign) else {
+                        break;
+                    };
+                    let Some((dx, dy)) = direction(x + dx * 0.5, y + dy * 0.5, sign) else {
+                        break;
+                    };
+                    (x, y) = (x + dx, y + dy);
+                    if !region.contains(x, y) || occupied.near((x, y), id, arc) {
+                        break;
+                    }
+                    half.push((x, y));
+                    occupied.insert((x, y), id, arc);
+                }
+            }
+            let [forward, backward] = halves;
+            let line: Vec<(f32, f32)> = backward
+                .into_iter()
+                .rev()
+                .chain(std::iter::once(seed))
+                .chain(forward)
+                .collect();
+            occupied.insert(seed, id, 0.0);
+            lines.push(line);
+        }
+    }
+    lines.retain(|line| line.len() as f32 >= spacing);
+    lines
+}
+
+/// Points of traced lines bucketed by grid cell, for finding nearby lines quickly
+///
+/// Each point records its line and its arc position along the line from the seed.
+struct Occupancy {
+    region: Rect,
+    cell: f32,
+    columns: usize,
+    rows: usize,
+    buckets: Vec<Vec<Mark>>,
+}
+
+/// A traced point: its position, line index and arc position along the line
+type Mark = ((f32, f32), usize, f32);
+
+impl Occupancy {
+    fn new(region: Rect, cell: f32) -> Self {
+        let columns = (region.width / cell).ceil().max(1.0) as usize;
+        let rows = (region.height / cell).ceil().max(1.0) as usize;
+        Self {
+            region,
+            cell,
+            columns,
+            rows,
+            buckets: vec![Vec::new(); columns * rows],
+        }
+    }
+
+    fn cell_of(&self, (x, y): (f32, f32)) -> (usize, usize) {
+        let col = ((x - self.region.x) / self.cell).max(0.0) as usize;
+        let row = ((y - self.region.y) / self.cell).max(0.0) as usize;
+        (col.min(self.columns - 1), row.min(self.rows - 1))
+    }
+
+    fn insert(&mut self, point: (f32, f32), line: usize, arc: f32) {
+        let (col, row) = self.cell_of(point);
+        self.buckets[row * self.columns + col].push((point, line, arc));
+    }
+
+    /// Returns whether another line, or a distant part of this one, is within a cell of `point`
+    ///
+    /// `line` and `arc` identify the point being traced, so a line stops when it
+    /// loops back onto itself.
+    fn near(&self, point: (f32, f32), line: usize, arc: f32) -> bool {
+        let (col, row) = self.cell_of(point);
+        let min_distance = self.cell * self.cell;
+        for r in row.saturating_sub(1)..=(row + 1).min(self.rows - 1) {
+            for c in col.saturating_sub(1)..=(col + 1).min(self.columns - 1) {
+                for &((x, y), other, other_arc) in &self.buckets[r * self.columns + c] {
+                    let (dx, dy) = (x - point.0, y - point.1);
+                    let distinct = other != line || (arc - other_arc).abs() > 3.0 * self.cell;
+                    if distinct && dx * dx + dy * dy < min_distance {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Draws anti-aliased dots of the given radius into an RGBA frame
+pub fn draw_dots(frame: &mut [u8], width: u32, dots: &[(f32, f32)], radius: f32, color: [u8; 4]) {
+    let style = StrokeStyle::new()
+        .weight(radius * 2.0)
+        .opacity(1.0)
+        .width_profile(Profile::Constant);
+    for &dot in dots {
+        stroke(frame, width, &[dot], color, &style);
+    }
+}
+
+/// Draws anti-aliased polylines of constant width into an RGBA frame
+pub fn draw_lines(
+    frame: &mut [u8],
+    width: u32,
+    lines: &[Vec<(f32, f32)>],
+    weight: f32,
+    color: [u8; 4],
+) {
+    let style = StrokeStyle::new()
+        .weight(weight)
+        .opacity(1.0)
+        .width_profile(Profile::Constant);
+    for line in lines {
+        stroke(frame, width, line, color, &style);
+    }
+}
+
+/// Writes dots and polylines as a black-on-white SVG document for plotting
+///
+/// Dots become filled circles of `dot_radius` and lines become polylines stroked
+/// `stroke_width` wide, in pixel units.
+pub fn to_svg(
+    width: u32,
+    height: u32,
+    dots: &[(f32, f32)],
+    dot_radius: f32,
+    lines: &[Vec<(f32, f32)>],
+    stroke_width: f32,
+) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n"
+    );
+    if !dots.is_empty() {
+        svg.push_str("<g fill=\"black\">\n");
+        for (x, y) in dots {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{x:.2}\" cy=\"{y:.2}\" r=\"{dot_radius}\"/>"
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+    if !lines.is_empty() {
+        let _ = writeln!(
+            svg,
+            "<g fill=\"none\" stroke=\"black\" stroke-width=\"{stroke_width}\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\">"
+        );
+        for line in lines {
+            svg.push_str("<polyline points=\"");
+            for (i, (x, y)) in line.iter().enumerate() {
+                let separator = if i == 0 { "" } else { " " };
+                let _ = write!(svg, "{separator}{x:.2},{y:.2}");
+            }
+            svg.push_str("\"/>\n");
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns how dark each pixel of an image is, from 0 for white to 1 for black
+///
+/// Transparent pixels count as white, so the result is a density for
+/// `weighted_stipple` or a height map for `contours`, one cell per pixel.
+pub fn darkness(image: &Image) -> Field {
+    let values = image
+        .data
+        .chunks_exact(4)
+        .map(|px| {
+            let luminance =
+                (0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32) / 255.0;
+            (1.0 - luminance) * px[3] as f32 / 255.0
+        })
+        .collect();
+    Field {
+        width: image.width,
+        height: image.height,
+        values,
+    }
+}
+
+/// Returns 1 where the luminance of an image is below `level` in [0, 1] and 0 elsewhere
+///
+/// Trace the result with `contours(&field, 0.5)` to get the outlines of the
+/// dark shapes.
+pub fn threshold(image: &Image, level: f32) -> Field {
+    let mut field = darkness(image);
+    for v in field.values.iter_mut() {
+        *v = if 1.0 - *v < level { 1.0 } else { 0.0 };
+    }
+    field
+}
+
+/// Places `count` dots distributed by `density` with weighted Voronoi stippling
+///
+/// Dots are scattered with probability proportional to the density and then
+/// spread evenly by `iterations` rounds of weighted Lloyd relaxation, moving each
+/// dot to the density-weighted centroid of the cells nearest to it (Secord's
+/// method). Coordinates are in cells, with the center of cell `(x, y)` at
+/// `(x + 0.5, y + 0.5)`; use `darkness` to stipple a photograph.
+///
+/// ```
+/// use artimate::buffer::Image;
+/// use artimate::raster::{darkness, tsp_path, weighted_stipple};
+/// use artimate::random::Rng;
+///
+/// let mut image = Image::new(64, 64);
+/// for y in 0..64 {
+///     for x in 0..64 {
+///         let v = (x * 4) as u8;
+///         image.set_pixel(x, y, [v, v, v, 255]);
+///     }
+/// }
+/// let dots = weighted_stipple(&darkness(&image), 200, 10, &mut Rng::new(5));
+/// assert_eq!(dots.len(), 200);
+///
+/// // One continuous line through every dot, for TSP art
+/// let path = tsp_path(&dots);
+/// assert_eq!(path.len(), 200);
+/// ```
+pub fn weighted_stipple(
+    density: &Field,
+    count: usize,
+    iterations: u32,
+    rng: &mut Rng,
+) -> Vec<(f32, f32)> {
+    let weights: Vec<f32> = density
+        .values
+        .iter()
+        .map(|v| if v.is_finite() { v.max(0.0) } else { 0.0 })
+        .collect();
+    let mut cumulative = Vec::with_capacity(weights.len());
+    let mut total = 0.0f64;
+    for w in &weights {
+        total += *w as f64;
+        cumulative.push(total);
+    }
+    if count == 0 || total <= 0.0 {
+        return Vec::new();
+    }
+    let width = density.width as usize;
+    let mut dots: Vec<(f32, f32)> = (0..count)
+        .map(|_| {
+            let target = rng.next_f64() * total;
+            let i = cumulative
+                .partition_point(|&c| c <= target)
+                .min(weights.len() - 1);
+            (
+                (i % width) as f32 + rng.next_f32(),
+                (i / width) as f32 + rng.next_f32(),
+            )
+        })
+        .collect();
+
+    for _ in 0..iterations {
+        let grid = PointGrid::new(&dots, density.width, density.height);
+        let mut sums = vec![(0.0f64, 0.0f64, 0.0f64); dots.len()];
+        for (i, &w) in weights.iter().enumerate() {
+            if w <= 0.0 {
+                continue;
+            }
+            let p = ((i % width) as f32 + 0.5, (i / width) as f32 + 0.5);
+            let nearest = grid.nearest(&dots, p);
+            let sum = &mut sums[nearest];
+            sum.0 += (w * p.0) as f64;
+            sum.1 += (w * p.1) as f64;
+            sum.2 += w as f64;
+        }
+        for (dot, (sx, sy, sw)) in dots.iter_mut().zip(sums) {
+            if sw > 0.0 {
+                *dot = ((sx / sw) as f32, (sy / sw) as f32);
+            }
+        }
+    }
+    dots
+}
+
+/// Indices of points bucketed by grid cell, for nearest point queries
+struct PointGrid {
+    cell: f32,
+    columns: usize,
+    rows: usize,
+    buckets: Vec<Vec<usize>>,
+}
+
+impl PointGrid {
+    /// Buckets `points` in a grid over a `width` x `height` area with about one point per cell
+    fn new(points: &[(f32, f32)], width: u32, height: u32) -> Self {
+        let cell = ((width * height) as f32 / points.len().max(1) as f32)
+            .sqrt()
+            .max(1.0);
+        let columns = (width as f32 / cell).ceil().max(1.0) as usize;
+        let rows = (height as f32 / cell).ceil().max(1.0) as usize;
+        let mut grid = Self {
+            cell,
+            columns,
+            rows,
+            buckets: vec![Vec::new(); columns * rows],
+        };
+        for (i, &p) in points.iter().enumerate() {
+            let (col, row) = grid.cell_of(p);
+            grid.buckets[row * columns + col].push(i);
+        }
+        grid
+    }
+
+    fn cell_of(&self, (x, y): (f32, f32)) -> (usize, usize) {
+        let col = (x / self.cell).max(0.0) as usize;
+        let row = (y / self.cell).max(0.0) as usize;
+        (col.min(self.columns - 1), row.min(self.rows - 1))
+    }
+
+    /// Returns the index of the point nearest to `p`, searching rings of cells outwards
+    fn nearest(&self, points: &[(f32, f32)], p: (f32, f32)) -> usize {
+        let (col, row) = self.cell_of(p);
+        let (mut best, mut best_distance) = (0, f32::MAX);
+        for ring in 0..self.columns.max(self.rows) {
+            let r0 = row.saturating_sub(ring);
+            let r1 = (row + ring).min(self.rows - 1);
+            let c0 = col.saturating_sub(ring);
+            let c1 = (col + ring).min(self.columns - 1);
+            for r in r0..=r1 {
+                for c in c0..=c1 {
+                    if r.abs_diff(row) != ring && c.abs_diff(col) != ring {
+                        continue;
+                    }
+                    for &i in &self.buckets[r * self.columns + c] {
+                        let (dx, dy) = (points[i].0 - p.0, points[i].1 - p.1);
+                        let d = dx * dx + dy * dy;
+                        if d < best_distance {
+                            (best, best_distance) = (i, d);
+                        }
+                    }
+                }
+            }
+            // Points in the next ring are at least `ring` cells away
+            let reach = ring as f32 * self.cell;
+            if best_distance <= reach * reach {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// Orders points into a short path visiting each once, for single-line "TSP art"
+///
+/// Builds a nearest neighbor tour from the first point and shortens it with 2-opt
+/// passes until no reversal helps. Quadratic per pass, so best for up to a few
+/// thousand points.
+pub fn tsp_path(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let distance = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).hypot(a.1 - b.1);
+    let mut remaining = points.to_vec();
+    let mut path = Vec::with_capacity(points.len());
+    if let Some(first) = remaining.first().copied() {
+        remaining.swap_remove(0);
+        path.push(first);
+    }
+    while let Some(&last) = path.last() {
+        let Some((i, _)) = remaining
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance(last, **a).total_cmp(&distance(last, **b)))
+        else {
+            break;
+        };
+        path.push(remaining.swap_remove(i));
+    }
+
+    let n = path.len();
+    for _ in 0..MAX_TWO_OPT_PASSES {
+        let mut improved = false;
+        for i in 0..n.saturating_sub(2) {
+            for j in i + 2..n {
+                let (a, b, c) = (path[i], path[i + 1], path[j]);
+                let before = distance(a, b) + path.get(j + 1).map_or(0.0, |&d| distance(c, d));
+                let after = distance(a, c) + path.get(j + 1).map_or(0.0, |&d| distance(b, d));
+                if after + 1e-4 < before {
+                    path[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    path
+}
+
+/// Upper bound on 2-opt passes in `tsp_path`, which usually converges far sooner
+const MAX_TWO_OPT_PASSES: u32 = 50;
+
+/// Traces the lines where a field crosses `level` with marching squares
+///
+/// Coordinates are in cells, with the center of cell `(x, y)` at
+/// `(x + 0.5, y + 0.5)`. Closed contours end with their first point repeated.
+/// Contour `darkness` at several levels for topographic drawings, or `threshold`
+/// at 0.5 for the outlines of shapes.
+pub fn contours(field: &Field, level: f32) -> Vec<Vec<(f32, f32)>> {
+    let (w, h) = (field.width as usize, field.height as usize);
+    if w < 2 || h < 2 {
+        return Vec::new();
+    }
+    let value = |x: usize, y: usize| field.values[y * w + x];
+    // Crossings are keyed by their edge: (x, y, vertical) for the edge leaving (x, y)
+    let crossing = |(x, y, vertical): Edge| {
+        let (x1, y1) = if vertical { (x, y + 1) } else { (x + 1, y) };
+        let (a, b) = (value(x, y), value(x1, y1));
+        let t = if a == b {
+            0.5
+        } else {
+            ((level - a) / (b - a)).clamp(0.0, 1.0)
+        };
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        if vertical {
+            (px, py + t)
+        } else {
+            (px + t, py)
+        }
+    };
+
+    let mut links: HashMap<Edge, Vec<Edge>> = HashMap::new();
+    for y in 0..h - 1 {
+        for x in 0..w - 1 {
+            let corners = [
+                value(x, y),
+                value(x + 1, y),
+                value(x + 1, y + 1),
+                value(x, y + 1),
+            ];
+            let inside = corners.map(|v| v >= level);
+            // The edges next to each corner, in the same order as the corners
+            let top = (x, y, false);
+            let right = (x + 1, y, true);
+            let bottom = (x, y + 1, false);
+            let left = (x, y, true);
+            let around = [(left, top), (top, right), (right, bottom), (bottom, left)];
+            let crossed: Vec<Edge> = [top, right, bottom, left]
+                .into_iter()
+                .zip([(0, 1), (1, 2), (3, 2), (0, 3)])
+                .filter(|&(_, (a, b))| inside[a] != inside[b])
+                .map(|(edge, _)| edge)
+                .collect();
+            let mut link = |a: Edge, b: Edge| {
+                links.entry(a).or_default().push(b);
+                links.entry(b).or_default().push(a);
+            };
+            match crossed.len() {
+                2 => link(crossed[0], crossed[1]),
+                4 => {
+                    // Saddle: cut off the corners that disagree with the center
+                    let center = corners.iter().sum::<f32>() / 4.0 >= level;
+                    for (corner, &(a, b)) in around.iter().enumerate() {
+                        if inside[corner] != center {
+                            link(a, b);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Walk open chains from their ends first, then the remaining closed loops
+    let mut lines = Vec::new();
+    let mut starts: Vec<Edge> = links.keys().copied().collect();
+    starts.sort_by_key(|&(x, y, vertical)| (links[&(x, y, vertical)].len() != 1, y, x, vertical));
+    for start in starts {
+        if links[&start].is_empty() {
+            continue;
+        }
+        let mut line = vec![crossing(start)];
+        let mut current = start;
+        while let Some(next) = links.get_mut(&current).and_then(|n| n.pop()) {
+            if let Some(back) = links.get_mut(&next) {
+                if let Some(i) = back.iter().position(|&e| e == current) {
+                    back.swap_remove(i);
+                }
+            }
+            line.push(crossing(next));
+            current = next;
+        }
+        if line.len() > 1 {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// A cell edge of a field, as the cell it leaves and whether it runs downwards
+type Edge = (usize, usize, bool);
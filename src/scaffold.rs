@@ -0,0 +1,235 @@
+//! # Project Scaffolding
+//!
+//! Generates ready-to-run sketch projects, so a workshop can go from nothing to a
+//! window on screen with a single call (or `cargo artimate new <name>` when the
+//! `cli` feature is enabled).
+//!
+//! ```rust,no_run
+//! use artimate::scaffold::{new_sketch, Template};
+//!
+//! let dir = new_sketch("my_sketch", Template::App).unwrap();
+//! println!("Created {}", dir.display());
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The kind of project generated by `new_sketch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Template {
+    /// A `SketchMode` project with only a draw function
+    Sketch,
+    /// An `AppMode` project with a model, update and draw function and hotkeys
+    #[default]
+    App,
+}
+
+impl Template {
+    /// Returns the contents of `src/main.rs` for this template
+    fn main_rs(self, name: &str) -> String {
+        let source = match self {
+            Template::Sketch => SKETCH_TEMPLATE,
+            Template::App => APP_TEMPLATE,
+        };
+        source.replace("{{name}}", name)
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sketch" => Ok(Template::Sketch),
+            "app" => Ok(Template::App),
+            other => Err(format!(
+                "unknown template '{}', expected 'sketch' or 'app'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Template::Sketch => write!(f, "sketch"),
+            Template::App => write!(f, "app"),
+        }
+    }
+}
+
+/// Creates a new sketch project in a directory called `name`
+///
+/// The directory contains a `Cargo.toml` depending on this version of artimate,
+/// a `src/main.rs` generated from `template` and a `.gitignore`.
+///
+/// # Arguments
+/// * `name` - Name of the project directory and crate, may include parent directories
+/// * `template` - The kind of sketch to generate
+///
+/// # Returns
+/// * `Ok(PathBuf)` - The path of the created project directory
+/// * `Err(io::Error)` - If the name is not a valid crate name, the directory already exists,
+///   or a file could not be written
+pub fn new_sketch(name: impl AsRef<Path>, template: Template) -> io::Result<PathBuf> {
+    let dir = name.as_ref().to_path_buf();
+    let crate_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    validate_crate_name(&crate_name)?;
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("destination '{}' already exists", dir.display()),
+        ));
+    }
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), cargo_toml(&crate_name))?;
+    fs::write(
+        dir.join("src").join("main.rs"),
+        template.main_rs(&crate_name),
+    )?;
+    fs::write(dir.join(".gitignore"), "/target\n")?;
+    Ok(dir)
+}
+
+/// Checks that `name` can be used as a Cargo package name
+fn validate_crate_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "'{}' is not a valid crate name, use letters, digits, '-' and '_'",
+                name
+            ),
+        ))
+    }
+}
+
+fn cargo_toml(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+artimate = "{version}"
+winit = "0.30"
+"#,
+        name = name,
+        version = env!("CARGO_PKG_VERSION"),
+    )
+}
+
+const SKETCH_TEMPLATE: &str = r#"use artimate::app::{App, Config, Error};
+use winit::keyboard::Key;
+
+fn main() -> Result<(), Error> {
+    let config = Config::with_dims(800, 800).set_title("{{name}}");
+    let mut app = App::sketch(config, draw);
+
+    // Cmd+S saves the current frame to Downloads/artmate
+    app.on_key_press(Key::Character("p".into()), |app| {
+        println!("frame {} at {:.2}s", app.frame_count, app.time);
+    });
+
+    app.run()
+}
+
+fn draw(app: &App, _model: &()) -> Vec<u8> {
+    let (w, h) = app.wh();
+    let mut pixels = vec![0u8; (w * h * 4) as usize];
+    for y in 0..h {
+        for x in 0..w {
+            let i = ((y * w + x) * 4) as usize;
+            pixels[i] = (x * 255 / w) as u8;
+            pixels[i + 1] = (y * 255 / h) as u8;
+            pixels[i + 2] = (128.0 + 127.0 * app.time.sin()) as u8;
+            pixels[i + 3] = 255;
+        }
+    }
+    pixels
+}
+"#;
+
+const APP_TEMPLATE: &str = r#"use artimate::app::{App, AppMode, Config, Error};
+use winit::keyboard::{Key, NamedKey};
+
+#[derive(Clone)]
+struct Model {
+    x: f32,
+    speed: f32,
+    paused: bool,
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            speed: 4.0,
+            paused: false,
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let config = Config::with_dims(800, 800).set_title("{{name}}");
+    let mut app = App::app(Model::default(), config, update, draw);
+
+    // Cmd+S saves the current frame to Downloads/artmate
+    app.on_key_press(Key::Named(NamedKey::Space), |app| {
+        app.model.paused = !app.model.paused;
+    });
+    app.on_key_press(Key::Named(NamedKey::ArrowUp), |app| {
+        app.model.speed += 1.0;
+    });
+    app.on_key_press(Key::Named(NamedKey::ArrowDown), |app| {
+        app.model.speed = (app.model.speed - 1.0).max(0.0);
+    });
+    app.on_key_press(Key::Character("r".into()), |app| {
+        app.model = Model::default();
+    });
+
+    println!("space: pause, up/down: speed, r: reset, cmd+s: save frame");
+    app.run()
+}
+
+fn update(app: &App<AppMode, Model>, mut model: Model) -> Model {
+    if !model.paused {
+        model.x = (model.x + model.speed) % app.w_f32();
+    }
+    model
+}
+
+fn draw(app: &App<AppMode, Model>, model: &Model) -> Vec<u8> {
+    let (w, h) = app.wh();
+    let mut pixels = vec![0u8; (w * h * 4) as usize];
+    let (cx, cy, r) = (model.x, app.h_f32() / 2.0, app.w_f32() / 10.0);
+    for y in 0..h {
+        for x in 0..w {
+            let i = ((y * w + x) * 4) as usize;
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            let inside = dx * dx + dy * dy <= r * r;
+            pixels[i] = if inside { 255 } else { 20 };
+            pixels[i + 1] = if inside { 140 } else { 20 };
+            pixels[i + 2] = if inside { 0 } else { 30 };
+            pixels[i + 3] = 255;
+        }
+    }
+    pixels
+}
+"#;
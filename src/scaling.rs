@@ -0,0 +1,360 @@
+//! # Scaling and Post-processing
+//!
+//! How the pixel buffer is scaled onto the window. `Filter::Nearest`, the
+//! default, scales by whole multiples so every buffer pixel becomes a crisp
+//! square, leaving a border when the window is not an exact multiple of the
+//! buffer. `Filter::Linear` blends neighboring pixels and fits the buffer to the
+//! window at any scale, keeping its aspect ratio, for smooth output such as a
+//! low-resolution preview stretched over a large window.
+//!
+//! ```
+//! use artimate::app::Config;
+//! use artimate::scaling::Filter;
+//!
+//! let config = Config::with_dims(320, 180).scaling_filter(Filter::Linear);
+//! assert_eq!(config.scaling_filter, Filter::Linear);
+//! ```
+//!
+//! ## Post-processing
+//!
+//! `Config::post_shader` replaces the fragment shader that copies the buffer to
+//! the window with WGSL of your own, which runs on the GPU for every window
+//! pixel. It gives CPU sketches cheap bloom, color grading or warping without
+//! touching their draw code, and is only applied on screen, never to saved
+//! frames. The shader defines `fs_main`, and can use these bindings, declared
+//! before it:
+//!
+//! - `frame_texture: texture_2d<f32>`, the frame drawn by the sketch
+//! - `frame_sampler: sampler`, filtering with the configured `Filter`
+//! - `locals.resolution: vec2<f32>`, the buffer size in pixels
+//! - `locals.time: f32`, the value of `app.time`
+//!
+//! ```
+//! use artimate::app::Config;
+//!
+//! // Vignette: darken towards the corners
+//! let config = Config::with_dims(600, 400).post_shader(
+//!     "@fragment
+//!      fn fs_main(@location(0) tex_coord: vec2<f32>) -> @location(0) vec4<f32> {
+//!          let color = textureSample(frame_texture, frame_sampler, tex_coord);
+//!          let d = distance(tex_coord, vec2<f32>(0.5, 0.5));
+//!          return vec4<f32>(color.rgb * (1.0 - d * d), color.a);
+//!      }",
+//! );
+//! ```
+
+use pixels::wgpu;
+use pixels::Pixels;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+/// How buffer pixels are sampled when the buffer is scaled to the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    /// Copy the nearest buffer pixel, scaling by whole multiples
+    #[default]
+    Nearest,
+    /// Interpolate between the four nearest buffer pixels, scaling to fit the window
+    Linear,
+}
+
+/// Size in bytes of the `Locals` uniform shared by the vertex and fragment shaders
+const LOCALS_SIZE: u64 = 32;
+
+/// Bindings and the vertex shader, which places the buffer quad in the window
+///
+/// A single triangle covers the quad; the scissor rectangle trims the overhang.
+const PRELUDE: &str = r"
+struct Locals {
+    scale: vec2<f32>,
+    offset: vec2<f32>,
+    resolution: vec2<f32>,
+    time: f32,
+}
+@group(0) @binding(0) var frame_texture: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+@group(0) @binding(2) var<uniform> locals: Locals;
+
+struct VertexOutput {
+    @location(0) tex_coord: vec2<f32>,
+    @builtin(position) position: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let position = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u)) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.tex_coord = fma(position, vec2<f32>(0.5, -0.5), vec2<f32>(0.5, 0.5));
+    out.position = vec4<f32>(position * locals.scale + locals.offset, 0.0, 1.0);
+    return out;
+}
+";
+
+/// Fragment shader used without `Config::post_shader`, a plain copy of the buffer
+const COPY_SHADER: &str = r"
+@fragment
+fn fs_main(@location(0) tex_coord: vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(frame_texture, frame_sampler, tex_coord);
+}
+";
+
+/// Renders the pixel buffer scaled to the surface through a fragment shader
+///
+/// Replaces the renderer built into `pixels` for `Filter::Linear` and post
+/// shaders. The buffer texture is recreated whenever the buffer is resized, so
+/// the bind group is built for every frame rather than kept.
+#[derive(Debug)]
+pub(crate) struct SurfaceRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    clear_color: wgpu::Color,
+    filter: Filter,
+    surface_size: (u32, u32),
+}
+
+impl SurfaceRenderer {
+    /// Creates a renderer for `pixels`, drawing onto a surface of `surface_size`
+    ///
+    /// # Returns
+    /// * `Ok(SurfaceRenderer)` - The renderer
+    /// * `Err(String)` - The compiler error if the post shader is invalid
+    pub(crate) fn new(
+        pixels: &Pixels,
+        surface_size: (u32, u32),
+        clear_color: wgpu::Color,
+        blend_state: wgpu::BlendState,
+        filter: Filter,
+        post_shader: Option<&str>,
+    ) -> Result<Self, String> {
+        let device = pixels.device();
+        let source = format!("{}{}", PRELUDE, post_shader.unwrap_or(COPY_SHADER));
+        // Invalid shaders are reported to the error scope instead of panicking
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("artimate_surface_shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let filter_mode = match filter {
+            Filter::Nearest => wgpu::FilterMode::Nearest,
+            Filter::Linear => wgpu::FilterMode::Linear,
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("artimate_surface_sampler"),
+            mag_filter: filter_mode,
+            min_filter: filter_mode,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("artimate_surface_uniform_buffer"),
+            size: LOCALS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("artimate_surface_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(LOCALS_SIZE),
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("artimate_surface_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("artimate_surface_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: pixels.render_texture_format(),
+                    blend: Some(blend_state),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+        if let Some(err) = block_on(device.pop_error_scope()) {
+            return Err(err.to_string());
+        }
+        Ok(Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            clear_color,
+            filter,
+            surface_size,
+        })
+    }
+
+    /// Sets the size of the surface in physical pixels
+    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+        self.surface_size = (width, height);
+    }
+
+    /// Uploads the buffer of `pixels` and draws it to the surface
+    ///
+    /// `time` is passed to the post shader as `locals.time`.
+    pub(crate) fn render(&self, pixels: &Pixels, time: f32) -> Result<(), pixels::Error> {
+        pixels.render_with(|encoder, render_target, context| {
+            let buffer = (context.texture_extent.width, context.texture_extent.height);
+            let (x, y, width, height) = placement(buffer, self.surface_size, self.filter);
+            let surface_width = self.surface_size.0.max(1) as f32;
+            let surface_height = self.surface_size.1.max(1) as f32;
+            // Clip space spans two units across the surface and points up
+            let locals = [
+                width as f32 / surface_width,
+                height as f32 / surface_height,
+                (2 * x + width as i32) as f32 / surface_width - 1.0,
+                1.0 - (2 * y + height as i32) as f32 / surface_height,
+                buffer.0 as f32,
+                buffer.1 as f32,
+                time,
+                0.0,
+            ];
+            let bytes: Vec<u8> = locals.iter().flat_map(|v| v.to_ne_bytes()).collect();
+            context.queue.write_buffer(&self.uniform_buffer, 0, &bytes);
+
+            let view = context
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = context
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("artimate_surface_bind_group"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: self.uniform_buffer.as_entire_binding(),
+                        },
+                    ],
+                });
+
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("artimate_surface_render_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: render_target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(self.clear_color),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            // The quad may overhang a window smaller than the buffer
+            let left = x.max(0) as u32;
+            let top = y.max(0) as u32;
+            let right = ((x + width as i32).max(0) as u32).min(self.surface_size.0);
+            let bottom = ((y + height as i32).max(0) as u32).min(self.surface_size.1);
+            if right > left && bottom > top {
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.set_scissor_rect(left, top, right - left, bottom - top);
+                pass.draw(0..3, 0..1);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Returns where the scaled buffer goes on the surface, as `(x, y, width, height)`
+///
+/// `Filter::Nearest` scales by the largest whole multiple that fits, at least 1,
+/// like the renderer built into `pixels`, so a window smaller than the buffer
+/// crops it. `Filter::Linear` scales to the largest size that fits. Either way
+/// the buffer is centered.
+fn placement(buffer: (u32, u32), surface: (u32, u32), filter: Filter) -> (i32, i32, u32, u32) {
+    let scale =
+        (surface.0 as f32 / buffer.0.max(1) as f32).min(surface.1 as f32 / buffer.1.max(1) as f32);
+    let scale = match filter {
+        Filter::Nearest => scale.floor().max(1.0),
+        Filter::Linear => scale,
+    };
+    let width = (buffer.0 as f32 * scale).round() as u32;
+    let height = (buffer.1 as f32 * scale).round() as u32;
+    (
+        (surface.0 as i32 - width as i32).div_euclid(2),
+        (surface.1 as i32 - height as i32).div_euclid(2),
+        width,
+        height,
+    )
+}
+
+/// Maps a position on the surface in physical pixels to buffer coordinates
+///
+/// Inverts `placement`, so positions in the borders around the buffer map to
+/// coordinates outside it.
+pub(crate) fn surface_to_buffer(
+    position: (f32, f32),
+    buffer: (u32, u32),
+    surface: (u32, u32),
+    filter: Filter,
+) -> (f32, f32) {
+    let (x, y, width, height) = placement(buffer, surface, filter);
+    (
+        (position.0 - x as f32) * buffer.0 as f32 / width.max(1) as f32,
+        (position.1 - y as f32) * buffer.1 as f32 / height.max(1) as f32,
+    )
+}
+
+/// Waits for a future that wgpu resolves immediately on native backends
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        std::thread::yield_now();
+    }
+}
@@ -0,0 +1,433 @@
+//! # Session Recording
+//!
+//! Records everything that makes a run of a sketch unique, so it can be played
+//! back exactly: the seed, the canvas size, the frame rate, frame skipping and
+//! export settings of the config, the time and skipped frames of every frame, and
+//! every key, mouse button, cursor, modifier, text and file drop event together
+//! with the frame it arrived before. Parameters changed from input handlers during
+//! the run change in the same way when the events are replayed.
+//!
+//! Sessions are plain text `.artimate` files. Record one with
+//! `App::record_session` or the `--record-session <path>` flag, and play it back
+//! with `App::replay` or `--replay <path>`:
+//!
+//! ```bash
+//! cargo run --release --example rose -- --record-session rose.artimate
+//! cargo run --release --example rose -- --replay rose.artimate
+//! ```
+//!
+//! Replaying ignores live input and stops after the recorded frames. Cursor
+//! positions are scaled to the canvas size, so replaying at a larger size with
+//! frame saving enabled re-exports the run at a higher resolution. The recorded
+//! export size and supersampling are only used if the sketch sets neither.
+//!
+//! The format is a header followed by one line per frame tick or event, each
+//! starting with the frame number and the value of `app.time`:
+//!
+//! ```text
+//! artimate session 1
+//! seed 7
+//! size 800 800
+//! fps 60
+//! deterministic none
+//! frame-skip none
+//! export-size none
+//! supersample 1
+//!
+//! 0 0 tick 0
+//! 1 0.012 move 400 300
+//! 1 0.016 tick 0
+//! 2 0.018 mouse-down Mouse Left
+//! 2 0.020 mouse-up Mouse Left
+//! 2 0.021 key-down 'r'
+//! 2 0.021 text r
+//! 2 0.033 tick 1
+//! ```
+
+use crate::app::{Config, FrameSkip};
+use crate::input::{parse_input, Input};
+use crate::overlay::mouse_button_name;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use winit::event::MouseButton;
+use winit::keyboard::{Key, ModifiersState};
+
+/// First line of every session file
+const SESSION_HEADER: &str = "artimate session 1";
+
+/// An input event a session replays
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A key was pressed, or held long enough to repeat
+    KeyDown {
+        /// The logical key
+        key: Key,
+        /// Whether this is a repeat of a held key
+        repeat: bool,
+    },
+    /// A key was released
+    KeyUp(Key),
+    /// A mouse button was pressed
+    MouseDown(MouseButton),
+    /// A mouse button was released
+    MouseUp(MouseButton),
+    /// The cursor moved to a position in logical pixels
+    MouseMove(f32, f32),
+    /// The held modifier keys changed
+    Modifiers(ModifiersState),
+    /// Text was typed, without control characters
+    Text(String),
+    /// A file was dropped onto the window
+    FileDrop(PathBuf),
+}
+
+/// An event and when it arrived
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedEvent {
+    /// Frame the event arrived before
+    pub frame: u32,
+    /// Value of `app.time` when the event arrived
+    pub time: f32,
+    /// The event
+    pub event: Event,
+}
+
+/// The clock of a single drawn frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    /// Index of the frame
+    pub frame: u32,
+    /// Value of `app.time` while the frame was drawn
+    pub time: f32,
+    /// Number of frame budget ticks missed before the frame
+    pub missed: u32,
+}
+
+/// The config settings a session records besides the seed and canvas size
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Target frame rate, `Config::fps`
+    pub fps: Option<f32>,
+    /// Frame rate of deterministic time, `Config::deterministic`
+    pub deterministic: Option<f32>,
+    /// What happens to frames over budget, `Config::frame_skip`
+    pub frame_skip: FrameSkip,
+    /// Size frames are exported at, `Config::export_size`
+    pub export_size: Option<(u32, u32)>,
+    /// Factor saved frames are rendered larger by, `Config::supersample`
+    pub supersample: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fps: None,
+            deterministic: None,
+            frame_skip: FrameSkip::None,
+            export_size: None,
+            supersample: 1,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the settings of a config
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            fps: config.fps,
+            deterministic: config.deterministic,
+            frame_skip: config.frame_skip,
+            export_size: config.export_size,
+            supersample: config.supersample,
+        }
+    }
+
+    /// Sets the recorded frame rate and frame skipping on `config`
+    ///
+    /// The export size and supersampling are only set if `config` has neither, so
+    /// a replay can re-export the run differently.
+    pub fn apply(&self, config: &mut Config) {
+        config.fps = self.fps;
+        config.deterministic = self.deterministic;
+        config.frame_skip = self.frame_skip;
+        if config.export_size.is_none() && config.supersample <= 1 {
+            config.export_size = self.export_size;
+            config.supersample = self.supersample.max(1);
+        }
+    }
+}
+
+/// A recorded run of a sketch
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Session {
+    /// Root seed of the sketch's random streams
+    pub seed: u64,
+    /// Width and height of the canvas in pixels
+    pub size: (u32, u32),
+    /// Config settings of the run, None for sessions recorded without them
+    pub settings: Option<Settings>,
+    /// Clock of every frame drawn, in order
+    pub ticks: Vec<Tick>,
+    /// Input events, in the order they arrived
+    pub events: Vec<TimedEvent>,
+}
+
+impl Session {
+    /// Creates an empty session for a sketch with the given seed and canvas size
+    pub fn new(seed: u64, size: (u32, u32)) -> Self {
+        Self {
+            seed,
+            size,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of frames from the start of the session to its last tick
+    pub fn frames(&self) -> u32 {
+        self.ticks.last().map_or(0, |tick| tick.frame + 1)
+    }
+
+    /// Returns the tick of `frame`, if it was drawn
+    pub fn tick(&self, frame: u32) -> Option<&Tick> {
+        let i = self.ticks.partition_point(|tick| tick.frame < frame);
+        self.ticks.get(i).filter(|tick| tick.frame == frame)
+    }
+
+    /// Adds an event, unless it is a key that cannot be written to a session file
+    pub fn push_event(&mut self, frame: u32, time: f32, event: Event) {
+        let writable = match &event {
+            Event::KeyDown { key, .. } | Event::KeyUp(key) => key_code(key).is_some(),
+            _ => true,
+        };
+        if writable {
+            self.events.push(TimedEvent { frame, time, event });
+        }
+    }
+
+    /// Parses the contents of a session file
+    ///
+    /// # Returns
+    /// * `Ok(Session)` - The parsed session
+    /// * `Err(io::Error)` - If the header is missing or a line cannot be parsed
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines().enumerate();
+        let invalid = |n: usize, message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("session line {}: {}", n + 1, message),
+            )
+        };
+        if lines.next().map(|(_, line)| line) != Some(SESSION_HEADER) {
+            return Err(invalid(0, "expected `artimate session 1`"));
+        }
+        let mut session = Session::default();
+        for (n, line) in lines.by_ref() {
+            let Some((name, value)) = line.split_once(' ') else {
+                if line.is_empty() {
+                    break;
+                }
+                return Err(invalid(n, "expected `name value`"));
+            };
+            match name {
+                "seed" => session.seed = value.parse().map_err(|_| invalid(n, "invalid seed"))?,
+                "size" => {
+                    session.size = value
+                        .split_once(' ')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or_else(|| invalid(n, "expected `size <width> <height>`"))?
+                }
+                "fps" | "deterministic" | "frame-skip" | "export-size" | "supersample" => {
+                    let settings = session.settings.get_or_insert_with(Settings::default);
+                    let invalid = || invalid(n, &format!("invalid {}", name));
+                    let rate = || match value {
+                        "none" => Ok(None),
+                        value => value.parse().map(Some).map_err(|_| invalid()),
+                    };
+                    match name {
+                        "fps" => settings.fps = rate()?,
+                        "deterministic" => settings.deterministic = rate()?,
+                        "frame-skip" => {
+                            settings.frame_skip = parse_frame_skip(value).ok_or_else(invalid)?
+                        }
+                        "export-size" if value == "none" => settings.export_size = None,
+                        "export-size" => {
+                            settings.export_size = value
+                                .split_once(' ')
+                                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                                .ok_or_else(invalid)
+                                .map(Some)?
+                        }
+                        _ => settings.supersample = value.parse().map_err(|_| invalid())?,
+                    }
+                }
+                // Unknown header fields are ignored, so newer files stay readable
+                _ => {}
+            }
+        }
+        for (n, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(4, ' ');
+            let (Some(frame), Some(time), Some(kind)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid(n, "expected `<frame> <time> <kind>`"));
+            };
+            let frame = frame.parse().map_err(|_| invalid(n, "invalid frame"))?;
+            let time = time.parse().map_err(|_| invalid(n, "invalid time"))?;
+            let arg = fields.next().unwrap_or("");
+            let key = || parse_key(arg).ok_or_else(|| invalid(n, &format!("unknown key {}", arg)));
+            let event = match kind {
+                "tick" => {
+                    let missed = arg.parse().map_err(|_| invalid(n, "invalid tick"))?;
+                    session.ticks.push(Tick {
+                        frame,
+                        time,
+                        missed,
+                    });
+                    continue;
+                }
+                "key-down" => Event::KeyDown {
+                    key: key()?,
+                    repeat: false,
+                },
+                "key-repeat" => Event::KeyDown {
+                    key: key()?,
+                    repeat: true,
+                },
+                "key-up" => Event::KeyUp(key()?),
+                "mouse-down" | "mouse-up" => match parse_input(arg) {
+                    Some(Input::Mouse(button)) if kind == "mouse-down" => Event::MouseDown(button),
+                    Some(Input::Mouse(button)) => Event::MouseUp(button),
+                    _ => return Err(invalid(n, &format!("unknown mouse button {}", arg))),
+                },
+                "move" => arg
+                    .split_once(' ')
+                    .and_then(|(x, y)| Some(Event::MouseMove(x.parse().ok()?, y.parse().ok()?)))
+                    .ok_or_else(|| invalid(n, "expected `move <x> <y>`"))?,
+                "text" if !arg.is_empty() => Event::Text(arg.to_string()),
+                "drop" if !arg.is_empty() => Event::FileDrop(PathBuf::from(arg)),
+                "modifiers" => {
+                    let bits = arg.parse().map_err(|_| invalid(n, "invalid modifiers"))?;
+                    Event::Modifiers(ModifiersState::from_bits_truncate(bits))
+                }
+                other => return Err(invalid(n, &format!("unknown event {}", other))),
+            };
+            session.events.push(TimedEvent { frame, time, event });
+        }
+        Ok(session)
+    }
+
+    /// Reads and parses a session file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Writes the session to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", SESSION_HEADER)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "size {} {}", self.size.0, self.size.1)?;
+        if let Some(settings) = &self.settings {
+            let rate = |rate: Option<f32>| rate.map_or("none".to_string(), |r| r.to_string());
+            writeln!(f, "fps {}", rate(settings.fps))?;
+            writeln!(f, "deterministic {}", rate(settings.deterministic))?;
+            writeln!(f, "frame-skip {}", frame_skip_name(settings.frame_skip))?;
+            match settings.export_size {
+                Some((w, h)) => writeln!(f, "export-size {} {}", w, h)?,
+                None => writeln!(f, "export-size none")?,
+            }
+            writeln!(f, "supersample {}", settings.supersample)?;
+        }
+        writeln!(f)?;
+        // Interleave ticks and events in the order they happened
+        let mut events = self.events.iter().peekable();
+        for tick in &self.ticks {
+            while let Some(event) = events.next_if(|event| event.frame <= tick.frame) {
+                writeln!(f, "{}", event)?;
+            }
+            writeln!(f, "{} {} tick {}", tick.frame, tick.time, tick.missed)?;
+        }
+        for event in events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.frame, self.time)?;
+        let code = |key: &Key| key_code(key).unwrap_or_default();
+        match &self.event {
+            Event::KeyDown { key, repeat: false } => write!(f, "key-down {}", code(key)),
+            Event::KeyDown { key, repeat: true } => write!(f, "key-repeat {}", code(key)),
+            Event::KeyUp(key) => write!(f, "key-up {}", code(key)),
+            Event::MouseDown(button) => write!(f, "mouse-down {}", mouse_button_name(button)),
+            Event::MouseUp(button) => write!(f, "mouse-up {}", mouse_button_name(button)),
+            Event::MouseMove(x, y) => write!(f, "move {} {}", x, y),
+            Event::Modifiers(state) => write!(f, "modifiers {}", state.bits()),
+            Event::Text(text) => write!(f, "text {}", text),
+            Event::FileDrop(path) => write!(f, "drop {}", path.display()),
+        }
+    }
+}
+
+/// Returns how a frame skipping policy is written in a session file
+fn frame_skip_name(frame_skip: FrameSkip) -> &'static str {
+    match frame_skip {
+        FrameSkip::None => "none",
+        FrameSkip::SkipFrames => "skip-frames",
+        FrameSkip::SkipPresent => "skip-present",
+        FrameSkip::CatchUp => "catch-up",
+    }
+}
+
+/// Parses a frame skipping policy written by `frame_skip_name`
+fn parse_frame_skip(name: &str) -> Option<FrameSkip> {
+    [
+        FrameSkip::None,
+        FrameSkip::SkipFrames,
+        FrameSkip::SkipPresent,
+        FrameSkip::CatchUp,
+    ]
+    .into_iter()
+    .find(|&frame_skip| frame_skip_name(frame_skip) == name)
+}
+
+/// Returns how a key is written in a session file, quoted if it is a character
+///
+/// Named keys are written by name if a bindings file could name them, and
+/// otherwise cannot be recorded.
+fn key_code(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(c) => Some(format!("'{}'", c)),
+        Key::Named(named) => {
+            let name = format!("{:?}", named);
+            (parse_input(&name) == Some(Input::Key(key.clone()))).then_some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Parses a key written by `key_code`
+fn parse_key(code: &str) -> Option<Key> {
+    if let Some(c) = code
+        .strip_prefix('\'')
+        .and_then(|code| code.strip_suffix('\''))
+    {
+        return Some(Key::Character(c.into()));
+    }
+    match parse_input(code)? {
+        Input::Key(key) => Some(key),
+        Input::Mouse(_) => None,
+    }
+}
@@ -0,0 +1,450 @@
+//! # Starter Sketches
+//!
+//! Ready-made starting points for common generative art genres. Each constructor
+//! returns a fully wired `App` whose model has public fields, so it can be tweaked
+//! (or have more handlers registered) before calling `run()`.
+//!
+//! ```rust,no_run
+//! use artimate::app::{Config, Error};
+//! use artimate::starters;
+//!
+//! fn main() -> Result<(), Error> {
+//!     let mut app = starters::flow_field(Config::with_dims(800, 800));
+//!     app.model.speed = 2.5;
+//!     app.run()
+//! }
+//! ```
+
+use crate::app::{App, AppMode, Config};
+use crate::buffer::clear;
+use crate::noise::{Noise, Simplex};
+use crate::random::Rng;
+use winit::event::MouseButton;
+use winit::keyboard::Key;
+
+/// Creates a flow field sketch: particles tracing trails through a smooth angle field
+///
+/// Press `r` to scatter the particles to new random positions.
+pub fn flow_field(config: Config) -> App<AppMode, FlowField> {
+    let model = FlowField::new(config.width, config.height, 1500, 1);
+    let mut app = App::app(model, config, FlowField::update, FlowField::draw);
+    app.on_key_press(Key::Character("r".into()), |app| {
+        let (w, h) = app.wh();
+        app.model.seed += 1;
+        app.model.scatter(w, h);
+    });
+    app
+}
+
+/// Creates Conway's Game of Life on a grid of 8 pixel cells filling the window
+///
+/// Press `r` to randomize the board, `space` to pause and click to toggle a cell.
+pub fn game_of_life(config: Config) -> App<AppMode, Life> {
+    let model = Life::new(config.width / 8, config.height / 8, 8, 1);
+    let mut app = App::app(model, config, Life::update, Life::draw);
+    app.on_key_press(Key::Character("r".into()), |app| {
+        app.model.seed += 1;
+        app.model.randomize();
+    });
+    app.on_key_press(Key::Named(winit::keyboard::NamedKey::Space), |app| {
+        app.model.paused = !app.model.paused;
+    });
+    app.on_mouse_press(MouseButton::Left, |app| {
+        let (x, y) = app.mouse_position;
+        app.model.toggle_at(x, y);
+    });
+    app
+}
+
+/// Creates a flocking simulation using separation, alignment and cohesion
+///
+/// Press `r` to scatter the flock.
+pub fn boids(config: Config) -> App<AppMode, Boids> {
+    let model = Boids::new(config.width, config.height, 300, 1);
+    let mut app = App::app(model, config, Boids::update, Boids::draw);
+    app.on_key_press(Key::Character("r".into()), |app| {
+        let (w, h) = app.wh();
+        app.model.seed += 1;
+        app.model.scatter(w, h);
+    });
+    app
+}
+
+/// Model for the `flow_field` starter
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    /// Particle positions, each with its recent history
+    pub particles: Vec<Vec<(f32, f32)>>,
+    /// Number of positions kept per particle trail
+    pub trail: usize,
+    /// Distance moved per frame in pixels
+    pub speed: f32,
+    /// Spatial frequency of the angle field
+    pub scale: f32,
+    /// Background color as RGBA
+    pub background: [u8; 4],
+    /// Trail color as RGBA, the alpha fades along the trail
+    pub color: [u8; 4],
+    /// Seed for particle placement and the angle field
+    pub seed: u64,
+}
+
+impl FlowField {
+    /// Creates a flow field with `count` particles scattered over a `width` x `height` canvas
+    pub fn new(width: u32, height: u32, count: usize, seed: u64) -> Self {
+        let mut field = Self {
+            particles: vec![Vec::new(); count],
+            trail: 24,
+            speed: 1.5,
+            scale: 0.004,
+            background: [12, 12, 20, 255],
+            color: [240, 200, 120, 255],
+            seed,
+        };
+        field.scatter(width, height);
+        field
+    }
+
+    /// Moves every particle to a new random position
+    pub fn scatter(&mut self, width: u32, height: u32) {
+        let mut rng = Rng::new(self.seed);
+        for particle in self.particles.iter_mut() {
+            particle.clear();
+            particle.push((
+                rng.next_f32() * width as f32,
+                rng.next_f32() * height as f32,
+            ));
+        }
+    }
+
+    /// Returns the angle of the field at `(x, y)`
+    pub fn angle(&self, x: f32, y: f32) -> f32 {
+        self.angle_in(&Simplex::new(self.seed), x, y)
+    }
+
+    /// Returns the angle at `(x, y)` of a field sampled from `noise`
+    fn angle_in(&self, noise: &Simplex, x: f32, y: f32) -> f32 {
+        noise.get2(x * self.scale, y * self.scale) * std::f32::consts::TAU
+    }
+
+    fn update(app: &App<AppMode, Self>, mut model: Self) -> Self {
+        let (w, h) = app.wh_f32();
+        let mut rng = Rng::new(model.seed ^ app.frame_count as u64);
+        let noise = Simplex::new(model.seed);
+        for i in 0..model.particles.len() {
+            let (x, y) = *model.particles[i].last().unwrap();
+            let a = model.angle_in(&noise, x, y);
+            let (nx, ny) = (x + a.cos() * model.speed, y + a.sin() * model.speed);
+            let particle = &mut model.particles[i];
+            if nx < 0.0 || ny < 0.0 || nx >= w || ny >= h {
+                particle.clear();
+                particle.push((rng.next_f32() * w, rng.next_f32() * h));
+                continue;
+            }
+            particle.push((nx, ny));
+            if particle.len() > model.trail {
+                particle.remove(0);
+            }
+        }
+        model
+    }
+
+    fn draw(app: &App<AppMode, Self>, model: &Self) -> Vec<u8> {
+        let (w, h) = app.wh();
+        let mut pixels = filled(w, h, model.background);
+        for particle in &model.particles {
+            let n = particle.len() as f32;
+            for (i, &(x, y)) in particle.iter().enumerate() {
+                let mut color = model.color;
+                color[3] = ((i + 1) as f32 / n * color[3] as f32) as u8;
+                blend(&mut pixels, w, h, x as i32, y as i32, color);
+            }
+        }
+        pixels
+    }
+}
+
+/// Model for the `game_of_life` starter
+#[derive(Clone, Debug)]
+pub struct Life {
+    /// Number of columns in the grid
+    pub cols: u32,
+    /// Number of rows in the grid
+    pub rows: u32,
+    /// Cell states in row-major order, true is alive
+    pub cells: Vec<bool>,
+    /// Size of a cell in pixels
+    pub cell_size: u32,
+    /// Color of live cells as RGBA
+    pub alive: [u8; 4],
+    /// Color of dead cells as RGBA
+    pub dead: [u8; 4],
+    /// Fraction of cells alive after `randomize`
+    pub density: f32,
+    /// If true, generations stop advancing
+    pub paused: bool,
+    /// Seed used by `randomize`
+    pub seed: u64,
+}
+
+impl Life {
+    /// Creates a randomized `cols` x `rows` board drawn with `cell_size` pixel cells
+    pub fn new(cols: u32, rows: u32, cell_size: u32, seed: u64) -> Self {
+        let mut life = Self {
+            cols,
+            rows,
+            cells: vec![false; (cols * rows) as usize],
+            cell_size,
+            alive: [230, 230, 230, 255],
+            dead: [15, 15, 15, 255],
+            density: 0.25,
+            paused: false,
+            seed,
+        };
+        life.randomize();
+        life
+    }
+
+    /// Fills the board with random cells, `density` of them alive
+    pub fn randomize(&mut self) {
+        let mut rng = Rng::new(self.seed);
+        for cell in self.cells.iter_mut() {
+            *cell = rng.next_f32() < self.density;
+        }
+    }
+
+    /// Flips the cell under the pixel position `(x, y)`
+    pub fn toggle_at(&mut self, x: f32, y: f32) {
+        let col = (x / self.cell_size as f32) as u32;
+        let row = (y / self.cell_size as f32) as u32;
+        if col < self.cols && row < self.rows {
+            let i = (row * self.cols + col) as usize;
+            self.cells[i] = !self.cells[i];
+        }
+    }
+
+    /// Returns the number of live neighbours of a cell, wrapping at the edges
+    pub fn neighbours(&self, col: u32, row: u32) -> u32 {
+        let mut count = 0;
+        for dy in [self.rows - 1, 0, 1] {
+            for dx in [self.cols - 1, 0, 1] {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let c = (col + dx) % self.cols;
+                let r = (row + dy) % self.rows;
+                count += self.cells[(r * self.cols + c) as usize] as u32;
+            }
+        }
+        count
+    }
+
+    /// Advances the board by one generation
+    pub fn step(&mut self) {
+        let next = (0..self.rows)
+            .flat_map(|row| (0..self.cols).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let alive = self.cells[(row * self.cols + col) as usize];
+                matches!((alive, self.neighbours(col, row)), (true, 2) | (_, 3))
+            })
+            .collect();
+        self.cells = next;
+    }
+
+    fn update(_app: &App<AppMode, Self>, mut model: Self) -> Self {
+        if !model.paused {
+            model.step();
+        }
+        model
+    }
+
+    fn draw(app: &App<AppMode, Self>, model: &Self) -> Vec<u8> {
+        let (w, h) = app.wh();
+        let mut pixels = filled(w, h, model.dead);
+        for y in 0..h {
+            let row = y / model.cell_size;
+            if row >= model.rows {
+                break;
+            }
+            for x in 0..w.min(model.cols * model.cell_size) {
+                let col = x / model.cell_size;
+                if model.cells[(row * model.cols + col) as usize] {
+                    let i = ((y * w + x) * 4) as usize;
+                    pixels[i..i + 4].copy_from_slice(&model.alive);
+                }
+            }
+        }
+        pixels
+    }
+}
+
+/// A single member of the `Boids` flock
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Boid {
+    /// Position in pixels
+    pub position: (f32, f32),
+    /// Velocity in pixels per frame
+    pub velocity: (f32, f32),
+}
+
+/// Model for the `boids` starter
+#[derive(Clone, Debug)]
+pub struct Boids {
+    /// Members of the flock
+    pub flock: Vec<Boid>,
+    /// Radius within which other boids are considered neighbours
+    pub vision: f32,
+    /// Distance below which boids steer away from each other
+    pub separation: f32,
+    /// Weight of the separation rule
+    pub separation_weight: f32,
+    /// Weight of the alignment rule
+    pub alignment_weight: f32,
+    /// Weight of the cohesion rule
+    pub cohesion_weight: f32,
+    /// Maximum speed in pixels per frame
+    pub max_speed: f32,
+    /// Background color as RGBA
+    pub background: [u8; 4],
+    /// Boid color as RGBA
+    pub color: [u8; 4],
+    /// Seed used by `scatter`
+    pub seed: u64,
+}
+
+impl Boids {
+    /// Creates a flock of `count` boids scattered over a `width` x `height` canvas
+    pub fn new(width: u32, height: u32, count: usize, seed: u64) -> Self {
+        let mut boids = Self {
+            flock: vec![
+                Boid {
+                    position: (0.0, 0.0),
+                    velocity: (0.0, 0.0),
+                };
+                count
+            ],
+            vision: 40.0,
+            separation: 12.0,
+            separation_weight: 0.05,
+            alignment_weight: 0.05,
+            cohesion_weight: 0.005,
+            max_speed: 3.0,
+            background: [240, 238, 230, 255],
+            color: [30, 30, 40, 255],
+            seed,
+        };
+        boids.scatter(width, height);
+        boids
+    }
+
+    /// Gives every boid a new random position and velocity
+    pub fn scatter(&mut self, width: u32, height: u32) {
+        let mut rng = Rng::new(self.seed);
+        for boid in self.flock.iter_mut() {
+            boid.position = (
+                rng.next_f32() * width as f32,
+                rng.next_f32() * height as f32,
+            );
+            let a = rng.next_f32() * std::f32::consts::TAU;
+            boid.velocity = (a.cos() * self.max_speed, a.sin() * self.max_speed);
+        }
+    }
+
+    fn update(app: &App<AppMode, Self>, mut model: Self) -> Self {
+        let (w, h) = app.wh_f32();
+        let flock = model.flock.clone();
+        for boid in model.flock.iter_mut() {
+            let (mut sep, mut align, mut center, mut n) = ((0.0, 0.0), (0.0, 0.0), (0.0, 0.0), 0.0);
+            for other in &flock {
+                let dx = other.position.0 - boid.position.0;
+                let dy = other.position.1 - boid.position.1;
+                let d2 = dx * dx + dy * dy;
+                if d2 == 0.0 || d2 > model.vision * model.vision {
+                    continue;
+                }
+                if d2 < model.separation * model.separation {
+                    sep = (sep.0 - dx, sep.1 - dy);
+                }
+                align = (align.0 + other.velocity.0, align.1 + other.velocity.1);
+                center = (center.0 + other.position.0, center.1 + other.position.1);
+                n += 1.0;
+            }
+            let mut v = boid.velocity;
+            v.0 += sep.0 * model.separation_weight;
+            v.1 += sep.1 * model.separation_weight;
+            if n > 0.0 {
+                v.0 += (align.0 / n - v.0) * model.alignment_weight;
+                v.1 += (align.1 / n - v.1) * model.alignment_weight;
+                v.0 += (center.0 / n - boid.position.0) * model.cohesion_weight;
+                v.1 += (center.1 / n - boid.position.1) * model.cohesion_weight;
+            }
+            let speed = (v.0 * v.0 + v.1 * v.1).sqrt();
+            if speed > model.max_speed {
+                v = (v.0 / speed * model.max_speed, v.1 / speed * model.max_speed);
+            }
+            boid.velocity = v;
+            boid.position = (
+                (boid.position.0 + v.0).rem_euclid(w),
+                (boid.position.1 + v.1).rem_euclid(h),
+            );
+        }
+        model
+    }
+
+    fn draw(app: &App<AppMode, Self>, model: &Self) -> Vec<u8> {
+        let (w, h) = app.wh();
+        let mut pixels = filled(w, h, model.background);
+        for boid in &model.flock {
+            let (x, y) = boid.position;
+            let speed = (boid.velocity.0.powi(2) + boid.velocity.1.powi(2))
+                .sqrt()
+                .max(f32::EPSILON);
+            let (dx, dy) = (boid.velocity.0 / speed, boid.velocity.1 / speed);
+            // A short line from the tail to the head, thickened near the head
+            for i in 0..8 {
+                let t = i as f32;
+                let (px, py) = (x - dx * t, y - dy * t);
+                blend(&mut pixels, w, h, px as i32, py as i32, model.color);
+                if i < 3 {
+                    blend(
+                        &mut pixels,
+                        w,
+                        h,
+                        (px - dy) as i32,
+                        (py + dx) as i32,
+                        model.color,
+                    );
+                    blend(
+                        &mut pixels,
+                        w,
+                        h,
+                        (px + dy) as i32,
+                        (py - dx) as i32,
+                        model.color,
+                    );
+                }
+            }
+        }
+        pixels
+    }
+}
+
+/// Returns a `width` x `height` RGBA buffer filled with `color`
+fn filled(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+    let mut pixels = vec![0; (width * height * 4) as usize];
+    clear(&mut pixels, color);
+    pixels
+}
+
+/// Alpha blends `color` over the pixel at `(x, y)`, ignoring positions outside the buffer
+fn blend(pixels: &mut [u8], width: u32, height: u32, x: i32, y: i32, color: [u8; 4]) {
+    if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+        return;
+    }
+    let i = ((y as u32 * width + x as u32) * 4) as usize;
+    let a = color[3] as u32;
+    for c in 0..3 {
+        pixels[i + c] = ((color[c] as u32 * a + pixels[i + c] as u32 * (255 - a)) / 255) as u8;
+    }
+    pixels[i + 3] = pixels[i + 3].max(color[3]);
+}
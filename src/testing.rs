@@ -0,0 +1,412 @@
+//! # Testing Helpers
+//!
+//! Utilities for regression testing deterministic sketches without a display.
+//! Instead of storing golden images, a test can record the hash of a frame once
+//! and assert that refactors keep producing the identical buffer.
+//!
+//! ```rust
+//! use artimate::app::{App, Config};
+//! use artimate::testing::assert_frame_hash;
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     vec![255; (app.config.width * app.config.height * 4) as usize]
+//! }
+//!
+//! let mut app = App::sketch(Config::with_dims(4, 4), draw);
+//! assert_frame_hash(&mut app, 0x2e54_fae2_959d_bb84);
+//! ```
+//!
+//! When a change is expected to alter output, `compare_dirs` compares two folders
+//! of rendered frames and produces a report of per-frame differences:
+//!
+//! ```rust,no_run
+//! use artimate::testing::compare_dirs;
+//!
+//! let report = compare_dirs("renders/before", "renders/after").unwrap();
+//! report.write_html("renders/report").unwrap();
+//! for frame in report.changed(0.99) {
+//!     println!("{}: ssim {:.4}", frame.name, frame.ssim);
+//! }
+//! ```
+
+use crate::app::App;
+use crate::buffer::Image;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Returns the 64-bit xxHash (XXH64, seed 0) of an RGBA buffer
+///
+/// The hash only depends on the bytes of the buffer, so it is stable across
+/// platforms, compiler versions and runs.
+pub fn hash_frame(frame: &[u8]) -> u64 {
+    xxh64(frame, 0)
+}
+
+/// Panics with a descriptive message if the app's current frame does not hash to `expected`
+///
+/// The frame is rendered with `App::frame_hash`, so no window or event loop is
+/// created.
+pub fn assert_frame_hash<Mode, M: Clone>(app: &mut App<Mode, M>, expected: u64) {
+    let actual = app.frame_hash();
+    assert!(
+        actual == expected,
+        "frame {} of \"{}\" ({}x{}) hashed to {:#018x}, expected {:#018x}",
+        app.frame_count,
+        app.config.window_title,
+        app.config.width,
+        app.config.height,
+        actual,
+        expected
+    );
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME_1)
+}
+
+fn merge_round(acc: u64, val: u64) -> u64 {
+    (acc ^ round(0, val))
+        .wrapping_mul(PRIME_1)
+        .wrapping_add(PRIME_4)
+}
+
+fn xxh64(input: &[u8], seed: u64) -> u64 {
+    let mut rest = input;
+    let mut h = if input.len() >= 32 {
+        let mut v = [
+            seed.wrapping_add(PRIME_1).wrapping_add(PRIME_2),
+            seed.wrapping_add(PRIME_2),
+            seed,
+            seed.wrapping_sub(PRIME_1),
+        ];
+        while rest.len() >= 32 {
+            for (i, acc) in v.iter_mut().enumerate() {
+                *acc = round(*acc, read_u64(&rest[i * 8..]));
+            }
+            rest = &rest[32..];
+        }
+        let mut h = v[0]
+            .rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+        for acc in v {
+            h = merge_round(h, acc);
+        }
+        h
+    } else {
+        seed.wrapping_add(PRIME_5)
+    };
+
+    h = h.wrapping_add(input.len() as u64);
+    while rest.len() >= 8 {
+        h ^= round(0, read_u64(rest));
+        h = h
+            .rotate_left(27)
+            .wrapping_mul(PRIME_1)
+            .wrapping_add(PRIME_4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        h ^= (read_u32(rest) as u64).wrapping_mul(PRIME_1);
+        h = h
+            .rotate_left(23)
+            .wrapping_mul(PRIME_2)
+            .wrapping_add(PRIME_3);
+        rest = &rest[4..];
+    }
+    for &byte in rest {
+        h ^= (byte as u64).wrapping_mul(PRIME_5);
+        h = h.rotate_left(11).wrapping_mul(PRIME_1);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME_3);
+    h ^ (h >> 32)
+}
+
+/// Comparison of a single frame present in both directories
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameDiff {
+    /// File name of the frame
+    pub name: String,
+    /// Path of the frame in the old directory
+    pub old: PathBuf,
+    /// Path of the frame in the new directory
+    pub new: PathBuf,
+    /// Structural similarity of the two frames' luminance, 1.0 means identical
+    pub ssim: f64,
+    /// Mean absolute difference per channel, from 0 to 255
+    pub mean_diff: f64,
+    /// Largest difference in any channel of any pixel
+    pub max_diff: u8,
+    /// Number of pixels that differ in at least one channel
+    pub differing_pixels: usize,
+    /// Amplified absolute difference image, None if the dimensions differ
+    pub diff: Option<Image>,
+}
+
+impl FrameDiff {
+    /// Returns true if the two frames are byte for byte identical
+    pub fn is_identical(&self) -> bool {
+        self.diff.is_some() && self.max_diff == 0
+    }
+}
+
+/// Result of comparing two directories of rendered frames
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Frames present in both directories, sorted by name
+    pub frames: Vec<FrameDiff>,
+    /// PNG files only present in the old directory
+    pub only_old: Vec<String>,
+    /// PNG files only present in the new directory
+    pub only_new: Vec<String>,
+}
+
+impl Report {
+    /// Returns the frames whose SSIM is below `threshold`
+    pub fn changed(&self, threshold: f64) -> impl Iterator<Item = &FrameDiff> {
+        self.frames.iter().filter(move |f| f.ssim < threshold)
+    }
+
+    /// Writes `index.html` and a `diff_<name>.png` image for every differing frame to `dir`
+    pub fn write_html(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let mut rows = String::new();
+        for frame in &self.frames {
+            let diff_cell = match &frame.diff {
+                Some(diff) if frame.max_diff > 0 => {
+                    let name = format!("diff_{}", frame.name);
+                    diff.save_png(dir.join(&name))?;
+                    format!("<img src=\"{}\">", escape_html(&name))
+                }
+                Some(_) => "identical".to_string(),
+                None => "size mismatch".to_string(),
+            };
+            rows.push_str(&format!(
+                "<tr class=\"{}\"><td>{}</td><td>{:.4}</td><td>{:.3}</td><td>{}</td><td>{}</td>\
+                 <td><img src=\"{}\"></td><td><img src=\"{}\"></td><td>{}</td></tr>\n",
+                if frame.is_identical() {
+                    "same"
+                } else {
+                    "changed"
+                },
+                escape_html(&frame.name),
+                frame.ssim,
+                frame.mean_diff,
+                frame.max_diff,
+                frame.differing_pixels,
+                escape_html(&file_url(&frame.old)),
+                escape_html(&file_url(&frame.new)),
+                diff_cell
+            ));
+        }
+        for (name, side) in self
+            .only_old
+            .iter()
+            .map(|n| (n, "old"))
+            .chain(self.only_new.iter().map(|n| (n, "new")))
+        {
+            rows.push_str(&format!(
+                "<tr class=\"changed\"><td>{}</td><td colspan=\"7\">only in {}</td></tr>\n",
+                escape_html(name),
+                side
+            ));
+        }
+        let html = format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>artimate frame diff</title>\n\
+             <style>body{{font-family:sans-serif}} img{{max-width:240px}} \
+             td{{padding:4px;border-bottom:1px solid #ccc}} .changed td:first-child{{color:#c00}}</style>\n\
+             </head><body>\n<h1>{} of {} frames changed</h1>\n<table>\n\
+             <tr><th>frame</th><th>ssim</th><th>mean diff</th><th>max diff</th><th>pixels</th>\
+             <th>old</th><th>new</th><th>diff</th></tr>\n{}</table>\n</body></html>\n",
+            self.frames.iter().filter(|f| !f.is_identical()).count()
+                + self.only_old.len()
+                + self.only_new.len(),
+            self.frames.len() + self.only_old.len() + self.only_new.len(),
+            rows
+        );
+        fs::write(dir.join("index.html"), html)
+    }
+}
+
+/// Compares every PNG frame in `old` with the frame of the same name in `new`
+///
+/// Frames are matched by file name. For each pair the report contains the SSIM of
+/// the luminance, pixel difference statistics and an amplified difference image.
+pub fn compare_dirs(old: impl AsRef<Path>, new: impl AsRef<Path>) -> io::Result<Report> {
+    let (old, new) = (old.as_ref(), new.as_ref());
+    let old_names = png_names(old)?;
+    let new_names = png_names(new)?;
+    let mut report = Report::default();
+    for name in &old_names {
+        if new_names.contains(name) {
+            let (a, b) = (old.join(name), new.join(name));
+            let diff = compare_images(&Image::load_png(&a)?, &Image::load_png(&b)?);
+            report.frames.push(FrameDiff {
+                name: name.clone(),
+                old: a,
+                new: b,
+                ..diff
+            });
+        } else {
+            report.only_old.push(name.clone());
+        }
+    }
+    report.only_new = new_names
+        .into_iter()
+        .filter(|name| !old_names.contains(name))
+        .collect();
+    Ok(report)
+}
+
+/// Compares two images, leaving the name and paths of the result empty
+pub fn compare_images(old: &Image, new: &Image) -> FrameDiff {
+    let mut result = FrameDiff {
+        name: String::new(),
+        old: PathBuf::new(),
+        new: PathBuf::new(),
+        ssim: 0.0,
+        mean_diff: 255.0,
+        max_diff: 255,
+        differing_pixels: (old.width * old.height).max(new.width * new.height) as usize,
+        diff: None,
+    };
+    if (old.width, old.height) != (new.width, new.height) {
+        return result;
+    }
+
+    let mut diff = Image::new(old.width, old.height);
+    let (mut total, mut max, mut differing) = (0u64, 0u8, 0usize);
+    for ((a, b), d) in old
+        .data
+        .chunks_exact(4)
+        .zip(new.data.chunks_exact(4))
+        .zip(diff.data.chunks_exact_mut(4))
+    {
+        let mut pixel_max = 0;
+        for c in 0..4 {
+            let delta = a[c].abs_diff(b[c]);
+            total += delta as u64;
+            pixel_max = pixel_max.max(delta);
+        }
+        max = max.max(pixel_max);
+        differing += (pixel_max > 0) as usize;
+        let v = pixel_max.saturating_mul(4);
+        d.copy_from_slice(&[v, v, v, 255]);
+    }
+
+    result.ssim = ssim(old, new);
+    result.mean_diff = total as f64 / old.data.len().max(1) as f64;
+    result.max_diff = max;
+    result.differing_pixels = differing;
+    result.diff = Some(diff);
+    result
+}
+
+/// Mean structural similarity of the luminance of two equally sized images over 8x8 windows
+///
+/// Images of different sizes have no structure in common, so their SSIM is 0.
+///
+/// ```
+/// use artimate::buffer::Image;
+/// use artimate::testing::ssim;
+///
+/// let image = Image::new(16, 16);
+/// assert_eq!(ssim(&image, &image), 1.0);
+/// assert_eq!(ssim(&image, &Image::new(16, 8)), 0.0);
+/// ```
+pub fn ssim(a: &Image, b: &Image) -> f64 {
+    const WINDOW: u32 = 8;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    let luma = |img: &Image, x: u32, y: u32| {
+        let [r, g, b, _] = img.pixel(x, y);
+        0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64
+    };
+    if (a.width, a.height) != (b.width, b.height) {
+        return 0.0;
+    }
+
+    let (mut sum, mut windows) = (0.0, 0);
+    for wy in (0..a.height).step_by(WINDOW as usize) {
+        for wx in (0..a.width).step_by(WINDOW as usize) {
+            let (w, h) = (WINDOW.min(a.width - wx), WINDOW.min(a.height - wy));
+            let n = (w * h) as f64;
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for y in wy..wy + h {
+                for x in wx..wx + w {
+                    let (la, lb) = (luma(a, x, y), luma(b, x, y));
+                    sa += la;
+                    sb += lb;
+                    saa += la * la;
+                    sbb += lb * lb;
+                    sab += la * lb;
+                }
+            }
+            let (ma, mb) = (sa / n, sb / n);
+            let (va, vb, cov) = (saa / n - ma * ma, sbb / n - mb * mb, sab / n - ma * mb);
+            sum += ((2.0 * ma * mb + C1) * (2.0 * cov + C2))
+                / ((ma * ma + mb * mb + C1) * (va + vb + C2));
+            windows += 1;
+        }
+    }
+    if windows == 0 {
+        1.0
+    } else {
+        sum / windows as f64
+    }
+}
+
+/// Returns the sorted names of the PNG files in `dir`
+fn png_names(dir: &Path) -> io::Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.to_ascii_lowercase().ends_with(".png"))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Escapes the characters of `text` that are special in HTML text and attributes
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn file_url(path: &Path) -> String {
+    let absolute = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("file://{}", absolute.display())
+}
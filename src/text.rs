@@ -0,0 +1,195 @@
+//! # Bitmap Text
+//!
+//! A tiny built-in 5x7 pixel font for drawing labels into RGBA frames, used by
+//! the in-window overlays and handy for debugging output in sketches.
+//!
+//! ```
+//! use artimate::text::{draw_text, text_width};
+//!
+//! let (width, height) = (200, 40);
+//! let mut frame = vec![0; (width * height * 4) as usize];
+//! draw_text(&mut frame, width, height, 4, 4, "frame 42", [255, 255, 255, 255], 2);
+//! assert_eq!(text_width("frame 42", 2), 94);
+//! ```
+
+/// Width of a glyph in font pixels
+pub const GLYPH_WIDTH: u32 = 5;
+/// Height of a glyph in font pixels
+pub const GLYPH_HEIGHT: u32 = 7;
+/// Horizontal distance between the starts of consecutive glyphs in font pixels
+const ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+/// Glyphs for ASCII 32 to 126, one byte per column, least significant bit at the top
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x14, 0x08, 0x3E, 0x08, 0x14], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // backslash
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Returns the width in pixels of `text` drawn at `scale`
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    (text.chars().count() as u32 * ADVANCE).saturating_sub(1) * scale
+}
+
+/// Returns the height in pixels of a line of text drawn at `scale`
+pub fn text_height(scale: u32) -> u32 {
+    GLYPH_HEIGHT * scale
+}
+
+/// Draws `text` into an RGBA frame with its top-left corner at `(x, y)`
+///
+/// Each font pixel is drawn as a `scale` x `scale` square, blended over the frame
+/// with the alpha of `color`. Characters outside printable ASCII are drawn as `?`
+/// and pixels outside the frame are clipped.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    text: &str,
+    color: [u8; 4],
+    scale: u32,
+) {
+    let alpha = color[3] as u32;
+    for (n, c) in text.chars().enumerate() {
+        let index = match c {
+            ' '..='~' => c as usize - ' ' as usize,
+            _ => '?' as usize - ' ' as usize,
+        };
+        let x0 = x + (n as u32 * ADVANCE * scale) as i32;
+        for (col, bits) in FONT[index].iter().enumerate() {
+            for row in 0..GLYPH_HEIGHT {
+                if bits & (1 << row) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let px = x0 + (col as u32 * scale + dx) as i32;
+                        let py = y + (row * scale + dy) as i32;
+                        if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height {
+                            continue;
+                        }
+                        let i = ((py as u32 * width + px as u32) * 4) as usize;
+                        for (dst, src) in frame[i..i + 3].iter_mut().zip(color) {
+                            *dst = ((src as u32 * alpha + *dst as u32 * (255 - alpha)) / 255) as u8;
+                        }
+                        frame[i + 3] = frame[i + 3].max(color[3]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Blends a filled rectangle of `color` into an RGBA frame, clipped to the frame
+pub(crate) fn fill_rect(
+    frame: &mut [u8],
+    width: u32,
+    height: u32,
+    (x0, y0, x1, y1): (u32, u32, u32, u32),
+    color: [u8; 4],
+) {
+    let alpha = color[3] as u32;
+    for y in y0.min(height)..y1.min(height) {
+        for x in x0.min(width)..x1.min(width) {
+            let i = ((y * width + x) * 4) as usize;
+            for (dst, src) in frame[i..i + 3].iter_mut().zip(color) {
+                *dst = ((src as u32 * alpha + *dst as u32 * (255 - alpha)) / 255) as u8;
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! # Uploading Exports
+//!
+//! Pushes finished exports to S3-compatible object storage, for render boxes
+//! without anyone at the keyboard. Uploads go through the `aws` command line tool,
+//! so credentials, profiles and regions are configured the usual way (environment
+//! variables or `~/.aws`), and any S3-compatible service can be targeted with a
+//! custom endpoint.
+//!
+//! Requires the `s3` feature and the `aws` command line tool
+//! (<https://aws.amazon.com/cli/>) on the `PATH`. The tool is not bundled or
+//! checked at build time; if it is missing, uploads fail with an error naming it.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, Config};
+//! use artimate::upload::S3Uploader;
+//!
+//! fn draw(app: &App, _model: &()) -> Vec<u8> {
+//!     app.new_frame()
+//! }
+//!
+//! let mut app = App::sketch(Config::with_dims(800, 800).set_frames_to_save(600), draw);
+//! let uploader = S3Uploader::new("my-renders")
+//!     .prefix("sketches/orbit")
+//!     .endpoint("https://s3.us-west-004.backblazeb2.com");
+//! app.on_export_complete(uploader.hook());
+//! app.run().unwrap();
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Uploads files to a bucket of an S3-compatible storage service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Uploader {
+    /// Name of the destination bucket
+    pub bucket: String,
+    /// Key prefix prepended to every uploaded file name
+    pub prefix: String,
+    /// Endpoint URL of an S3-compatible service, None for AWS
+    pub endpoint: Option<String>,
+}
+
+impl S3Uploader {
+    /// Creates an uploader writing to the root of `bucket` on AWS S3
+    pub fn new(bucket: &str) -> Self {
+        Self {
+            bucket: bucket.to_string(),
+            prefix: String::new(),
+            endpoint: None,
+        }
+    }
+
+    /// Sets the key prefix of uploaded files and returns updated uploader
+    pub fn prefix(self, prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Sets the endpoint URL of an S3-compatible service and returns updated uploader
+    pub fn endpoint(self, url: &str) -> Self {
+        Self {
+            endpoint: Some(url.to_string()),
+            ..self
+        }
+    }
+
+    /// Returns the `s3://` URL a file is uploaded to
+    ///
+    /// ```
+    /// use artimate::upload::S3Uploader;
+    /// use std::path::Path;
+    ///
+    /// let uploader = S3Uploader::new("renders").prefix("/orbit/");
+    /// assert_eq!(
+    ///     uploader.url(Path::new("out/frame_0001.png")),
+    ///     "s3://renders/orbit/frame_0001.png"
+    /// );
+    /// assert_eq!(
+    ///     uploader.url(Path::new("out/thumbnails/frame_0001.png")),
+    ///     "s3://renders/orbit/thumbnails/frame_0001.png"
+    /// );
+    /// ```
+    pub fn url(&self, path: &Path) -> String {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let folder = match path.parent().and_then(Path::file_name) {
+            Some(parent) if parent == "thumbnails" => "thumbnails/",
+            _ => "",
+        };
+        match self.prefix.as_str() {
+            "" => format!("s3://{}/{}{}", self.bucket, folder, name),
+            prefix => format!("s3://{}/{}/{}{}", self.bucket, prefix, folder, name),
+        }
+    }
+
+    /// Returns the `aws` command that uploads `path`
+    ///
+    /// ```
+    /// use artimate::upload::S3Uploader;
+    /// use std::path::Path;
+    ///
+    /// let uploader = S3Uploader::new("renders").endpoint("https://s3.example.com");
+    /// let command = uploader.command(Path::new("frame_0001.png"));
+    /// assert_eq!(command.get_program(), "aws");
+    /// let args: Vec<_> = command.get_args().collect();
+    /// assert_eq!(
+    ///     args,
+    ///     [
+    ///         "s3",
+    ///         "cp",
+    ///         "frame_0001.png",
+    ///         "s3://renders/frame_0001.png",
+    ///         "--endpoint-url",
+    ///         "https://s3.example.com",
+    ///         "--only-show-errors",
+    ///     ]
+    /// );
+    /// ```
+    pub fn command(&self, path: &Path) -> Command {
+        let mut command = Command::new("aws");
+        command.arg("s3").arg("cp").arg(path).arg(self.url(path));
+        if let Some(endpoint) = &self.endpoint {
+            command.arg("--endpoint-url").arg(endpoint);
+        }
+        command.arg("--only-show-errors");
+        command
+    }
+
+    /// Uploads every file in `paths`, stopping at the first failure
+    ///
+    /// # Returns
+    /// * `Ok(())` - If every file was uploaded
+    /// * `Err(io::Error)` - If a file does not exist, the `aws` tool is not on the
+    ///   `PATH`, or an upload fails; the error includes the tool's error output
+    ///
+    /// ```
+    /// use artimate::upload::S3Uploader;
+    /// use std::path::PathBuf;
+    ///
+    /// let missing = PathBuf::from("no_such_export.png");
+    /// let err = S3Uploader::new("renders").upload(&[missing]).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    /// ```
+    pub fn upload(&self, paths: &[PathBuf]) -> io::Result<()> {
+        for path in paths {
+            if !path.is_file() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("cannot upload {}: no such file", path.display()),
+                ));
+            }
+            let output = self
+                .command(path)
+                .output()
+                .map_err(|err| match err.kind() {
+                    io::ErrorKind::NotFound => io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "the `aws` command line tool needed by the s3 feature is not on the PATH",
+                    ),
+                    _ => err,
+                })?;
+            if !output.status.success() {
+                return Err(io::Error::other(format!(
+                    "uploading {} failed ({}): {}",
+                    path.display(),
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an export hook for `App::on_export_complete` that uploads the exported files
+    ///
+    /// Upload failures are reported on stderr and do not stop the application.
+    pub fn hook(self) -> impl Fn(&[PathBuf]) + 'static {
+        move |paths| {
+            if let Err(err) = self.upload(paths) {
+                eprintln!("Failed to upload exports: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! # Visualization
+//!
+//! Helpers for looking at simulation state, such as drawing a scalar field as a
+//! heat map instead of writing a bespoke grayscale mapping every time.
+//!
+//! ```
+//! use artimate::geom::Rect;
+//! use artimate::viz::{heatmap, Colormap, Field};
+//!
+//! let (width, height) = (200, 100);
+//! let mut frame = vec![0; (width * height * 4) as usize];
+//! let field = Field::from_fn(32, 16, |x, y| (x as f32 * 0.3).sin() + y as f32 * 0.1);
+//! heatmap(&mut frame, width, Rect::from_size(200.0, 100.0), &field, Colormap::Viridis);
+//! ```
+
+use crate::buffer::Image;
+pub use crate::colormap::Colormap;
+use crate::geom::Rect;
+
+/// A 2D grid of scalar values, stored row by row
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Field {
+    /// Number of columns
+    pub width: u32,
+    /// Number of rows
+    pub height: u32,
+    /// Values in row-major order, `width * height` long
+    pub values: Vec<f32>,
+}
+
+impl Field {
+    /// Creates a field of the given size filled with zeros
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            values: vec![0.0; (width * height) as usize],
+        }
+    }
+
+    /// Creates a field by evaluating `f(x, y)` at every cell
+    pub fn from_fn(width: u32, height: u32, f: impl Fn(u32, u32) -> f32) -> Self {
+        let values = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Returns the value at `(x, y)`
+    pub fn get(&self, x: u32, y: u32) -> f32 {
+        self.values[(y * self.width + x) as usize]
+    }
+
+    /// Sets the value at `(x, y)`
+    pub fn set(&mut self, x: u32, y: u32, value: f32) {
+        self.values[(y * self.width + x) as usize] = value;
+    }
+
+    /// Returns the smallest and largest finite values, or None if there are none
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold(None, |range, &v| match range {
+                None => Some((v, v)),
+                Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+            })
+    }
+
+    /// Renders the field as an image, one pixel per cell, normalized to its range
+    ///
+    /// Handy as the output of a debug view.
+    pub fn to_image(&self, colormap: Colormap) -> Image {
+        let mut image = Image::new(self.width, self.height);
+        heatmap(
+            &mut image.data,
+            self.width,
+            Rect::from_size(self.width as f32, self.height as f32),
+            self,
+            colormap,
+        );
+        image
+    }
+}
+
+/// Draws a scalar field into `rect` of an RGBA frame as a heat map
+///
+/// Values are normalized from the field's minimum to its maximum and colored with
+/// `colormap`; each pixel samples the nearest cell. Non-finite values are drawn
+/// with the color of the minimum. The rectangle is clipped to the frame.
+///
+/// # Arguments
+/// * `frame` - RGBA frame buffer, `width` pixels wide
+/// * `width` - Width of the frame in pixels
+/// * `rect` - Area of the frame the field is stretched over
+/// * `field` - The scalar field to draw
+/// * `colormap` - Colormap applied to the normalized values
+pub fn heatmap(frame: &mut [u8], width: u32, rect: Rect, field: &Field, colormap: Colormap) {
+    if width == 0 || field.width == 0 || field.height == 0 {
+        return;
+    }
+    let height = (frame.len() / 4) as u32 / width;
+    let (lo, hi) = field.range().unwrap_or((0.0, 1.0));
+    let span = if hi > lo { hi - lo } else { 1.0 };
+    let lut = colormap.lut();
+    let x0 = rect.x.max(0.0).round() as u32;
+    let y0 = rect.y.max(0.0).round() as u32;
+    let x1 = (rect.right().round().max(0.0) as u32).min(width);
+    let y1 = (rect.bottom().round().max(0.0) as u32).min(height);
+    for y in y0..y1 {
+        let (_, v) = rect.normalize(0.0, y as f32 + 0.5);
+        let cy = ((v * field.height as f32) as u32).min(field.height - 1);
+        for x in x0..x1 {
+            let (u, _) = rect.normalize(x as f32 + 0.5, 0.0);
+            let cx = ((u * field.width as f32) as u32).min(field.width - 1);
+            let value = field.get(cx, cy);
+            let t = if value.is_finite() {
+                (value - lo) / span
+            } else {
+                0.0
+            };
+            let i = ((y * width + x) * 4) as usize;
+            frame[i..i + 4].copy_from_slice(&lut[(t * 255.0).round() as usize]);
+        }
+    }
+}
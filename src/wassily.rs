@@ -0,0 +1,88 @@
+//! # Wassily Sketches
+//!
+//! First-class support for drawing with [wassily](https://crates.io/crates/wassily):
+//! the app owns a `Canvas` matching the configured size, clears it each frame,
+//! hands it to the draw function and converts it for presentation, reusing the
+//! same allocation every frame.
+//!
+//! Requires the `wassily` feature.
+//!
+//! ```rust,no_run
+//! use artimate::app::{App, Config, Error};
+//! use artimate::wassily::WassilyApp;
+//! use wassily::prelude::*;
+//!
+//! fn main() -> Result<(), Error> {
+//!     let config = Config::with_dims(800, 600).clear_color([0, 0, 0, 255]);
+//!     App::wassily_sketch(config, draw).run()
+//! }
+//!
+//! fn draw(canvas: &mut Canvas, app: &WassilyApp) {
+//!     let x = 100.0 * app.time % app.config.width as f32;
+//!     Shape::new()
+//!         .circle(pt(x, 300.0), 75.0)
+//!         .fill_color(*CORNFLOWERBLUE)
+//!         .draw(canvas);
+//! }
+//! ```
+
+use crate::app::{App, AppMode, Config};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wassily::core::Canvas;
+use wassily::prelude::Color;
+
+/// An app drawing onto a wassily canvas
+pub type WassilyApp = App<AppMode, WassilySketch>;
+
+/// Function called each frame to draw onto the app's canvas
+pub type CanvasDrawFn = fn(&mut Canvas, &WassilyApp);
+
+/// Model of a wassily sketch: the reused canvas and the user's draw function
+#[derive(Clone)]
+pub struct WassilySketch {
+    canvas: Rc<RefCell<Canvas>>,
+    draw: CanvasDrawFn,
+}
+
+impl App<AppMode, WassilySketch> {
+    /// Creates a sketch that draws onto a wassily `Canvas` owned by the app
+    ///
+    /// Each frame the canvas is filled with `config.clear_color` (or keeps the
+    /// previous frame when `config.auto_clear` is disabled), passed to `draw`, and
+    /// converted from premultiplied alpha for presentation. The canvas is only
+    /// reallocated when the dimensions change.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration settings for the window and rendering
+    /// * `draw` - Function called each frame with the canvas and the app
+    pub fn wassily_sketch(config: Config, draw: CanvasDrawFn) -> Self {
+        let mut canvas = Canvas::new(config.width.max(1), config.height.max(1));
+        canvas.fill(clear_color(&config));
+        let model = WassilySketch {
+            canvas: Rc::new(RefCell::new(canvas)),
+            draw,
+        };
+        App::app(model, config, |_, model| model, draw_canvas)
+    }
+}
+
+/// Returns the configured clear color as a wassily color
+fn clear_color(config: &Config) -> Color {
+    let [r, g, b, a] = config.clear_color;
+    Color::from_rgba8(r, g, b, a)
+}
+
+/// Clears or reallocates the canvas, runs the sketch's draw function and converts the result
+fn draw_canvas(app: &WassilyApp, model: &WassilySketch) -> Vec<u8> {
+    let mut canvas = model.canvas.borrow_mut();
+    let (width, height) = app.wh();
+    if canvas.width() != width || canvas.height() != height {
+        *canvas = Canvas::new(width.max(1), height.max(1));
+        canvas.fill(clear_color(&app.config));
+    } else if app.config.auto_clear {
+        canvas.fill(clear_color(&app.config));
+    }
+    (model.draw)(&mut canvas, app);
+    app.present_pixmap(&canvas.pixmap)
+}
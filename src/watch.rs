@@ -0,0 +1,218 @@
+//! # Watching Files
+//!
+//! Polls parameter and asset files for changes, for a fast edit and look loop.
+//! A running sketch can react to a change in place with `App::on_file_change`,
+//! for example to reload parameters without losing its state, while
+//! [`supervise`] restarts the whole sketch process, which is what the
+//! `artimate-watch` binary does:
+//!
+//! ```bash
+//! cargo install artimate --features watch
+//! artimate-watch --watch params.txt --watch assets -- cargo run --release
+//! ```
+//!
+//! Watching polls modification times, so it works the same on every platform
+//! and needs no system services. Requires the `watch` feature.
+//!
+//! ```no_run
+//! use artimate::watch::Watcher;
+//! use std::time::Duration;
+//!
+//! let mut watcher = Watcher::new(["params.txt", "assets"]).interval(Duration::from_millis(100));
+//! loop {
+//!     for path in watcher.wait() {
+//!         println!("{} changed", path.display());
+//!     }
+//! }
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Default time between polls
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Summary of a file or directory tree that changes whenever any file in it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Fingerprint {
+    /// Latest modification time of any file
+    modified: Option<SystemTime>,
+    /// Number of files
+    files: u64,
+    /// Total size of the files in bytes
+    bytes: u64,
+}
+
+impl Fingerprint {
+    /// Returns the fingerprint of `path`, walking directories recursively
+    ///
+    /// Missing paths have an empty fingerprint, so creating one counts as a change.
+    fn of(path: &Path) -> Self {
+        let mut fingerprint = Self::default();
+        fingerprint.add(path);
+        fingerprint
+    }
+
+    fn add(&mut self, path: &Path) {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return;
+        };
+        if metadata.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(path) {
+                for entry in entries.flatten() {
+                    self.add(&entry.path());
+                }
+            }
+        } else {
+            self.files += 1;
+            self.bytes += metadata.len();
+            self.modified = self.modified.max(metadata.modified().ok());
+        }
+    }
+}
+
+/// Watches files and directories for changes by polling their modification times
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    /// Watched paths and their fingerprints at the last poll
+    watched: Vec<(PathBuf, Fingerprint)>,
+    /// Minimum time between polls
+    interval: Duration,
+    /// When the paths were last polled
+    last_poll: Instant,
+}
+
+impl Watcher {
+    /// Creates a watcher for `paths`, taking their current contents as unchanged
+    ///
+    /// Directories are watched recursively. Paths that do not exist yet are
+    /// reported once they are created.
+    pub fn new<P: Into<PathBuf>>(paths: impl IntoIterator<Item = P>) -> Self {
+        let watched = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.into();
+                let fingerprint = Fingerprint::of(&path);
+                (path, fingerprint)
+            })
+            .collect();
+        Self {
+            watched,
+            interval: DEFAULT_INTERVAL,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Sets the minimum time between polls and returns updated watcher
+    ///
+    /// Defaults to 250 ms. Shorter intervals react faster but walk the watched
+    /// directories more often.
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Adds a path to watch, taking its current contents as unchanged
+    pub fn add(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let fingerprint = Fingerprint::of(&path);
+        self.watched.push((path, fingerprint));
+    }
+
+    /// Returns the watched paths
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.watched.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Returns the watched paths that changed since the last poll
+    ///
+    /// Returns nothing without looking at the files if the interval has not
+    /// elapsed since the last poll, so this is cheap to call every frame.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < self.interval {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+        let mut changed = Vec::new();
+        for (path, fingerprint) in &mut self.watched {
+            let current = Fingerprint::of(path);
+            if current != *fingerprint {
+                *fingerprint = current;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+
+    /// Blocks until a watched path changes and returns the changed paths
+    ///
+    /// Keeps polling until the files have been quiet for one interval, so an
+    /// editor writing several files at once is reported as a single change.
+    pub fn wait(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        loop {
+            thread::sleep(self.interval.saturating_sub(self.last_poll.elapsed()));
+            let batch = self.poll();
+            if batch.is_empty() && !changed.is_empty() {
+                return changed;
+            }
+            for path in batch {
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+    }
+}
+
+/// Runs `command` and restarts it whenever a path watched by `watcher` changes
+///
+/// If the command exits on its own, for example after a compile error or when
+/// the sketch window is closed, it is started again after the next change. Only
+/// returns if the command cannot be started.
+pub fn supervise(command: &mut Command, watcher: &mut Watcher) -> io::Result<()> {
+    let mut child = Some(start(command)?);
+    loop {
+        let changed = match child.as_mut().map(Child::try_wait) {
+            Some(Ok(None)) => {
+                thread::sleep(watcher.interval);
+                watcher.poll()
+            }
+            Some(Ok(Some(status))) => {
+                println!("Sketch exited ({}), waiting for changes", status);
+                child = None;
+                watcher.wait()
+            }
+            Some(Err(err)) => return Err(err),
+            None => watcher.wait(),
+        };
+        if changed.is_empty() {
+            continue;
+        }
+        for path in &changed {
+            println!("Changed: {}", path.display());
+        }
+        if let Some(mut running) = child.take() {
+            // The sketch may have exited in the meantime, in which case kill fails harmlessly
+            let _ = running.kill();
+            running.wait()?;
+        }
+        child = Some(start(command)?);
+    }
+}
+
+/// Starts `command`, printing it first
+fn start(command: &mut Command) -> io::Result<Child> {
+    let args: Vec<_> = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .collect();
+    println!(
+        "Running: {} {}",
+        command.get_program().to_string_lossy(),
+        args.join(" ")
+    );
+    command.spawn()
+}